@@ -3,6 +3,7 @@ mod middleware;
 mod nango;
 mod native;
 mod openapi;
+mod pyannote;
 mod slack;
 mod state;
 #[path = "stripe.rs"]
@@ -111,6 +112,10 @@ fn main() {
             let realtime_stt = hypr_stt::realtime::Client::builder()
                 .deepgram_api_key(get_env("DEEPGRAM_API_KEY"))
                 .clova_api_key(get_env("CLOVA_API_KEY"))
+                .whisper_api_base(get_env("WHISPER_API_BASE"))
+                .whisper_api_key(get_env("WHISPER_API_KEY"))
+                .rtzr_client_id(get_env("RTZR_CLIENT_ID"))
+                .rtzr_client_secret(get_env("RTZR_CLIENT_SECRET"))
                 .build();
 
             let recorded_stt = hypr_stt::recorded::Client::builder()
@@ -160,10 +165,15 @@ fn main() {
 
             let stripe_client = stripe::Client::new(get_env("STRIPE_SECRET_KEY"));
 
+            let pyannote = hypr_pyannote::cloud::PyannoteClient::builder()
+                .api_key(get_env("PYANNOTE_API_KEY"))
+                .build();
+
             let state = state::AppState {
                 clerk: clerk.clone(),
                 realtime_stt,
                 recorded_stt,
+                listen_sessions: hypr_ws_utils::ListenSessions::default(),
                 turso,
                 admin_db,
                 nango,
@@ -171,6 +181,10 @@ fn main() {
                 s3,
                 openai,
                 stripe: stripe_client,
+                pyannote,
+                diarization_jobs: native::diarize::DiarizationJobs::default(),
+                pyannote_webhook_url: get_env("PYANNOTE_WEBHOOK_URL"),
+                pyannote_webhook_secret: get_env("PYANNOTE_WEBHOOK_SECRET"),
             };
 
             let web_connect_router =
@@ -208,7 +222,18 @@ fn main() {
                     api_get(native::user::list_integrations),
                 )
                 .api_route("/subscription", api_get(native::subscription::handler))
-                .route("/listen/realtime", get(native::listen::realtime::handler));
+                .route("/listen/realtime", get(native::listen::realtime::handler))
+                .merge(
+                    // Jobs belong to the user who submitted them.
+                    ApiRouter::new()
+                        .route("/diarize/upload", post(native::diarize::upload))
+                        .route("/diarize", post(native::diarize::submit))
+                        .route("/diarize/{job_id}", get(native::diarize::result))
+                        .layer(axum::middleware::from_fn_with_state(
+                            AuthState::from_ref(&state),
+                            middleware::verify_api_key,
+                        )),
+                );
             // .layer(
             //     tower::builder::ServiceBuilder::new()
             //         .layer(axum::middleware::from_fn_with_state(
@@ -230,6 +255,7 @@ fn main() {
             let webhook_router = ApiRouter::new()
                 .route("/nango", post(nango::handler))
                 .route("/stripe", post(stripe_webhook::handler))
+                .route("/pyannote", post(pyannote::handler))
                 .nest("/slack", slack_router);
 
             let mut router = ApiRouter::new()
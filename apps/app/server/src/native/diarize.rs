@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use hypr_pyannote::cloud::{create_media_upload, submit_diarization_job, DiarizationSegment};
+
+use crate::state::DiarizationState;
+
+// Jobs, and outcomes nobody fetched, are forgotten after this long.
+const JOB_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+type JobResult = Result<Vec<DiarizationSegment>, String>;
+
+struct Job {
+    // `None` until `register`, since the webhook can arrive before `submit` returns.
+    owner: Option<String>,
+    result: Option<JobResult>,
+    created_at: Instant,
+}
+
+impl Job {
+    fn new() -> Self {
+        Self {
+            owner: None,
+            result: None,
+            created_at: Instant::now(),
+        }
+    }
+}
+
+// Outcomes of jobs delivered by the pyannote webhook, kept for the user who submitted them.
+#[derive(Clone)]
+pub struct DiarizationJobs {
+    jobs: Arc<Mutex<HashMap<String, Job>>>,
+    ttl: Duration,
+}
+
+impl Default for DiarizationJobs {
+    fn default() -> Self {
+        Self::with_ttl(JOB_TTL)
+    }
+}
+
+impl DiarizationJobs {
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    pub fn register(&self, job_id: impl Into<String>, owner: impl Into<String>) {
+        let mut jobs = self.lock();
+        jobs.entry(job_id.into()).or_insert_with(Job::new).owner = Some(owner.into());
+    }
+
+    pub fn complete(&self, job_id: impl Into<String>, result: JobResult) {
+        let mut jobs = self.lock();
+        jobs.entry(job_id.into()).or_insert_with(Job::new).result = Some(result);
+    }
+
+    // `None` for jobs that are unknown or owned by someone else, `Some(None)` while running.
+    pub fn get(&self, job_id: &str, owner: &str) -> Option<Option<JobResult>> {
+        let jobs = self.lock();
+        jobs.get(job_id)
+            .filter(|job| job.owner.as_deref() == Some(owner))
+            .map(|job| job.result.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Job>> {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|_, job| job.created_at.elapsed() < self.ttl);
+        jobs
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UploadResponse {
+    // What to submit, once the audio was `PUT` to `upload_url`.
+    audio_url: String,
+    upload_url: String,
+}
+
+// Recordings only live on the desktop, so they go to pyannote's temporary storage first.
+pub async fn upload(
+    Extension(user): Extension<hypr_db_admin::User>,
+    State(state): State<DiarizationState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let audio_url =
+        create_media_upload::media_url(&format!("diarize/{}/{}", user.id, uuid::Uuid::new_v4()));
+
+    let res = state
+        .pyannote
+        .create_media_upload(create_media_upload::Request {
+            url: audio_url.clone(),
+        })
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    match res {
+        create_media_upload::Response::Ok { url } => Ok(Json(UploadResponse {
+            audio_url,
+            upload_url: url,
+        })),
+        create_media_upload::Response::Error { message } => Err((StatusCode::BAD_GATEWAY, message)),
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SubmitRequest {
+    audio_url: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SubmitResponse {
+    job_id: String,
+}
+
+pub async fn submit(
+    Extension(user): Extension<hypr_db_admin::User>,
+    State(state): State<DiarizationState>,
+    Json(input): Json<SubmitRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let res = state
+        .pyannote
+        .submit_diarization_job(submit_diarization_job::Request {
+            url: input.audio_url,
+            webhook: Some(state.webhook_url.clone()),
+            num_speakers: None,
+            confidence: None,
+        })
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    match res {
+        submit_diarization_job::Response::Ok { job_id, .. } => {
+            state.jobs.register(&job_id, user.id);
+            Ok(Json(SubmitResponse { job_id }))
+        }
+        submit_diarization_job::Response::Error { message } => {
+            Err((StatusCode::BAD_REQUEST, message))
+        }
+    }
+}
+
+// `202 Accepted` until the webhook delivered the job's outcome, which can then be fetched until it expires.
+pub async fn result(
+    Extension(user): Extension<hypr_db_admin::User>,
+    State(state): State<DiarizationState>,
+    Path(job_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    match state.jobs.get(&job_id, &user.id) {
+        Some(Some(Ok(segments))) => Ok((StatusCode::OK, Json(segments))),
+        Some(Some(Err(e))) => Err((StatusCode::UNPROCESSABLE_ENTITY, e)),
+        Some(None) => Ok((StatusCode::ACCEPTED, Json(vec![]))),
+        None => Err((StatusCode::NOT_FOUND, "job_not_found".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        future::IntoFuture,
+        net::{Ipv4Addr, SocketAddr},
+    };
+
+    use hypr_pyannote::cloud::{webhook, PyannoteClient};
+    use hypr_stt_mock::{DiarizationScript, MockServer};
+
+    const SECRET: &str = "whsec_test";
+    const USER_HEADER: &str = "x-user-id";
+    const USER: &str = "user-0";
+
+    async fn serve() -> (SocketAddr, MockServer) {
+        let mock = hypr_stt_mock::serve_pyannote(DiarizationScript::english(), 0).await;
+
+        let state = DiarizationState {
+            pyannote: PyannoteClient::builder()
+                .api_key("mock")
+                .api_base(mock.api_base())
+                .build(),
+            jobs: DiarizationJobs::default(),
+            webhook_url: "http://localhost/webhook/pyannote".to_string(),
+            webhook_secret: SECRET.to_string(),
+        };
+
+        let router = axum::Router::new()
+            .route("/api/desktop/diarize/upload", axum::routing::post(upload))
+            .route("/api/desktop/diarize", axum::routing::post(submit))
+            .route("/api/desktop/diarize/{job_id}", axum::routing::get(result))
+            .layer(axum::middleware::from_fn(as_user))
+            .route(
+                "/webhook/pyannote",
+                axum::routing::post(crate::pyannote::handler),
+            )
+            .with_state(state);
+
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        (addr, mock)
+    }
+
+    // Stands in for `verify_api_key`, with the user id taken from a header.
+    async fn as_user(
+        mut req: axum::extract::Request,
+        next: axum::middleware::Next,
+    ) -> axum::response::Response {
+        let id = req
+            .headers()
+            .get(USER_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or(USER)
+            .to_string();
+
+        req.extensions_mut().insert(hypr_db_admin::User {
+            id,
+            account_id: "account".to_string(),
+            human_id: "human".to_string(),
+            timestamp: chrono::Utc::now(),
+            clerk_user_id: "clerk".to_string(),
+        });
+        next.run(req).await
+    }
+
+    fn webhook_request(
+        client: &reqwest::Client,
+        addr: SocketAddr,
+        body: &'static str,
+        secret: &str,
+    ) -> reqwest::RequestBuilder {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = webhook::sign(secret, &timestamp, body.as_bytes());
+
+        client
+            .post(format!("http://{}/webhook/pyannote", addr))
+            .header(webhook::TIMESTAMP_HEADER, timestamp)
+            .header(webhook::SIGNATURE_HEADER, signature)
+            .header("content-type", "application/json")
+            .body(body)
+    }
+
+    #[tokio::test]
+    async fn test_diarize_webhook() {
+        let (addr, _mock) = serve().await;
+        let client = reqwest::Client::new();
+
+        let res = client
+            .post(format!("http://{}/api/desktop/diarize/upload", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        let UploadResponse {
+            audio_url,
+            upload_url,
+        } = res.json().await.unwrap();
+        assert!(audio_url.starts_with("media://diarize/"));
+
+        let res = client
+            .put(upload_url)
+            .body(vec![0u8; 16])
+            .send()
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+
+        let res = client
+            .post(format!("http://{}/api/desktop/diarize", addr))
+            .json(&serde_json::json!({ "audio_url": audio_url }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        let SubmitResponse { job_id } = res.json().await.unwrap();
+        assert_eq!(job_id, "job-0");
+
+        let result_url = format!("http://{}/api/desktop/diarize/{}", addr, job_id);
+        let res = client.get(&result_url).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::ACCEPTED);
+
+        let body = r#"{"jobId":"job-0","status":"succeeded","output":{"diarization":[{"speaker":"SPEAKER_00","start":0.5,"end":1.5}]}}"#;
+
+        // Not signed with our secret.
+        let res = webhook_request(&client, addr, body, "whsec_other")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let res = webhook_request(&client, addr, body, SECRET)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+        // Fetching doesn't consume the outcome.
+        for _ in 0..2 {
+            let res = client.get(&result_url).send().await.unwrap();
+            assert_eq!(res.status(), reqwest::StatusCode::OK);
+            let segments: Vec<DiarizationSegment> = res.json().await.unwrap();
+            assert_eq!(
+                segments,
+                vec![DiarizationSegment {
+                    speaker: "SPEAKER_00".to_string(),
+                    start: 0.5,
+                    end: 1.5,
+                }]
+            );
+        }
+
+        let res = client
+            .get(&result_url)
+            .header(USER_HEADER, "user-1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_diarize_webhook_failed_job() {
+        let (addr, _mock) = serve().await;
+        let client = reqwest::Client::new();
+
+        // Delivered before `submit` returned the job to its owner.
+        let body = r#"{"jobId":"job-0","status":"failed"}"#;
+        let res = webhook_request(&client, addr, body, SECRET)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+        let result_url = format!("http://{}/api/desktop/diarize/job-0", addr);
+        let res = client.get(&result_url).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let res = client
+            .post(format!("http://{}/api/desktop/diarize", addr))
+            .json(&serde_json::json!({ "audio_url": "https://example.com/audio.wav" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+        let res = client.get(&result_url).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn test_jobs_expire() {
+        let jobs = DiarizationJobs::default();
+        jobs.register("job-0", USER);
+        assert!(matches!(jobs.get("job-0", USER), Some(None)));
+        jobs.complete("job-0", Ok(vec![]));
+        assert!(matches!(jobs.get("job-0", USER), Some(Some(Ok(_)))));
+        assert!(jobs.get("job-0", "user-1").is_none());
+
+        let jobs = DiarizationJobs::with_ttl(Duration::ZERO);
+        jobs.register("job-0", USER);
+        jobs.complete("job-0", Ok(vec![]));
+        assert!(jobs.get("job-0", USER).is_none());
+    }
+}
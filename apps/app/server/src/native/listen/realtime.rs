@@ -2,17 +2,20 @@
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, WebSocket, WebSocketUpgrade},
         Query, State,
     },
+    http::StatusCode,
     response::IntoResponse,
 };
 
 use bytes::Bytes;
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{stream::BoxStream, StreamExt};
 
-use hypr_listener_interface::{ListenInputChunk, ListenOutputChunk, ListenParams};
-use hypr_stt::realtime::RealtimeSpeechToText;
+use hypr_audio_utils::AudioFormatExt;
+use hypr_listener_interface::{ListenOutputChunk, ListenParams};
+use hypr_stt::realtime::{MultiClient, RealtimeSpeechToText};
+use hypr_ws_utils::{ListenSession, ListenSink, WebSocketAudioSource};
 
 use crate::state::STTState;
 
@@ -20,95 +23,519 @@ pub async fn handler(
     Query(params): Query<ListenParams>,
     ws: WebSocketUpgrade,
     State(state): State<STTState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| websocket(socket, state, params))
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    hypr_stt::Backend::for_realtime(&params.language)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    Ok(ws.on_upgrade(|socket| websocket(socket, state, params)))
 }
 
 async fn websocket(socket: WebSocket, state: STTState, params: ListenParams) {
     tracing::info!("websocket_connected");
 
-    let (mut ws_sender, ws_receiver) = socket.split();
+    // `None` when the client resumed a session that is already being transcribed.
+    let Some(session) = state.listen_sessions.accept(socket).await else {
+        return;
+    };
+    let ListenSession {
+        sources,
+        sink,
+        params: start_params,
+    } = session;
+    let params = start_params.unwrap_or(params);
 
-    let mut stt = state.realtime_stt.for_language(params.language).await;
+    let streams = match stt_streams(&state, &params, sources).await {
+        Ok(streams) => streams,
+        Err(e) => {
+            tracing::error!("stt_client_error: {:?}", e);
 
-    let input_stream =
-        futures_util::stream::try_unfold(ws_receiver, |mut ws_receiver| async move {
-            match ws_receiver.next().await {
-                Some(Ok(Message::Text(data))) => {
-                    let input: ListenInputChunk = serde_json::from_str(&data).unwrap();
+            sink.close(Some(CloseFrame {
+                code: close_code::ERROR,
+                reason: e.to_string().into(),
+            }))
+            .await;
+            return;
+        }
+    };
 
-                    match input {
-                        ListenInputChunk::Audio { data } => {
-                            let audio = Bytes::from(data);
-                            Ok::<Option<(Bytes, _)>, axum::Error>(Some((audio, ws_receiver)))
-                        }
-                        ListenInputChunk::End => Ok::<Option<(Bytes, _)>, axum::Error>(None),
-                    }
-                }
-                _ => Ok::<Option<(Bytes, _)>, axum::Error>(None),
-            }
-        });
+    let _handle = tokio::spawn(async move {
+        futures_util::future::join_all(
+            streams
+                .into_iter()
+                .map(|(stt, input, channel)| transcribe(stt, input, channel, &sink)),
+        )
+        .await;
 
-    let input_stream = Box::pin(input_stream);
+        sink.close(None).await;
 
-    let _handle = tokio::spawn(async move {
-        match stt.transcribe(input_stream).await {
-            Err(e) => tracing::error!("transcription error: {:?}", e),
-            Ok(mut transcript_stream) => {
-                while let Some(result) = transcript_stream.next().await {
-                    match result {
-                        Ok(data) => {
-                            let out: ListenOutputChunk = data.into();
-                            let msg = Message::Text(serde_json::to_string(&out).unwrap().into());
-
-                            if let Err(e) = ws_sender.send(msg).await {
-                                tracing::error!("websocket send error: {:?}", e);
-                                break;
-                            }
-                        }
-                        Err(e) => {
-                            tracing::error!("transcription error: {:?}", e);
-                            break;
+        tracing::info!("websocket_disconnected");
+    });
+}
+
+type InputStream = BoxStream<'static, Bytes>;
+
+// Multichannel audio goes to Deepgram as is, and to other backends as one stream per channel.
+async fn stt_streams(
+    state: &STTState,
+    params: &ListenParams,
+    sources: Vec<WebSocketAudioSource>,
+) -> Result<Vec<(MultiClient, InputStream, Option<u8>)>, hypr_stt::Error> {
+    let stt_client = || {
+        state
+            .realtime_stt
+            .for_language(params.language.clone(), params.vocabulary.clone())
+    };
+
+    let mut stt = stt_client().await?;
+    let mut inputs: Vec<InputStream> = sources
+        .into_iter()
+        .map(|source| source.to_i16_le_chunks(16 * 1000, 1024).boxed())
+        .collect();
+
+    if inputs.len() == 1 {
+        return Ok(vec![(stt, inputs.remove(0), None)]);
+    }
+
+    if stt.supports_multichannel() {
+        stt.set_channels(inputs.len() as u16);
+        let input = hypr_audio_utils::interleave_i16_le(inputs).boxed();
+        return Ok(vec![(stt, input, None)]);
+    }
+
+    let mut streams = vec![(stt, inputs.remove(0), Some(0))];
+    for (i, input) in inputs.into_iter().enumerate() {
+        streams.push((stt_client().await?, input, Some(i as u8 + 1)));
+    }
+    Ok(streams)
+}
+
+// `channel` tags the output of backends that only ever see a single channel.
+async fn transcribe(
+    mut stt: MultiClient,
+    input: InputStream,
+    channel: Option<u8>,
+    sink: &ListenSink,
+) {
+    match stt.transcribe(input.map(Ok::<Bytes, std::io::Error>)).await {
+        Err(e) => tracing::error!("transcription error: {:?}", e),
+        Ok(mut transcript_stream) => {
+            while let Some(result) = transcript_stream.next().await {
+                match result {
+                    Ok(data) => {
+                        let mut out: ListenOutputChunk = data.into();
+                        if channel.is_some() {
+                            out.channel = channel;
                         }
+                        sink.send(&out).await;
+                    }
+                    Err(e) => {
+                        tracing::error!("transcription error: {:?}", e);
+                        break;
                     }
                 }
             }
         }
-
-        tracing::info!("websocket_disconnected");
-    });
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures_util::{stream::SplitStream, SinkExt};
+    use hypr_listener_interface::{
+        AudioEncoding, AudioFormat, ListenControlMessage, ListenInputChunk, ListenResume,
+        ListenServerMessage, LISTEN_PROTOCOL_VERSION,
+    };
+    use hypr_stt_mock::MockServer;
     use std::{
         future::IntoFuture,
         net::{Ipv4Addr, SocketAddr},
     };
+    use tokio_tungstenite::tungstenite::Message as ClientMessage;
 
-    fn app() -> axum::Router {
+    fn app(realtime_stt: hypr_stt::realtime::Client) -> axum::Router {
         axum::Router::new()
             .route("/api/desktop/transcribe", axum::routing::get(handler))
             .with_state(STTState {
-                realtime_stt: hypr_stt::realtime::Client::builder()
-                    .deepgram_api_key("".to_string())
-                    .clova_api_key("".to_string())
-                    .build(),
+                realtime_stt,
                 recorded_stt: hypr_stt::recorded::Client::builder()
                     .deepgram_api_key("".to_string())
                     .clova_api_key("".to_string())
                     .build(),
+                listen_sessions: hypr_ws_utils::ListenSessions::default(),
             })
     }
 
+    async fn serve(realtime_stt: hypr_stt::realtime::Client) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(axum::serve(listener, app(realtime_stt)).into_future());
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_language() {
+        let addr = serve(
+            hypr_stt::realtime::Client::builder()
+                .deepgram_api_key("".to_string())
+                .clova_api_key("".to_string())
+                .build(),
+        )
+        .await;
+
+        let res = reqwest::Client::new()
+            .get(format!(
+                "http://{}/api/desktop/transcribe?language=ab&static_prompt=&dynamic_prompt=",
+                addr
+            ))
+            .header("connection", "upgrade")
+            .header("upgrade", "websocket")
+            .header("sec-websocket-version", "13")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), reqwest::StatusCode::BAD_REQUEST);
+        assert!(res.text().await.unwrap().contains("ab"));
+    }
+
+    type ClientSocket = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn connect(addr: SocketAddr) -> ClientSocket {
+        let (socket, _) = tokio_tungstenite::connect_async(format!(
+            "ws://{}/api/desktop/transcribe?language=en&static_prompt=&dynamic_prompt=",
+            addr
+        ))
+        .await
+        .unwrap();
+
+        socket
+    }
+
+    async fn serve_with_deepgram(script: &hypr_stt_mock::Script) -> (SocketAddr, MockServer) {
+        let deepgram = hypr_stt_mock::serve_deepgram(script.clone()).await;
+
+        let addr = serve(
+            hypr_stt::realtime::Client::builder()
+                .deepgram_api_base(deepgram.api_base())
+                .deepgram_api_key("mock")
+                .build(),
+        )
+        .await;
+
+        (addr, deepgram)
+    }
+
+    async fn collect_words(mut receiver: SplitStream<ClientSocket>) -> Vec<String> {
+        let mut words = vec![];
+        while let Some(Ok(msg)) = receiver.next().await {
+            if let ClientMessage::Text(text) = msg {
+                if serde_json::from_str::<ListenServerMessage>(&text).is_ok() {
+                    continue;
+                }
+
+                let chunk: ListenOutputChunk = serde_json::from_str(&text).unwrap();
+                words.extend(chunk.words.into_iter().map(|w| w.text));
+            }
+        }
+        words
+    }
+
+    fn start_message(resume: Option<ListenResume>) -> ClientMessage {
+        let start = ListenControlMessage::Start {
+            version: LISTEN_PROTOCOL_VERSION,
+            params: ListenParams {
+                language: hypr_language::ISO639::En.into(),
+                ..Default::default()
+            },
+            format: AudioFormat::default(),
+            resume,
+        };
+
+        ClientMessage::Text(serde_json::to_string(&start).unwrap().into())
+    }
+
+    async fn next_session(socket: &mut ClientSocket) -> (String, u64) {
+        while let Some(Ok(msg)) = socket.next().await {
+            if let ClientMessage::Text(text) = msg {
+                if let Ok(ListenServerMessage::Session {
+                    session_id,
+                    next_seq,
+                }) = serde_json::from_str(&text)
+                {
+                    return (session_id, next_seq);
+                }
+            }
+        }
+
+        panic!("no session message");
+    }
+
     // https://github.com/tokio-rs/axum/blob/4f11b45/examples/testing-websockets/src/main.rs#L104
     #[tokio::test]
     async fn integration_test() {
-        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+        let script = hypr_stt_mock::Script::english();
+        let (addr, _deepgram) = serve_with_deepgram(&script).await;
+        let (mut sender, receiver) = connect(addr).await.split();
+
+        tokio::spawn(async move {
+            for chunk in hypr_data::english_2::AUDIO.chunks(3200) {
+                let input = ListenInputChunk::Audio {
+                    data: chunk.to_vec(),
+                };
+                let msg = ClientMessage::Text(serde_json::to_string(&input).unwrap().into());
+                sender.send(msg).await.unwrap();
+            }
+
+            let end = serde_json::to_string(&ListenInputChunk::End).unwrap();
+            sender.send(ClientMessage::Text(end.into())).await.unwrap();
+        });
+
+        let expected: Vec<_> = script.words.iter().map(|w| w.text.trim()).collect();
+        assert_eq!(collect_words(receiver).await, expected);
+    }
+
+    #[tokio::test]
+    async fn integration_test_v2() {
+        let script = hypr_stt_mock::Script::english();
+        let (addr, _deepgram) = serve_with_deepgram(&script).await;
+        let (mut sender, receiver) = connect(addr).await.split();
+
+        tokio::spawn(async move {
+            let start = ListenControlMessage::Start {
+                version: LISTEN_PROTOCOL_VERSION,
+                params: ListenParams {
+                    language: hypr_language::ISO639::En.into(),
+                    ..Default::default()
+                },
+                format: AudioFormat {
+                    sample_rate: 16 * 1000,
+                    encoding: AudioEncoding::Linear16,
+                    channels: 2,
+                },
+                resume: None,
+            };
+            let start = serde_json::to_string(&start).unwrap();
+            sender
+                .send(ClientMessage::Text(start.into()))
+                .await
+                .unwrap();
+
+            for chunk in hypr_data::english_2::AUDIO.chunks(3200) {
+                // Same sample on both channels.
+                let stereo: Vec<u8> = chunk
+                    .chunks_exact(2)
+                    .flat_map(|s| [s[0], s[1], s[0], s[1]])
+                    .collect();
+                sender
+                    .send(ClientMessage::Binary(stereo.into()))
+                    .await
+                    .unwrap();
+            }
+
+            let keepalive = serde_json::to_string(&ListenControlMessage::KeepAlive).unwrap();
+            sender
+                .send(ClientMessage::Text(keepalive.into()))
+                .await
+                .unwrap();
+
+            let end = serde_json::to_string(&ListenControlMessage::End).unwrap();
+            sender.send(ClientMessage::Text(end.into())).await.unwrap();
+        });
+
+        let expected: Vec<_> = script.words.iter().map(|w| w.text.trim()).collect();
+        assert_eq!(collect_words(receiver).await, expected);
+    }
+
+    #[tokio::test]
+    async fn test_multichannel() {
+        let script = hypr_stt_mock::Script::english();
+        let deepgram = hypr_stt_mock::serve_deepgram_multichannel(vec![
+            script.clone(),
+            hypr_stt_mock::Script::default(),
+        ])
+        .await;
+        let addr = serve(
+            hypr_stt::realtime::Client::builder()
+                .deepgram_api_base(deepgram.api_base())
+                .deepgram_api_key("mock")
+                .build(),
+        )
+        .await;
+        let (mut sender, mut receiver) = connect(addr).await.split();
+
+        tokio::spawn(async move {
+            let start = ListenControlMessage::Start {
+                version: LISTEN_PROTOCOL_VERSION,
+                params: ListenParams {
+                    language: hypr_language::ISO639::En.into(),
+                    multichannel: true,
+                    ..Default::default()
+                },
+                format: AudioFormat {
+                    sample_rate: 16 * 1000,
+                    encoding: AudioEncoding::Linear16,
+                    channels: 2,
+                },
+                resume: None,
+            };
+            let start = serde_json::to_string(&start).unwrap();
+            sender
+                .send(ClientMessage::Text(start.into()))
+                .await
+                .unwrap();
+
+            for chunk in hypr_data::english_2::AUDIO.chunks(3200) {
+                // Speech on the mic channel, silence on the other.
+                let stereo: Vec<u8> = chunk
+                    .chunks_exact(2)
+                    .flat_map(|s| [s[0], s[1], 0, 0])
+                    .collect();
+                sender
+                    .send(ClientMessage::Binary(stereo.into()))
+                    .await
+                    .unwrap();
+            }
+
+            let end = serde_json::to_string(&ListenControlMessage::End).unwrap();
+            sender.send(ClientMessage::Text(end.into())).await.unwrap();
+        });
+
+        let mut words = vec![];
+        while let Some(Ok(msg)) = receiver.next().await {
+            if let ClientMessage::Text(text) = msg {
+                if serde_json::from_str::<ListenServerMessage>(&text).is_ok() {
+                    continue;
+                }
+
+                let chunk: ListenOutputChunk = serde_json::from_str(&text).unwrap();
+                if !chunk.words.is_empty() {
+                    assert_eq!(chunk.channel, Some(hypr_listener_interface::MIC_CHANNEL));
+                }
+                words.extend(chunk.words.into_iter().map(|w| w.text));
+            }
+        }
+
+        let expected: Vec<_> = script.words.iter().map(|w| w.text.trim()).collect();
+        assert_eq!(words, expected);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_frame() {
+        let script = hypr_stt_mock::Script::english();
+        let (addr, _deepgram) = serve_with_deepgram(&script).await;
+        let (mut sender, mut receiver) = connect(addr).await.split();
+
+        sender
+            .send(ClientMessage::Binary(vec![0u8; 3200].into()))
             .await
             .unwrap();
-        let _addr = listener.local_addr().unwrap();
-        tokio::spawn(axum::serve(listener, app()).into_future());
+        sender
+            .send(ClientMessage::Text("not json".into()))
+            .await
+            .unwrap();
+
+        let mut close_code = None;
+        while let Some(Ok(msg)) = receiver.next().await {
+            if let ClientMessage::Close(frame) = msg {
+                close_code = frame.map(|f| u16::from(f.code));
+                break;
+            }
+        }
+
+        assert_eq!(close_code, Some(close_code::INVALID));
+    }
+
+    #[tokio::test]
+    async fn test_resume() {
+        let script = hypr_stt_mock::Script::english();
+        let (addr, _deepgram) = serve_with_deepgram(&script).await;
+        let frames: Vec<_> = hypr_data::english_2::AUDIO.chunks(3200).collect();
+
+        let mut socket = connect(addr).await;
+        socket.send(start_message(None)).await.unwrap();
+        let (session_id, next_seq) = next_session(&mut socket).await;
+        assert_eq!(next_seq, 0);
+
+        for frame in &frames[..frames.len() / 2] {
+            socket
+                .send(ClientMessage::Binary(frame.to_vec().into()))
+                .await
+                .unwrap();
+        }
+
+        // Wait for some transcript, then drop the connection without `end`.
+        let mut words = vec![];
+        let mut received = 0;
+        while let Some(Ok(msg)) = socket.next().await {
+            if let ClientMessage::Text(text) = msg {
+                if let Ok(chunk) = serde_json::from_str::<ListenOutputChunk>(&text) {
+                    words.extend(chunk.words.into_iter().map(|w| w.text));
+                    received += 1;
+                    break;
+                }
+            }
+        }
+        assert!(!words.is_empty());
+        drop(socket);
+
+        let mut socket = connect(addr).await;
+        socket
+            .send(start_message(Some(ListenResume {
+                session_id,
+                received,
+            })))
+            .await
+            .unwrap();
+        let (_, next_seq) = next_session(&mut socket).await;
+        assert!(next_seq as usize <= frames.len() / 2);
+
+        let (mut sender, receiver) = socket.split();
+        tokio::spawn(async move {
+            for frame in &frames[next_seq as usize..] {
+                sender
+                    .send(ClientMessage::Binary(frame.to_vec().into()))
+                    .await
+                    .unwrap();
+            }
+
+            let end = serde_json::to_string(&ListenControlMessage::End).unwrap();
+            sender.send(ClientMessage::Text(end.into())).await.unwrap();
+        });
+
+        words.extend(collect_words(receiver).await);
+
+        let expected: Vec<_> = script.words.iter().map(|w| w.text.trim()).collect();
+        assert_eq!(words, expected);
+    }
+
+    #[tokio::test]
+    async fn test_resume_unknown_session() {
+        let script = hypr_stt_mock::Script::english();
+        let (addr, _deepgram) = serve_with_deepgram(&script).await;
+
+        let mut socket = connect(addr).await;
+        socket
+            .send(start_message(Some(ListenResume {
+                session_id: "unknown".to_string(),
+                received: 0,
+            })))
+            .await
+            .unwrap();
+
+        let mut close_code = None;
+        while let Some(Ok(msg)) = socket.next().await {
+            if let ClientMessage::Close(frame) = msg {
+                close_code = frame.map(|f| u16::from(f.code));
+                break;
+            }
+        }
+
+        assert_eq!(close_code, Some(close_code::POLICY));
     }
 }
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Query, State},
+    http::StatusCode,
     response::IntoResponse,
     Json,
 };
@@ -12,11 +13,15 @@ use hypr_stt::recorded::{RecordedSpeech, RecordedSpeechToText};
 pub async fn handler(
     Query(params): Query<ListenParams>,
     State(state): State<STTState>,
-) -> impl IntoResponse {
-    let stt = state.recorded_stt.for_language(params.language).await;
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let stt = state
+        .recorded_stt
+        .for_language(params.language, params.vocabulary)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
 
     let input = RecordedSpeech::File("TODO".into());
     let result = stt.transcribe(input).await.unwrap();
 
-    Json(result)
+    Ok(Json(result))
 }
@@ -1,3 +1,4 @@
+pub mod diarize;
 pub mod listen;
 pub mod openai;
 pub mod subscription;
@@ -0,0 +1,56 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+
+use hypr_pyannote::cloud::webhook::{self, WebhookPayload};
+
+use crate::state::DiarizationState;
+
+// Older deliveries are rejected, so a captured request can't be replayed.
+const MAX_TIMESTAMP_AGE_SECS: i64 = 5 * 60;
+
+// https://docs.pyannote.ai/webhooks/receiving-webhooks
+pub async fn handler(
+    State(state): State<DiarizationState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, StatusCode> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or(StatusCode::BAD_REQUEST)
+    };
+    let timestamp = header(webhook::TIMESTAMP_HEADER)?;
+    let signature = header(webhook::SIGNATURE_HEADER)?;
+
+    let age = timestamp
+        .parse::<i64>()
+        .map(|t| chrono::Utc::now().timestamp() - t)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if age.abs() > MAX_TIMESTAMP_AGE_SECS {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    if !webhook::verify(&state.webhook_secret, timestamp, &body, signature) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let payload: WebhookPayload =
+        serde_json::from_slice(&body).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let job_id = payload.job_id.clone();
+
+    match payload.segments() {
+        Ok(segments) => state.jobs.complete(job_id, Ok(segments)),
+        // Only sent once a job is done, so there is nothing to record for one still running.
+        Err(hypr_pyannote::cloud::Error::JobPending(_)) => {}
+        Err(e) => {
+            tracing::error!("pyannote_job_error: {:?}", e);
+            state.jobs.complete(job_id, Err(e.to_string()));
+        }
+    }
+
+    Ok(StatusCode::OK)
+}
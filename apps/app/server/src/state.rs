@@ -5,6 +5,7 @@ use hypr_analytics::AnalyticsClient;
 use hypr_db_admin::AdminDatabase;
 use hypr_nango::NangoClient;
 use hypr_openai::OpenAIClient;
+use hypr_pyannote::cloud::PyannoteClient;
 use hypr_s3::Client as S3Client;
 use hypr_turso::TursoClient;
 
@@ -14,12 +15,17 @@ pub struct AppState {
     pub clerk: Clerk,
     pub realtime_stt: hypr_stt::realtime::Client,
     pub recorded_stt: hypr_stt::recorded::Client,
+    pub listen_sessions: hypr_ws_utils::ListenSessions,
     pub admin_db: AdminDatabase,
     pub analytics: AnalyticsClient,
     pub turso: TursoClient,
     pub nango: NangoClient,
     pub s3: S3Client,
     pub stripe: stripe::Client,
+    pub pyannote: PyannoteClient,
+    pub diarization_jobs: crate::native::diarize::DiarizationJobs,
+    pub pyannote_webhook_url: String,
+    pub pyannote_webhook_secret: String,
 }
 
 #[derive(Clone)]
@@ -34,6 +40,16 @@ pub struct WorkerState {
 pub struct STTState {
     pub realtime_stt: hypr_stt::realtime::Client,
     pub recorded_stt: hypr_stt::recorded::Client,
+    pub listen_sessions: hypr_ws_utils::ListenSessions,
+}
+
+#[derive(Clone)]
+pub struct DiarizationState {
+    pub pyannote: PyannoteClient,
+    pub jobs: crate::native::diarize::DiarizationJobs,
+    // Where pyannote delivers job outcomes, i.e. our `/webhook/pyannote`.
+    pub webhook_url: String,
+    pub webhook_secret: String,
 }
 
 #[derive(Clone)]
@@ -53,6 +69,18 @@ impl FromRef<AppState> for STTState {
         STTState {
             realtime_stt: app_state.realtime_stt.clone(),
             recorded_stt: app_state.recorded_stt.clone(),
+            listen_sessions: app_state.listen_sessions.clone(),
+        }
+    }
+}
+
+impl FromRef<AppState> for DiarizationState {
+    fn from_ref(app_state: &AppState) -> DiarizationState {
+        DiarizationState {
+            pyannote: app_state.pyannote.clone(),
+            jobs: app_state.diarization_jobs.clone(),
+            webhook_url: app_state.pyannote_webhook_url.clone(),
+            webhook_secret: app_state.pyannote_webhook_secret.clone(),
         }
     }
 }
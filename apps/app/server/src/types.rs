@@ -19,6 +19,8 @@ pub enum Error {
     #[error(transparent)]
     DatabaseError(#[from] hypr_db_core::Error),
     #[error(transparent)]
+    DatabaseUserError(#[from] hypr_db_user::Error),
+    #[error(transparent)]
     NangoError(#[from] hypr_nango::Error),
     #[error(transparent)]
     TursoError(#[from] hypr_turso::Error),
@@ -100,6 +100,11 @@ pub async fn perform(job: Job, ctx: Data<WorkerState>) -> Result<(), Error> {
                         start_date: e.start_date,
                         end_date: e.end_date,
                         google_event_url: None,
+                        participants: e
+                            .participants
+                            .into_iter()
+                            .map(hypr_db_user::EventParticipant::from)
+                            .collect(),
                     };
 
                     let _ = user_db.upsert_event(event).await;
@@ -50,7 +50,9 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> AppExt<R> for T {
 
         {
             use tauri_plugin_local_llm::LocalLlmPluginExt;
-            if let Ok(true) = self.is_model_downloaded().await {
+
+            let current_model = self.current_model().await.map_err(|e| e.to_string())?;
+            if let Ok(true) = self.is_model_downloaded(&current_model).await {
                 if let Err(e) = self.start_server().await {
                     tracing::error!("start_local_llm_server: {}", e);
                 }
@@ -9,6 +9,7 @@ import { useHypr } from "@/contexts";
 import { commands as analyticsCommands } from "@hypr/plugin-analytics";
 import { commands as connectorCommands } from "@hypr/plugin-connector";
 import { commands as dbCommands } from "@hypr/plugin-db";
+import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
 import { commands as miscCommands } from "@hypr/plugin-misc";
 import { commands as templateCommands } from "@hypr/plugin-template";
 import Editor, { type TiptapEditor } from "@hypr/tiptap/editor";
@@ -53,12 +54,16 @@ export default function EditorArea({
   );
 
   const generateTitle = useGenerateTitleMutation({ sessionId });
+  const extract = useExtractMutation({ sessionId });
+  const index = useIndexMutation({ sessionId });
   const enhance = useEnhanceMutation({
     sessionId,
     rawContent,
     onSuccess: (content) => {
       console.log("useEnhanceMutation onSuccess", content);
       generateTitle.mutate({ enhancedContent: content });
+      extract.mutate();
+      index.mutate();
     },
   });
 
@@ -215,6 +220,13 @@ export function useEnhanceMutation({
         { config, type },
       );
 
+      // Partial summaries when the transcript doesn't fit in the context window, `null` otherwise.
+      const summaries = await connectorCommands.summarizeTranscript({
+        type,
+        config,
+        words: JSON.stringify(words),
+      });
+
       const userMessage = await templateCommands.render(
         "enhance.user",
         {
@@ -222,6 +234,7 @@ export function useEnhanceMutation({
           editor: rawContent,
           words: JSON.stringify(words),
           participants,
+          summaries,
         },
       );
 
@@ -352,6 +365,69 @@ function useGenerateTitleMutation({ sessionId }: { sessionId: string }) {
   return generateTitle;
 }
 
+function useExtractMutation({ sessionId }: { sessionId: string }) {
+  const { onboardingSessionId } = useHypr();
+  const createdAt = useSession(sessionId, (s) => s.session.created_at);
+
+  const extract = useMutation({
+    mutationKey: ["extract", sessionId],
+    mutationFn: async () => {
+      if (sessionId === onboardingSessionId) {
+        return;
+      }
+
+      const words = await dbCommands.getWords(sessionId);
+      if (!words.length) {
+        return;
+      }
+
+      const { type } = await connectorCommands.getLlmConnection();
+      const config = await dbCommands.getConfig();
+      const participants = await dbCommands.sessionListParticipants(sessionId);
+
+      const { action_items, decisions } = await connectorCommands.extractSession({
+        type,
+        config,
+        words: JSON.stringify(words),
+        participants,
+        session_id: sessionId,
+        date: createdAt.slice(0, 10),
+      });
+
+      await dbCommands.replaceSessionExtraction(sessionId, action_items, decisions);
+    },
+    onError: (error) => {
+      console.error(error);
+    },
+  });
+
+  return extract;
+}
+
+function useIndexMutation({ sessionId }: { sessionId: string }) {
+  const index = useMutation({
+    mutationKey: ["index", sessionId],
+    mutationFn: async () => {
+      if (!(await localLlmCommands.isEmbeddingModelDownloaded())) {
+        return;
+      }
+
+      const chunks = await dbCommands.splitSessionChunks(sessionId);
+      const embeddings = await localLlmCommands.embedDocuments(chunks.map((chunk) => chunk.text));
+
+      await dbCommands.replaceSessionChunks(
+        sessionId,
+        chunks.map((chunk, i) => ({ ...chunk, embedding: embeddings[i] })),
+      );
+    },
+    onError: (error) => {
+      console.error(error);
+    },
+  });
+
+  return index;
+}
+
 function useAutoEnhance({
   sessionId,
   enhanceStatus,
@@ -1,12 +1,20 @@
 import { cn } from "@hypr/ui/lib/utils";
 import { Trans } from "@lingui/react/macro";
+import { useNavigate } from "@tanstack/react-router";
 import { Message } from "./types";
 
 interface ChatMessageProps {
   message: Message;
 }
 
+const formatTimestamp = (ms: number) => {
+  const secs = Math.floor(ms / 1000);
+  return `${Math.floor(secs / 60)}:${(secs % 60).toString().padStart(2, "0")}`;
+};
+
 export function ChatMessage({ message }: ChatMessageProps) {
+  const navigate = useNavigate();
+
   return (
     <div className="w-full mb-4">
       <div
@@ -18,6 +26,21 @@ export function ChatMessage({ message }: ChatMessageProps) {
         {message.isUser ? <Trans>User:</Trans> : <Trans>Assistant:</Trans>}
       </div>
       <div className="text-sm whitespace-pre-wrap break-words overflow-wrap-anywhere max-w-full">{message.content}</div>
+      {message.sources && message.sources.length > 0 && (
+        <div className="mt-2 flex flex-col gap-1">
+          {message.sources.map((source) => (
+            <button
+              key={source.id}
+              title={source.text}
+              className="text-left text-xs text-neutral-500 hover:text-neutral-800 truncate"
+              onClick={() => navigate({ to: "/app/note/$id", params: { id: source.session_id } })}
+            >
+              [{source.citation}] {source.start_ms !== null && `${formatTimestamp(source.start_ms)} · `}
+              {source.text}
+            </button>
+          ))}
+        </div>
+      )}
     </div>
   );
 }
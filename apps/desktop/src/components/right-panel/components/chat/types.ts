@@ -1,8 +1,11 @@
+import type { ChatMessageSource } from "@hypr/plugin-db";
+
 export type Message = {
   id: string;
   content: string;
   isUser: boolean;
   timestamp: Date;
+  sources?: ChatMessageSource[];
 };
 
 export type ChatSession = {
@@ -21,6 +21,7 @@ export function useTranscript(sessionId: string | null) {
   ]);
 
   const [words, setWords] = useState<Word[]>([]);
+  const [interimWords, setInterimWords] = useState<Word[]>([]);
   const [selectedLanguage, setSelectedLanguage] = useState<string>("en");
 
   const existingWords = useQuery({
@@ -50,6 +51,9 @@ export function useTranscript(sessionId: string | null) {
     listenerEvents.sessionEvent.listen(({ payload }) => {
       if (payload.type === "words") {
         setWords(payload.words as Word[]);
+        setInterimWords([]);
+      } else if (payload.type === "interimWords") {
+        setInterimWords(payload.words as Word[]);
       }
     }).then((fn) => {
       unlisten = fn;
@@ -67,7 +71,7 @@ export function useTranscript(sessionId: string | null) {
   };
 
   return {
-    words,
+    words: interimWords.length ? [...words, ...interimWords] : words,
     isLive,
     selectedLanguage,
     handleLanguageChange,
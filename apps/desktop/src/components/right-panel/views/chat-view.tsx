@@ -1,6 +1,8 @@
 import { useEffect, useState } from "react";
 
-import { useRightPanel } from "@/contexts";
+import { useHypr, useRightPanel } from "@/contexts";
+import { commands as connectorCommands } from "@hypr/plugin-connector";
+import { commands as dbCommands } from "@hypr/plugin-db";
 import { useMatch, useNavigate } from "@tanstack/react-router";
 import {
   ChatHistoryView,
@@ -21,6 +23,7 @@ export type BadgeType = "note" | "human" | "organization";
 
 export function ChatView() {
   const navigate = useNavigate();
+  const { userId } = useHypr();
   const { isExpanded, chatInputRef } = useRightPanel();
 
   const [messages, setMessages] = useState<Message[]>([]);
@@ -30,6 +33,7 @@ export function ChatView() {
 
   const [activeEntity, setActiveEntity] = useState<ActiveEntityInfo | null>(null);
   const [hasChatStarted, setHasChatStarted] = useState(false);
+  const [groupId, setGroupId] = useState<string | null>(null);
 
   const [chatHistory, _setChatHistory] = useState<ChatSession[]>([]);
 
@@ -79,18 +83,10 @@ export function ChatView() {
     setInputValue(e.target.value);
   };
 
-  const handleSubmit = () => {
-    if (!inputValue.trim()) {
-      return;
-    }
-
-    if (!hasChatStarted && activeEntity) {
-      setHasChatStarted(true);
-    }
-
+  const sendMessage = async (content: string) => {
     const userMessage: Message = {
       id: Date.now().toString(),
-      content: inputValue,
+      content,
       isUser: true,
       timestamp: new Date(),
     };
@@ -98,15 +94,54 @@ export function ChatView() {
     setMessages((prev) => [...prev, userMessage]);
     setInputValue("");
 
-    setTimeout(() => {
-      const aiMessage: Message = {
-        id: (Date.now() + 1).toString(),
-        content: "This is a sample response from the AI assistant.",
+    try {
+      let currentGroupId = groupId;
+      if (!currentGroupId) {
+        const group = await dbCommands.createChatGroup({
+          id: crypto.randomUUID(),
+          user_id: userId,
+          name: content.slice(0, 50),
+          created_at: new Date().toISOString(),
+        });
+        currentGroupId = group.id;
+        setGroupId(group.id);
+      }
+
+      const config = await dbCommands.getConfig();
+      const { message, sources } = await connectorCommands.answerChat(
+        currentGroupId,
+        {
+          user_id: userId,
+          participant_id: activeEntity?.type === "human" ? activeEntity.id : null,
+          tag_id: null,
+          start: null,
+          end: null,
+        },
+        { config, question: content },
+      );
+
+      setMessages((prev) => [...prev, {
+        id: message.id,
+        content: message.content,
         isUser: false,
-        timestamp: new Date(),
-      };
-      setMessages((prev) => [...prev, aiMessage]);
-    }, 1000);
+        timestamp: new Date(message.created_at),
+        sources,
+      }]);
+    } catch (error) {
+      console.error(error);
+    }
+  };
+
+  const handleSubmit = () => {
+    if (!inputValue.trim()) {
+      return;
+    }
+
+    if (!hasChatStarted && activeEntity) {
+      setHasChatStarted(true);
+    }
+
+    sendMessage(inputValue);
   };
 
   const handleKeyDown = (e: React.KeyboardEvent<HTMLTextAreaElement>) => {
@@ -117,25 +152,7 @@ export function ChatView() {
   };
 
   const handleQuickAction = (prompt: string) => {
-    const userMessage: Message = {
-      id: Date.now().toString(),
-      content: prompt,
-      isUser: true,
-      timestamp: new Date(),
-    };
-
-    setMessages((prev) => [...prev, userMessage]);
-    setInputValue("");
-
-    setTimeout(() => {
-      const aiMessage: Message = {
-        id: (Date.now() + 1).toString(),
-        content: "This is a sample response from the AI assistant.",
-        isUser: false,
-        timestamp: new Date(),
-      };
-      setMessages((prev) => [...prev, aiMessage]);
-    }, 1000);
+    sendMessage(prompt);
 
     if (chatInputRef.current) {
       chatInputRef.current.focus();
@@ -150,6 +167,7 @@ export function ChatView() {
 
   const handleNewChat = () => {
     setMessages([]);
+    setGroupId(null);
     setInputValue("");
     setShowHistory(false);
     setHasChatStarted(false);
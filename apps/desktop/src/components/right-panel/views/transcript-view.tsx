@@ -7,7 +7,7 @@ import { memo, useCallback, useEffect, useRef, useState } from "react";
 
 import { ParticipantsChipInner } from "@/components/editor-area/note-header/chips/participants-chip";
 import { useHypr } from "@/contexts";
-import { commands as dbCommands, Human, Word } from "@hypr/plugin-db";
+import { commands as dbCommands, Human, SpeakerSuggestion, Word } from "@hypr/plugin-db";
 import { commands as miscCommands } from "@hypr/plugin-misc";
 import TranscriptEditor, {
   type SpeakerChangeRange,
@@ -209,6 +209,13 @@ const MemoizedSpeakerSelector = memo(({
     queryFn: () => dbCommands.sessionListParticipants(sessionId!),
   });
 
+  const { data: suggestion } = useQuery({
+    enabled: !!sessionId && !speakerId,
+    queryKey: ["speaker-suggestions", sessionId],
+    queryFn: () => dbCommands.listSpeakerSuggestions(sessionId!),
+    select: (suggestions) => suggestions.find((s) => s.speaker_index === speakerIndex),
+  });
+
   useEffect(() => {
     if (human) {
       onSpeakerChange(human, speakerRange);
@@ -265,6 +272,16 @@ const MemoizedSpeakerSelector = memo(({
         </PopoverTrigger>
         <PopoverContent align="start" side="bottom">
           <div className="space-y-4">
+            {suggestion && (
+              <div className="border-b border-neutral-100 pb-3">
+                <SpeakerSuggestionPrompt
+                  sessionId={sessionId}
+                  suggestion={suggestion}
+                  onDone={() => setIsOpen(false)}
+                />
+              </div>
+            )}
+
             {!speakerId && (
               <div className="border-b border-neutral-100 pb-3">
                 <SpeakerRangeSelector
@@ -282,6 +299,51 @@ const MemoizedSpeakerSelector = memo(({
   );
 });
 
+function SpeakerSuggestionPrompt({
+  sessionId,
+  suggestion,
+  onDone,
+}: {
+  sessionId: string;
+  suggestion: SpeakerSuggestion;
+  onDone: () => void;
+}) {
+  const queryClient = useQueryClient();
+
+  const { data: human } = useQuery({
+    queryKey: ["human", suggestion.human_id],
+    queryFn: () => dbCommands.getHuman(suggestion.human_id),
+  });
+
+  const handleAnswer = async (accept: boolean) => {
+    if (accept) {
+      await dbCommands.acceptSpeakerSuggestion(suggestion.id);
+    } else {
+      await dbCommands.rejectSpeakerSuggestion(suggestion.id);
+    }
+
+    queryClient.invalidateQueries({ queryKey: ["speaker-suggestions", sessionId] });
+    queryClient.invalidateQueries({ queryKey: ["session", "words", sessionId] });
+    onDone();
+  };
+
+  if (!human) {
+    return null;
+  }
+
+  return (
+    <div className="space-y-1.5">
+      <p className="text-sm font-medium text-neutral-700">
+        Is this {human.full_name ?? "someone you know"}?
+      </p>
+      <div className="flex gap-2">
+        <Button size="sm" onClick={() => handleAnswer(true)}>Yes</Button>
+        <Button size="sm" variant="outline" onClick={() => handleAnswer(false)}>No</Button>
+      </div>
+    </div>
+  );
+}
+
 interface SpeakerRangeSelectorProps {
   value: SpeakerChangeRange;
   onChange: (value: SpeakerChangeRange) => void;
@@ -1,13 +1,17 @@
 import { zodResolver } from "@hookform/resolvers/zod";
 import { cn } from "@hypr/ui/lib/utils";
 import { Trans, useLingui } from "@lingui/react/macro";
-import { useMutation, useQuery } from "@tanstack/react-query";
-import { useEffect } from "react";
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { DownloadIcon, Trash2Icon } from "lucide-react";
+import { useEffect, useState } from "react";
 import { useForm } from "react-hook-form";
+import { toast } from "sonner";
 import { z } from "zod";
 
+import { showLlmModelDownloadToast } from "@/components/toast/shared";
 import { commands as connectorCommands, type Connection } from "@hypr/plugin-connector";
-import { type SupportedModel as SupportedModelLLM } from "@hypr/plugin-local-llm";
+import { commands as localLlmCommands, type SupportedModel as SupportedModelLLM } from "@hypr/plugin-local-llm";
+import { Button } from "@hypr/ui/components/ui/button";
 import {
   Form,
   FormControl,
@@ -43,8 +47,41 @@ const endpointSchema = z.object({
 
 export type FormValues = z.infer<typeof endpointSchema>;
 
+const formatBytes = (bytes: number) => `${(bytes / 1024 ** 3).toFixed(1)} GB`;
+
 export function LLMView() {
   const { t } = useLingui();
+  const queryClient = useQueryClient();
+  const [downloadingModelName, setDownloadingModelName] = useState<SupportedModelLLM | null>(null);
+
+  const currentLLMModel = useQuery({
+    queryKey: ["local-llm", "current-model"],
+    queryFn: () => localLlmCommands.getCurrentModel(),
+  });
+
+  const setCurrentLLMModel = useMutation({
+    mutationFn: (model: SupportedModelLLM) => localLlmCommands.setCurrentModel(model),
+    onSuccess: () => {
+      currentLLMModel.refetch();
+    },
+  });
+
+  const supportedLLMModels = useQuery({
+    queryKey: ["local-llm", "supported-models"],
+    queryFn: async () => {
+      const models = await localLlmCommands.listSupportedModels();
+      const downloadedModels = await Promise.all(models.map((info) => localLlmCommands.isModelDownloaded(info.model)));
+      return models.map((info, index) => ({ ...info, isDownloaded: downloadedModels[index] }));
+    },
+  });
+
+  const deleteLLMModel = useMutation({
+    mutationFn: (model: SupportedModelLLM) => localLlmCommands.deleteModel(model),
+    onError: console.error,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["local-llm", "supported-models"] });
+    },
+  });
   const customLLMConnection = useQuery({
     queryKey: ["custom-llm-connection"],
     queryFn: () => connectorCommands.getCustomLlmConnection(),
@@ -124,40 +161,109 @@ export function LLMView() {
     return apiBase && (apiBase.includes("localhost") || apiBase.includes("127.0.0.1"));
   };
 
-  const currentLLM: SupportedModelLLM | "custom" = customLLMEnabled.data ? "custom" : "Llama3p2_3bQ4";
+  const currentLLM: SupportedModelLLM | "custom" | undefined = customLLMEnabled.data
+    ? "custom"
+    : currentLLMModel.data;
 
   return (
     <RadioGroup
       value={currentLLM}
       onValueChange={(value) => {
         setCustomLLMEnabled.mutate(value === "custom");
+
+        if (value !== "custom") {
+          setCurrentLLMModel.mutate(value as SupportedModelLLM);
+        }
       }}
       className="space-y-4"
     >
-      <Label
-        htmlFor="llama-3.2-3b-q4"
-        className={cn(
-          "p-4 rounded-lg shadow-sm transition-all duration-150 ease-in-out",
-          currentLLM === "Llama3p2_3bQ4"
-            ? "border border-blue-500 ring-2 ring-blue-500 bg-blue-50"
-            : "border border-neutral-200 bg-white hover:border-neutral-300",
-          "cursor-pointer flex flex-col gap-2",
-        )}
-      >
-        <div className="flex items-start justify-between w-full">
-          <div className="flex items-center">
-            <RadioGroupItem value="llama-3.2-3b-q4" id="llama-3.2-3b-q4" className="peer sr-only" />
-            <div className="flex flex-col">
-              <span className="font-medium">
-                Default (llama-3.2-3b-q4)
-              </span>
-              <p className="text-xs font-normal text-neutral-500 mt-1">
-                <Trans>Use the local Llama 3.2 model for enhanced privacy and offline capability.</Trans>
-              </p>
+      {supportedLLMModels.data?.map((model) => {
+        const isSelected = currentLLM === model.model;
+        const isCurrentlyDownloading = downloadingModelName === model.model;
+        const size = formatBytes(model.size);
+        const memory = formatBytes(model.ram_required);
+
+        return (
+          <Label
+            key={model.model}
+            htmlFor={model.model}
+            onClick={(e) => {
+              if (!model.isDownloaded) {
+                e.preventDefault();
+                toast.info("You need to download this model first to be able to use it.", {
+                  duration: 2500,
+                });
+              }
+            }}
+            className={cn(
+              "p-4 rounded-lg shadow-sm transition-all duration-150 ease-in-out",
+              model.isDownloaded
+                ? isSelected
+                  ? "border border-blue-500 ring-2 ring-blue-500 bg-blue-50 cursor-pointer"
+                  : "border border-neutral-200 bg-white hover:border-neutral-300 cursor-pointer"
+                : "border border-neutral-300 bg-neutral-100",
+              "flex flex-col gap-2",
+            )}
+          >
+            <div className="flex items-start justify-between w-full">
+              <div className="flex items-center">
+                <RadioGroupItem
+                  value={model.model}
+                  id={model.model}
+                  className="peer sr-only"
+                  disabled={!model.isDownloaded}
+                />
+                <div className="flex flex-col">
+                  <span className="font-medium">{model.name}</span>
+                  <p className="text-xs font-normal text-neutral-500 mt-1">
+                    <Trans>Runs locally. {size} download, needs about {memory} of memory.</Trans>
+                  </p>
+                </div>
+              </div>
+
+              {model.isDownloaded
+                ? (
+                  <Button
+                    size="sm"
+                    variant="ghost"
+                    onClick={(e) => {
+                      e.preventDefault();
+                      e.stopPropagation();
+                      deleteLLMModel.mutate(model.model);
+                    }}
+                    disabled={deleteLLMModel.isPending}
+                  >
+                    <Trash2Icon className="w-4 h-4" />
+                  </Button>
+                )
+                : (
+                  <Button
+                    size="sm"
+                    onClick={(e) => {
+                      e.preventDefault();
+                      e.stopPropagation();
+                      if (isCurrentlyDownloading || supportedLLMModels.isFetching) {
+                        return;
+                      }
+
+                      setDownloadingModelName(model.model);
+                      showLlmModelDownloadToast(model.model, () => {
+                        queryClient.invalidateQueries({ queryKey: ["local-llm", "supported-models"] })
+                          .finally(() => {
+                            setDownloadingModelName(null);
+                          });
+                      });
+                    }}
+                    disabled={supportedLLMModels.isFetching || isCurrentlyDownloading}
+                  >
+                    <DownloadIcon className="w-4 h-4" />
+                    <Trans>Download</Trans>
+                  </Button>
+                )}
             </div>
-          </div>
-        </div>
-      </Label>
+          </Label>
+        );
+      })}
 
       <Label
         htmlFor="custom"
@@ -8,7 +8,7 @@ import { z } from "zod";
 
 import { showModelSelectToast } from "@/components/toast/model-select";
 import { commands } from "@/types";
-import { commands as dbCommands, type ConfigGeneral } from "@hypr/plugin-db";
+import { commands as dbCommands, type ConfigGeneral, type LanguageDetection } from "@hypr/plugin-db";
 import {
   Form,
   FormControl,
@@ -74,6 +74,8 @@ const schema = z.object({
   telemetryConsent: z.boolean().optional(),
   jargons: z.string(),
   saveRecordings: z.boolean().optional(),
+  languageDetection: z.enum(["off", "first_chunk", "per_chunk"]),
+  multichannel: z.boolean().optional(),
 });
 
 type Schema = z.infer<typeof schema>;
@@ -98,6 +100,8 @@ export default function General() {
       telemetryConsent: true,
       jargons: "",
       saveRecordings: true,
+      languageDetection: "off",
+      multichannel: false,
     },
   });
 
@@ -109,6 +113,8 @@ export default function General() {
         telemetryConsent: config.data.general.telemetry_consent ?? true,
         jargons: (config.data.general.jargons ?? []).join(", "),
         saveRecordings: config.data.general.save_recordings ?? true,
+        languageDetection: config.data.general.language_detection ?? "off",
+        multichannel: config.data.general.multichannel ?? false,
       });
     }
   }, [config.data, form]);
@@ -126,6 +132,8 @@ export default function General() {
         telemetry_consent: v.telemetryConsent ?? true,
         jargons: v.jargons.split(",").map((jargon) => jargon.trim()).filter(Boolean),
         save_recordings: v.saveRecordings ?? true,
+        language_detection: v.languageDetection as LanguageDetection,
+        multichannel: v.multichannel ?? false,
       };
 
       await dbCommands.setConfig({
@@ -189,6 +197,32 @@ export default function General() {
             )}
           />
 
+          <FormField
+            control={form.control}
+            name="multichannel"
+            render={({ field }) => (
+              <FormItem className="flex flex-row items-center justify-between">
+                <div>
+                  <FormLabel>
+                    <Trans>Separate my voice from others</Trans>
+                  </FormLabel>
+                  <FormDescription>
+                    <Trans>
+                      Transcribe your microphone and system audio separately, so your words are attributed to you.
+                    </Trans>
+                  </FormDescription>
+                </div>
+                <FormControl>
+                  <Switch
+                    checked={field.value}
+                    onCheckedChange={field.onChange}
+                    color="gray"
+                  />
+                </FormControl>
+              </FormItem>
+            )}
+          />
+
           <FormField
             control={form.control}
             name="telemetryConsent"
@@ -252,6 +286,44 @@ export default function General() {
             )}
           />
 
+          <FormField
+            control={form.control}
+            name="languageDetection"
+            render={({ field }) => (
+              <FormItem className="flex flex-row items-center justify-between">
+                <div className="space-y-0.5">
+                  <FormLabel>
+                    <Trans>Detect spoken language</Trans>
+                  </FormLabel>
+                  <FormDescription>
+                    <Trans>Transcribe in the language being spoken, instead of your preferred language</Trans>
+                  </FormDescription>
+                </div>
+                <FormControl>
+                  <Select
+                    onValueChange={field.onChange}
+                    value={field.value}
+                  >
+                    <SelectTrigger className="w-[200px]">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="off">
+                        <Trans>Off</Trans>
+                      </SelectItem>
+                      <SelectItem value="first_chunk">
+                        <Trans>Once per meeting</Trans>
+                      </SelectItem>
+                      <SelectItem value="per_chunk">
+                        <Trans>Continuously</Trans>
+                      </SelectItem>
+                    </SelectContent>
+                  </Select>
+                </FormControl>
+              </FormItem>
+            )}
+          />
+
           <FormField
             control={form.control}
             name="jargons"
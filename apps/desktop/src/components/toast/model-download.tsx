@@ -12,13 +12,18 @@ export default function ModelDownloadNotification() {
     queryFn: () => localSttCommands.getCurrentModel(),
   });
 
+  const currentLlmModel = useQuery({
+    queryKey: ["current-llm-model"],
+    queryFn: () => localLlmCommands.getCurrentModel(),
+  });
+
   const checkForModelDownload = useQuery({
-    enabled: !!currentSttModel.data,
+    enabled: !!currentSttModel.data && !!currentLlmModel.data,
     queryKey: ["check-model-downloaded"],
     queryFn: async () => {
       const [stt, llm] = await Promise.all([
         localSttCommands.isModelDownloaded(currentSttModel.data!),
-        localLlmCommands.isModelDownloaded(),
+        localLlmCommands.isModelDownloaded(currentLlmModel.data!),
       ]);
 
       return {
@@ -40,10 +45,10 @@ export default function ModelDownloadNotification() {
   });
 
   const llmModelDownloading = useQuery({
-    enabled: !checkForModelDownload.data?.llmModelDownloaded,
+    enabled: !checkForModelDownload.data?.llmModelDownloaded && !!currentLlmModel.data,
     queryKey: ["llm-model-downloading"],
     queryFn: async () => {
-      return localLlmCommands.isModelDownloading();
+      return localLlmCommands.isModelDownloading(currentLlmModel.data!);
     },
     refetchInterval: 3000,
   });
@@ -76,7 +81,7 @@ export default function ModelDownloadNotification() {
             }
 
             if (!checkForModelDownload.data?.llmModelDownloaded && !llmModelDownloading.data) {
-              showLlmModelDownloadToast();
+              showLlmModelDownloadToast(currentLlmModel.data!);
             }
           },
           primary: true,
@@ -13,6 +13,7 @@ import {
 } from "@hypr/ui/components/ui/carousel";
 
 import { showLlmModelDownloadToast, showSttModelDownloadToast } from "@/components/toast/shared";
+import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
 import { SupportedModel } from "@hypr/plugin-local-stt";
 import { commands as localSttCommands } from "@hypr/plugin-local-stt";
 import PushableButton from "@hypr/ui/components/ui/pushable-button";
@@ -71,7 +72,7 @@ export const ModelSelectionView = ({
 
   const handleContinue = () => {
     showSttModelDownloadToast(selectedModel);
-    showLlmModelDownloadToast();
+    localLlmCommands.getCurrentModel().then((model) => showLlmModelDownloadToast(model));
     onContinue(selectedModel);
   };
 
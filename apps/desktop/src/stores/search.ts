@@ -1,4 +1,5 @@
 import { commands as dbCommands, type Event, type Human, type Organization, type Session } from "@hypr/plugin-db";
+import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
 import { debounce } from "lodash-es";
 import type React from "react";
 import { createStore } from "zustand";
@@ -57,6 +58,18 @@ const saveSearchHistory = (userId: string, history: string[]) => {
   }
 };
 
+const searchSessionsSemantic = async (query: string, userId: string): Promise<Session[]> => {
+  try {
+    if (!(await localLlmCommands.isEmbeddingModelDownloaded())) {
+      return [];
+    }
+
+    return await dbCommands.listSessions({ type: "semantic", query, limit: 5, user_id: userId });
+  } catch {
+    return [];
+  }
+};
+
 export const createSearchStore = (userId: string) => {
   const performSearch = debounce(async (query: string, setState: any, getState: any) => {
     setState({ isSearching: true });
@@ -69,8 +82,9 @@ export const createSearchStore = (userId: string) => {
       }
 
       // Fast, simple API calls
-      const [sessions, events, humans, organizations] = await Promise.all([
+      const [keywordSessions, semanticSessions, events, humans, organizations] = await Promise.all([
         dbCommands.listSessions({ type: "search", query, limit: 10, user_id: userId }),
+        searchSessionsSemantic(query, userId),
         dbCommands.listEvents({ type: "search", query, limit: 5, user_id: userId }),
         dbCommands.listHumans({ search: [3, query] }),
         dbCommands.listOrganizations({ search: [3, query] }),
@@ -81,6 +95,12 @@ export const createSearchStore = (userId: string) => {
         return;
       }
 
+      // Keyword matches first, then sessions that only match by meaning
+      const sessions = [
+        ...keywordSessions,
+        ...semanticSessions.filter((s) => !keywordSessions.some((k) => k.id === s.id)),
+      ];
+
       // Simple mapping
       const matches: SearchMatch[] = [
         ...sessions.map((session) => ({
@@ -26,3 +26,28 @@ pub trait AudioFormatExt: AsyncSource {
         })
     }
 }
+
+// Interleaves equally chunked linear16 streams, e.g. from `to_i16_le_chunks`, into one multichannel stream.
+// Ends with the shortest one.
+pub fn interleave_i16_le<S>(streams: Vec<S>) -> impl Stream<Item = Bytes> + Send + Unpin
+where
+    S: Stream<Item = Bytes> + Send + Unpin + 'static,
+{
+    Box::pin(futures_util::stream::unfold(
+        streams,
+        |mut streams| async move {
+            let chunks = futures_util::future::join_all(streams.iter_mut().map(|s| s.next())).await;
+            let chunks = chunks.into_iter().collect::<Option<Vec<_>>>()?;
+            let len = chunks.iter().map(|c| c.len()).min().unwrap_or(0);
+
+            let mut buf = BytesMut::with_capacity(len * chunks.len());
+            for i in (0..len).step_by(2) {
+                for chunk in &chunks {
+                    buf.put_slice(&chunk[i..i + 2]);
+                }
+            }
+
+            Some((buf.freeze(), streams))
+        },
+    ))
+}
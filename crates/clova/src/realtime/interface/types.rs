@@ -70,6 +70,8 @@ impl From<Vec<String>> for KeywordBoosting {
 pub enum Language {
     #[serde(rename = "ko")]
     Korean,
+    #[serde(rename = "en")]
+    English,
     #[serde(rename = "ja")]
     Japanese,
 }
@@ -13,6 +13,8 @@ use tonic::{service::interceptor::InterceptedService, transport::Channel, Reques
 // 'Send' is required in the websocket handler context
 type Interceptor = Box<dyn FnMut(Request<()>) -> Result<Request<()>, Status> + Send>;
 
+const DEFAULT_API_BASE: &str = "https://clovaspeech-gw.ncloud.com:50051";
+
 #[derive(Debug)]
 pub struct Client {
     inner: NestServiceClient<InterceptedService<Channel, Interceptor>>,
@@ -21,27 +23,47 @@ pub struct Client {
 
 #[derive(Debug, Default)]
 pub struct ClientBuilder {
+    api_base: Option<String>,
     api_key: Option<String>,
-    keywords: Option<Vec<String>>,
+    language: Option<interface::Language>,
+    keyword_boosting: Option<interface::KeywordBoosting>,
 }
 
 impl ClientBuilder {
+    pub fn api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
     pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
         self.api_key = Some(api_key.into());
         self
     }
 
+    pub fn language(mut self, language: interface::Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
     pub fn keywords(mut self, keywords: impl Into<Vec<String>>) -> Self {
-        self.keywords = Some(keywords.into());
+        self.keyword_boosting = Some(keywords.into().into());
+        self
+    }
+
+    pub fn keyword_boosting(mut self, keyword_boosting: interface::KeywordBoosting) -> Self {
+        self.keyword_boosting = Some(keyword_boosting);
         self
     }
 
     pub async fn build(self) -> Result<Client, crate::Error> {
-        let channel =
-            tonic::transport::Channel::from_static("https://clovaspeech-gw.ncloud.com:50051")
-                .tls_config(tonic::transport::ClientTlsConfig::new().with_native_roots())?
-                .connect()
-                .await?;
+        let api_base = self.api_base.unwrap_or(DEFAULT_API_BASE.to_string());
+
+        let mut endpoint = tonic::transport::Endpoint::from_shared(api_base.clone())?;
+        if api_base.starts_with("https") {
+            endpoint = endpoint
+                .tls_config(tonic::transport::ClientTlsConfig::new().with_native_roots())?;
+        }
+        let channel = endpoint.connect().await?;
 
         let inner = NestServiceClient::with_interceptor(
             channel,
@@ -50,9 +72,12 @@ impl ClientBuilder {
 
         let config = interface::ConfigRequest {
             transcription: Some(interface::Transcription {
-                language: interface::Language::Korean,
+                language: self.language.unwrap_or(interface::Language::Korean),
             }),
-            keyword_boosting: Some(self.keywords.unwrap_or_default().into()),
+            keyword_boosting: Some(
+                self.keyword_boosting
+                    .unwrap_or_else(|| Vec::<String>::new().into()),
+            ),
             semantic_epd: Some(interface::SemanticEpd {
                 skip_empty_text: Some(true),
                 use_word_epd: Some(true),
@@ -13,8 +13,9 @@ impl super::Client {
             .push("url");
 
         let params = RequestParams {
-            language: super::Language::KoreanWithEnglish,
+            language: self.language.clone(),
             completion: super::Completion::Sync,
+            boostings: self.boostings.clone(),
         };
 
         let mut params_value: serde_json::Value = serde_json::to_value(params).unwrap();
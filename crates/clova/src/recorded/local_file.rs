@@ -13,8 +13,9 @@ impl super::Client {
             .push("upload");
 
         let params = RequestParams {
-            language: super::Language::KoreanWithEnglish,
+            language: self.language.clone(),
             completion: super::Completion::Sync,
+            boostings: self.boostings.clone(),
         };
 
         let form = reqwest::multipart::Form::new()
@@ -8,6 +8,8 @@ pub use types::*;
 pub struct ClientBuilder {
     api_base: Option<String>,
     api_key: Option<String>,
+    language: Option<Language>,
+    boostings: Option<Vec<Boosting>>,
 }
 
 impl ClientBuilder {
@@ -21,6 +23,16 @@ impl ClientBuilder {
         self
     }
 
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    pub fn boostings(mut self, boostings: impl Into<Vec<Boosting>>) -> Self {
+        self.boostings = Some(boostings.into());
+        self
+    }
+
     pub fn build(self) -> Client {
         let mut headers = reqwest::header::HeaderMap::new();
         let mut auth = reqwest::header::HeaderValue::from_str(&self.api_key.unwrap()).unwrap();
@@ -35,6 +47,8 @@ impl ClientBuilder {
         Client {
             api_base: self.api_base.unwrap().parse().unwrap(),
             client,
+            language: self.language.unwrap_or(Language::KoreanWithEnglish),
+            boostings: self.boostings.unwrap_or_default(),
         }
     }
 }
@@ -43,6 +57,8 @@ impl ClientBuilder {
 pub struct Client {
     api_base: url::Url,
     client: reqwest::Client,
+    language: Language,
+    boostings: Vec<Boosting>,
 }
 
 impl Client {
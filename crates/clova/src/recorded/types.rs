@@ -4,6 +4,14 @@
 pub struct RequestParams {
     pub language: Language,
     pub completion: Completion,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boostings: Vec<Boosting>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Boosting {
+    // Comma separated.
+    pub words: String,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -19,3 +19,5 @@ pub mod english_5;
 
 pub mod korean_1;
 pub mod korean_2;
+
+pub mod whisper_hallucination;
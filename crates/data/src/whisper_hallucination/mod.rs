@@ -0,0 +1,6 @@
+pub const SEGMENTS_JSON: &str = include_str!("./segments.json");
+
+pub const SEGMENTS_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/whisper_hallucination/segments.json"
+);
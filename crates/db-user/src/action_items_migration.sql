@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS action_items (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  owner_id TEXT,
+  text TEXT NOT NULL,
+  due TEXT,
+  done INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL,
+  FOREIGN KEY (session_id) REFERENCES sessions (id) ON DELETE CASCADE,
+  FOREIGN KEY (owner_id) REFERENCES humans (id) ON DELETE SET NULL
+);
@@ -0,0 +1,331 @@
+use hypr_db_core::SqlTable;
+
+use super::{ActionItem, Decision, ListActionItemFilter, UserDatabase};
+
+impl UserDatabase {
+    pub async fn upsert_action_item(&self, item: ActionItem) -> Result<ActionItem, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "INSERT INTO {} (
+                id,
+                session_id,
+                owner_id,
+                text,
+                due,
+                done,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                owner_id = excluded.owner_id,
+                text = excluded.text,
+                due = excluded.due,
+                done = excluded.done
+            RETURNING *",
+            ActionItem::sql_table()
+        );
+
+        let mut rows = conn.query(&sql, action_item_params(&item)).await?;
+        let row = rows.next().await?.unwrap();
+        let item: ActionItem = libsql::de::from_row(&row)?;
+        Ok(item)
+    }
+
+    pub async fn delete_action_item(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!("DELETE FROM {} WHERE id = ?", ActionItem::sql_table());
+        conn.execute(&sql, vec![id.into()]).await?;
+        Ok(())
+    }
+
+    pub async fn list_action_items(
+        &self,
+        filter: ListActionItemFilter,
+    ) -> Result<Vec<ActionItem>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = match filter {
+            ListActionItemFilter::Session { session_id } => {
+                let sql = format!(
+                    "SELECT * FROM {} WHERE session_id = ? ORDER BY created_at ASC",
+                    ActionItem::sql_table()
+                );
+                conn.query(&sql, vec![session_id]).await?
+            }
+            ListActionItemFilter::Open {
+                user_id,
+                owner_id: None,
+            } => {
+                let sql = format!(
+                    "SELECT a.* FROM {} a JOIN sessions s ON s.id = a.session_id
+                    WHERE a.done = 0 AND s.user_id = ?
+                    ORDER BY a.due IS NULL, a.due ASC, a.created_at DESC",
+                    ActionItem::sql_table()
+                );
+                conn.query(&sql, vec![user_id]).await?
+            }
+            ListActionItemFilter::Open {
+                user_id,
+                owner_id: Some(owner_id),
+            } => {
+                let sql = format!(
+                    "SELECT a.* FROM {} a JOIN sessions s ON s.id = a.session_id
+                    WHERE a.done = 0 AND s.user_id = ? AND a.owner_id = ?
+                    ORDER BY a.due IS NULL, a.due ASC, a.created_at DESC",
+                    ActionItem::sql_table()
+                );
+                conn.query(&sql, vec![user_id, owner_id]).await?
+            }
+        };
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: ActionItem = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    // Replaces what was extracted from the session before.
+    // Action items already marked as done are kept, and not added again.
+    pub async fn replace_session_extraction(
+        &self,
+        session_id: impl Into<String>,
+        action_items: Vec<ActionItem>,
+        decisions: Vec<Decision>,
+    ) -> Result<(), crate::Error> {
+        let session_id = session_id.into();
+
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        let done = {
+            let sql = format!(
+                "SELECT text FROM {} WHERE session_id = ? AND done = 1",
+                ActionItem::sql_table()
+            );
+            let mut rows = tx.query(&sql, vec![session_id.clone()]).await?;
+
+            let mut texts = Vec::new();
+            while let Some(row) = rows.next().await? {
+                texts.push(row.get::<String>(0)?);
+            }
+            texts
+        };
+
+        tx.execute(
+            &format!(
+                "DELETE FROM {} WHERE session_id = ? AND done = 0",
+                ActionItem::sql_table()
+            ),
+            vec![session_id.clone()],
+        )
+        .await?;
+        tx.execute(
+            &format!("DELETE FROM {} WHERE session_id = ?", Decision::sql_table()),
+            vec![session_id.clone()],
+        )
+        .await?;
+
+        for item in action_items {
+            if done.contains(&item.text) {
+                continue;
+            }
+
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, session_id, owner_id, text, due, done, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    ActionItem::sql_table()
+                ),
+                action_item_params(&ActionItem {
+                    session_id: session_id.clone(),
+                    ..item
+                }),
+            )
+            .await?;
+        }
+
+        for decision in decisions {
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, session_id, text, created_at) VALUES (?, ?, ?, ?)",
+                    Decision::sql_table()
+                ),
+                vec![
+                    decision.id,
+                    session_id.clone(),
+                    decision.text,
+                    decision.created_at.to_rfc3339(),
+                ],
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+fn action_item_params(item: &ActionItem) -> impl libsql::params::IntoParams {
+    (
+        item.id.clone(),
+        item.session_id.clone(),
+        item.owner_id.clone(),
+        item.text.clone(),
+        item.due.map(|due| due.to_string()),
+        item.done,
+        item.created_at.to_rfc3339(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::setup_db, ActionItem, Decision, Human, ListActionItemFilter, Session};
+
+    #[tokio::test]
+    async fn test_action_items() {
+        let db = setup_db().await;
+
+        let human = db
+            .upsert_human(Human {
+                full_name: Some("Jane Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let session = |title: &str| Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: human.id.clone(),
+            created_at: chrono::Utc::now(),
+            visited_at: chrono::Utc::now(),
+            calendar_event_id: None,
+            title: title.to_string(),
+            raw_memo_html: "".to_string(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+            words: vec![],
+            record_start: None,
+            record_end: None,
+            language: None,
+        };
+        let first = db.upsert_session(session("First")).await.unwrap();
+        let second = db.upsert_session(session("Second")).await.unwrap();
+        let other_user = db.upsert_human(Human::default()).await.unwrap();
+        let other = db
+            .upsert_session(Session {
+                user_id: other_user.id.clone(),
+                ..session("Other")
+            })
+            .await
+            .unwrap();
+
+        db.replace_session_extraction(
+            &first.id,
+            vec![
+                ActionItem {
+                    owner_id: Some(human.id.clone()),
+                    due: chrono::NaiveDate::from_ymd_opt(2025, 1, 31),
+                    ..ActionItem::new(&first.id, "Send the deck")
+                },
+                ActionItem::new(&first.id, "Book a room"),
+            ],
+            vec![Decision::new(&first.id, "Ship on Friday")],
+        )
+        .await
+        .unwrap();
+        db.replace_session_extraction(
+            &second.id,
+            vec![ActionItem::new(&second.id, "Review the contract")],
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.replace_session_extraction(
+            &other.id,
+            vec![ActionItem::new(&other.id, "Someone else's")],
+            vec![],
+        )
+        .await
+        .unwrap();
+        let open = |owner_id: Option<String>| ListActionItemFilter::Open {
+            user_id: human.id.clone(),
+            owner_id,
+        };
+
+        let items = db
+            .list_action_items(ListActionItemFilter::Session {
+                session_id: first.id.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].due, chrono::NaiveDate::from_ymd_opt(2025, 1, 31));
+        assert_eq!(db.list_decisions(&first.id).await.unwrap().len(), 1);
+
+        let open_items = db.list_action_items(open(None)).await.unwrap();
+        assert_eq!(open_items.len(), 3);
+        assert_eq!(open_items[0].text, "Send the deck");
+
+        let owned = db
+            .list_action_items(open(Some(human.id.clone())))
+            .await
+            .unwrap();
+        assert_eq!(owned.len(), 1);
+
+        db.upsert_action_item(ActionItem {
+            done: true,
+            ..owned[0].clone()
+        })
+        .await
+        .unwrap();
+
+        // Extracting again keeps the done item, without adding it twice.
+        db.replace_session_extraction(
+            &first.id,
+            vec![
+                ActionItem::new(&first.id, "Send the deck"),
+                ActionItem::new(&first.id, "Call the vendor"),
+            ],
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let items = db
+            .list_action_items(ListActionItemFilter::Session {
+                session_id: first.id.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            items.iter().map(|i| i.text.as_str()).collect::<Vec<_>>(),
+            vec!["Send the deck", "Call the vendor"]
+        );
+        assert!(items[0].done);
+        assert!(db.list_decisions(&first.id).await.unwrap().is_empty());
+
+        db.delete_action_item(&items[1].id).await.unwrap();
+        assert_eq!(db.list_action_items(open(None)).await.unwrap().len(), 1);
+
+        // Deleting the owner keeps the item, unassigned.
+        let owner = db.upsert_human(Human::default()).await.unwrap();
+        db.upsert_action_item(ActionItem {
+            owner_id: Some(owner.id.clone()),
+            ..ActionItem::new(&second.id, "Renew the license")
+        })
+        .await
+        .unwrap();
+        db.delete_human(&owner.id).await.unwrap();
+
+        let items = db
+            .list_action_items(ListActionItemFilter::Session {
+                session_id: second.id.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| i.owner_id.is_none()));
+    }
+}
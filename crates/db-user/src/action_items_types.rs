@@ -0,0 +1,43 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("action_items")]
+    pub struct ActionItem {
+        pub id: String,
+        pub session_id: String,
+        pub owner_id: Option<String>,
+        pub text: String,
+        pub due: Option<NaiveDate>,
+        pub done: bool,
+        pub created_at: DateTime<Utc>,
+    }
+}
+
+user_common_derives! {
+    pub enum ListActionItemFilter {
+        #[serde(rename = "session")]
+        Session { session_id: String },
+        // Not done yet, across all sessions of the user.
+        #[serde(rename = "open")]
+        Open {
+            user_id: String,
+            owner_id: Option<String>,
+        },
+    }
+}
+
+impl ActionItem {
+    pub fn new(session_id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.into(),
+            owner_id: None,
+            text: text.into(),
+            due: None,
+            done: false,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS chat_message_sources (
+  id TEXT PRIMARY KEY,
+  message_id TEXT NOT NULL,
+  session_id TEXT NOT NULL,
+  citation INTEGER NOT NULL,
+  text TEXT NOT NULL,
+  start_ms INTEGER,
+  end_ms INTEGER,
+  FOREIGN KEY (message_id) REFERENCES chat_messages (id) ON DELETE CASCADE,
+  FOREIGN KEY (session_id) REFERENCES sessions (id) ON DELETE CASCADE
+);
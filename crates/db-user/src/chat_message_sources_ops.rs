@@ -0,0 +1,139 @@
+use hypr_db_core::SqlTable;
+
+use super::{ChatMessageSource, UserDatabase};
+
+impl UserDatabase {
+    pub async fn insert_chat_message_sources(
+        &self,
+        sources: Vec<ChatMessageSource>,
+    ) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        for source in sources {
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, message_id, session_id, citation, text, start_ms, end_ms) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    ChatMessageSource::sql_table()
+                ),
+                (
+                    source.id,
+                    source.message_id,
+                    source.session_id,
+                    source.citation as i64,
+                    source.text,
+                    source.start_ms.map(|ms| ms as i64),
+                    source.end_ms.map(|ms| ms as i64),
+                ),
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn list_chat_message_sources(
+        &self,
+        message_id: impl Into<String>,
+    ) -> Result<Vec<ChatMessageSource>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT * FROM {} WHERE message_id = ? ORDER BY citation ASC",
+                    ChatMessageSource::sql_table()
+                ),
+                vec![message_id.into()],
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: ChatMessageSource = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::setup_db, ChatGroup, ChatMessage, ChatMessageRole, ChatMessageSource, Human, Session,
+    };
+
+    #[tokio::test]
+    async fn test_chat_message_sources() {
+        let db = setup_db().await;
+
+        let human = db
+            .upsert_human(Human {
+                full_name: Some("John Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: human.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Weekly".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![],
+                record_start: None,
+                record_end: None,
+                language: None,
+            })
+            .await
+            .unwrap();
+
+        let group = db
+            .create_chat_group(ChatGroup {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: human.id.clone(),
+                name: None,
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let message = db
+            .upsert_chat_message(ChatMessage {
+                id: uuid::Uuid::new_v4().to_string(),
+                group_id: group.id.clone(),
+                created_at: chrono::Utc::now(),
+                role: ChatMessageRole::Assistant,
+                content: "We ship on Friday [2].".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let source = |citation: u8, start_ms: Option<u64>| ChatMessageSource {
+            id: uuid::Uuid::new_v4().to_string(),
+            message_id: message.id.clone(),
+            session_id: session.id.clone(),
+            citation,
+            text: "we ship on friday".to_string(),
+            start_ms,
+            end_ms: start_ms.map(|ms| ms + 2000),
+        };
+
+        db.insert_chat_message_sources(vec![source(2, Some(1000)), source(1, None)])
+            .await
+            .unwrap();
+
+        let sources = db.list_chat_message_sources(&message.id).await.unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].citation, 1);
+        assert_eq!(sources[1].start_ms, Some(1000));
+        assert_eq!(sources[1].end_ms, Some(3000));
+    }
+}
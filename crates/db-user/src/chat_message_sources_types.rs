@@ -0,0 +1,16 @@
+use crate::user_common_derives;
+
+// Part of a session that an assistant message cites.
+user_common_derives! {
+    #[sql_table("chat_message_sources")]
+    pub struct ChatMessageSource {
+        pub id: String,
+        pub message_id: String,
+        pub session_id: String,
+        // Number the message refers to the source with, as in "[1]".
+        pub citation: u8,
+        pub text: String,
+        pub start_ms: Option<u64>,
+        pub end_ms: Option<u64>,
+    }
+}
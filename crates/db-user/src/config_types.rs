@@ -44,6 +44,8 @@ user_common_derives! {
         pub jargons: Vec<String>,
         pub telemetry_consent: bool,
         pub save_recordings: Option<bool>,
+        pub language_detection: Option<hypr_listener_interface::LanguageDetection>,
+        pub multichannel: Option<bool>,
     }
 }
 
@@ -55,6 +57,8 @@ impl Default for ConfigGeneral {
             jargons: vec![],
             telemetry_consent: true,
             save_recordings: Some(true),
+            language_detection: None,
+            multichannel: None,
         }
     }
 }
@@ -0,0 +1,25 @@
+use hypr_db_core::SqlTable;
+
+use super::{Decision, UserDatabase};
+
+impl UserDatabase {
+    pub async fn list_decisions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<Decision>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY created_at ASC",
+            Decision::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: Decision = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
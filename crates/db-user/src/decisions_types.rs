@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("decisions")]
+    pub struct Decision {
+        pub id: String,
+        pub session_id: String,
+        pub text: String,
+        pub created_at: DateTime<Utc>,
+    }
+}
+
+impl Decision {
+    pub fn new(session_id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.into(),
+            text: text.into(),
+            created_at: Utc::now(),
+        }
+    }
+}
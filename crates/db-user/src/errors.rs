@@ -0,0 +1,38 @@
+use serde::{ser::Serializer, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    CoreError(#[from] hypr_db_core::Error),
+    #[error("no speaker index left in session {0}")]
+    SpeakerIndexOverflow(String),
+    #[error("embedding has {actual} dimensions, expected {expected}")]
+    EmbeddingDimensions { expected: usize, actual: usize },
+}
+
+impl From<libsql::Error> for Error {
+    fn from(e: libsql::Error) -> Self {
+        Self::CoreError(e.into())
+    }
+}
+
+impl From<serde::de::value::Error> for Error {
+    fn from(e: serde::de::value::Error) -> Self {
+        Self::CoreError(e.into())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::CoreError(e.into())
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
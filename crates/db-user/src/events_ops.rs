@@ -40,7 +40,8 @@ impl UserDatabase {
                     note,
                     start_date,
                     end_date,
-                    google_event_url
+                    google_event_url,
+                    participants
                 ) VALUES (
                     :id,
                     :user_id,
@@ -50,13 +51,15 @@ impl UserDatabase {
                     :note,
                     :start_date,
                     :end_date,
-                    :google_event_url
+                    :google_event_url,
+                    :participants
                 ) ON CONFLICT(tracking_id) DO UPDATE SET
                     name = :name,
                     note = :note,
                     start_date = :start_date,
                     end_date = :end_date,
-                    google_event_url = :google_event_url
+                    google_event_url = :google_event_url,
+                    participants = :participants
                 RETURNING *",
                 libsql::named_params! {
                     ":id": event.id,
@@ -68,6 +71,7 @@ impl UserDatabase {
                     ":start_date": event.start_date.to_rfc3339(),
                     ":end_date": event.end_date.to_rfc3339(),
                     ":google_event_url": event.google_event_url,
+                    ":participants": serde_json::to_string(&event.participants).unwrap(),
                 },
             )
             .await?;
@@ -145,7 +149,7 @@ impl UserDatabase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{tests::setup_db, Calendar, Human, Platform};
+    use crate::{tests::setup_db, Calendar, EventParticipant, Human, Platform};
 
     #[tokio::test]
     async fn test_events() {
@@ -193,11 +197,17 @@ mod tests {
             start_date: chrono::Utc::now(),
             end_date: chrono::Utc::now(),
             google_event_url: None,
+            participants: vec![EventParticipant {
+                name: "Alice Smith".to_string(),
+                email: Some("alice@example.com".to_string()),
+            }],
         };
 
         let event = db.upsert_event(event).await.unwrap();
         assert_eq!(event.tracking_id, "event_test");
         assert_eq!(event.google_event_url, None);
+        assert_eq!(event.participants.len(), 1);
+        assert_eq!(event.participants[0].name, "Alice Smith");
 
         let events = db.list_events(None).await.unwrap();
         assert_eq!(events.len(), 1);
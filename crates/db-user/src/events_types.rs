@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
 
 use crate::user_common_derives;
 
@@ -13,6 +14,24 @@ user_common_derives! {
         pub start_date: DateTime<Utc>,
         pub end_date: DateTime<Utc>,
         pub google_event_url: Option<String>,
+        #[serde(default, deserialize_with = "deserialize_participants")]
+        pub participants: Vec<EventParticipant>,
+    }
+}
+
+user_common_derives! {
+    pub struct EventParticipant {
+        pub name: String,
+        pub email: Option<String>,
+    }
+}
+
+impl From<hypr_calendar_interface::Participant> for EventParticipant {
+    fn from(participant: hypr_calendar_interface::Participant) -> Self {
+        Self {
+            name: participant.name,
+            email: participant.email,
+        }
     }
 }
 
@@ -45,3 +64,21 @@ user_common_derives! {
         NotAssignedPast {},
     }
 }
+
+// Stored as JSON text, but arrives as a plain array from the frontend.
+fn deserialize_participants<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<EventParticipant>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Json(String),
+        Value(Vec<EventParticipant>),
+    }
+
+    match Option::<Raw>::deserialize(deserializer)? {
+        None => Ok(vec![]),
+        Some(Raw::Json(s)) => serde_json::from_str(&s).map_err(serde::de::Error::custom),
+        Some(Raw::Value(v)) => Ok(v),
+    }
+}
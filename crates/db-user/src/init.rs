@@ -1,8 +1,8 @@
 use crate::{Config, ConfigAI, ConfigGeneral, ConfigNotification};
 
 use super::{
-    Calendar, ChatGroup, ChatMessage, ChatMessageRole, Event, Human, Organization, Platform,
-    Session, Tag, UserDatabase,
+    Calendar, ChatGroup, ChatMessage, ChatMessageRole, Event, EventParticipant, Human,
+    Organization, Platform, Session, Tag, UserDatabase,
 };
 
 const ONBOARDING_RAW_HTML: &str = include_str!("../assets/onboarding-raw.html");
@@ -57,6 +57,7 @@ pub async fn onboarding(db: &UserDatabase, user_id: impl Into<String>) -> Result
         start_date: chrono::Utc::now() + chrono::Duration::minutes(3),
         end_date: chrono::Utc::now() + chrono::Duration::minutes(10),
         google_event_url: None,
+        participants: vec![],
     };
 
     let onboarding_session_id = db.onboarding_session_id();
@@ -74,6 +75,7 @@ pub async fn onboarding(db: &UserDatabase, user_id: impl Into<String>) -> Result
         words: vec![],
         record_start: None,
         record_end: None,
+        language: None,
     };
 
     let onboarding_session = Session {
@@ -89,6 +91,7 @@ pub async fn onboarding(db: &UserDatabase, user_id: impl Into<String>) -> Result
         words: vec![],
         record_start: None,
         record_end: None,
+        language: None,
     };
 
     let _ = db.upsert_calendar(default_calendar).await?;
@@ -222,6 +225,16 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             start_date: now + chrono::Duration::minutes(15),
             end_date: now + chrono::Duration::minutes(30),
             google_event_url: None,
+            participants: vec![
+                EventParticipant {
+                    name: "Alice Smith".to_string(),
+                    email: Some("alice.smith@example.com".to_string()),
+                },
+                EventParticipant {
+                    name: "Bob Johnson".to_string(),
+                    email: Some("bob.johnson@example.com".to_string()),
+                },
+            ],
         },
         // --- Past Events for linking to NotesList ---
         // Last Month
@@ -235,6 +248,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             start_date: now - chrono::Duration::days(7),
             end_date: now - chrono::Duration::days(7) + chrono::Duration::hours(1),
             google_event_url: None,
+            participants: vec![],
         },
         // Ten Days Ago
         Event {
@@ -247,6 +261,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             start_date: now - chrono::Duration::days(10),
             end_date: now - chrono::Duration::days(10) + chrono::Duration::hours(2),
             google_event_url: None,
+            participants: vec![],
         },
         // Event without Session attached
         Event {
@@ -259,6 +274,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             start_date: now - chrono::Duration::days(14),
             end_date: now - chrono::Duration::days(14) + chrono::Duration::hours(1),
             google_event_url: None,
+            participants: vec![],
         },
         // Event without Session attached
         Event {
@@ -271,6 +287,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             start_date: now - chrono::Duration::days(10),
             end_date: now - chrono::Duration::days(10) + chrono::Duration::hours(1),
             google_event_url: None,
+            participants: vec![],
         },
         // Event without Session attached
         Event {
@@ -283,6 +300,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             start_date: now - chrono::Duration::days(1),
             end_date: now - chrono::Duration::days(1) + chrono::Duration::minutes(30),
             google_event_url: None,
+            participants: vec![],
         },
     ];
 
@@ -487,5 +505,6 @@ fn new_default_session(user_id: impl Into<String>) -> Session {
         words: vec![],
         record_start: None,
         record_end: None,
+        language: None,
     }
 }
@@ -1,11 +1,17 @@
+mod action_items_ops;
+mod action_items_types;
 mod calendars_ops;
 mod calendars_types;
 mod chat_groups_ops;
 mod chat_groups_types;
+mod chat_message_sources_ops;
+mod chat_message_sources_types;
 mod chat_messages_ops;
 mod chat_messages_types;
 mod config_ops;
 mod config_types;
+mod decisions_ops;
+mod decisions_types;
 mod events_ops;
 mod events_types;
 mod extensions_ops;
@@ -14,13 +20,21 @@ mod humans_ops;
 mod humans_types;
 mod organizations_ops;
 mod organizations_types;
+mod session_chunks_ops;
+mod session_chunks_types;
 mod sessions_ops;
 mod sessions_types;
 mod tags_ops;
 mod tags_types;
 mod templates_ops;
 mod templates_types;
+mod voice_profiles_ops;
+mod voice_profiles_types;
 
+#[allow(unused)]
+pub use action_items_ops::*;
+#[allow(unused)]
+pub use action_items_types::*;
 #[allow(unused)]
 pub use calendars_ops::*;
 #[allow(unused)]
@@ -30,6 +44,10 @@ pub use chat_groups_ops::*;
 #[allow(unused)]
 pub use chat_groups_types::*;
 #[allow(unused)]
+pub use chat_message_sources_ops::*;
+#[allow(unused)]
+pub use chat_message_sources_types::*;
+#[allow(unused)]
 pub use chat_messages_ops::*;
 #[allow(unused)]
 pub use chat_messages_types::*;
@@ -38,6 +56,10 @@ pub use config_ops::*;
 #[allow(unused)]
 pub use config_types::*;
 #[allow(unused)]
+pub use decisions_ops::*;
+#[allow(unused)]
+pub use decisions_types::*;
+#[allow(unused)]
 pub use events_ops::*;
 #[allow(unused)]
 pub use events_types::*;
@@ -54,6 +76,10 @@ pub use organizations_ops::*;
 #[allow(unused)]
 pub use organizations_types::*;
 #[allow(unused)]
+pub use session_chunks_ops::*;
+#[allow(unused)]
+pub use session_chunks_types::*;
+#[allow(unused)]
 pub use sessions_ops::*;
 #[allow(unused)]
 pub use sessions_types::*;
@@ -65,10 +91,16 @@ pub use tags_types::*;
 pub use templates_ops::*;
 #[allow(unused)]
 pub use templates_types::*;
+#[allow(unused)]
+pub use voice_profiles_ops::*;
+#[allow(unused)]
+pub use voice_profiles_types::*;
 
+mod errors;
 pub mod init;
 
-pub use hypr_db_core::{Database, Error};
+pub use errors::*;
+pub use hypr_db_core::Database;
 
 #[macro_export]
 macro_rules! user_common_derives {
@@ -129,7 +161,7 @@ impl std::ops::Deref for UserDatabase {
 }
 
 // Append only. Do not reorder.
-const MIGRATIONS: [&str; 17] = [
+const MIGRATIONS: [&str; 25] = [
     include_str!("./calendars_migration.sql"),
     include_str!("./configs_migration.sql"),
     include_str!("./events_migration.sql"),
@@ -147,6 +179,14 @@ const MIGRATIONS: [&str; 17] = [
     include_str!("./sessions_migration_1.sql"),
     include_str!("./sessions_migration_2.sql"),
     include_str!("./sessions_migration_3.sql"),
+    include_str!("./sessions_migration_4.sql"),
+    include_str!("./events_migration_1.sql"),
+    include_str!("./voice_profiles_migration.sql"),
+    include_str!("./speaker_suggestions_migration.sql"),
+    include_str!("./action_items_migration.sql"),
+    include_str!("./decisions_migration.sql"),
+    include_str!("./session_chunks_migration.sql"),
+    include_str!("./chat_message_sources_migration.sql"),
 ];
 
 pub async fn migrate(db: &UserDatabase) -> Result<(), crate::Error> {
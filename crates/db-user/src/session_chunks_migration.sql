@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS session_chunks (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  text TEXT NOT NULL,
+  embedding F32_BLOB(768) NOT NULL,
+  start_ms INTEGER,
+  end_ms INTEGER,
+  FOREIGN KEY (session_id) REFERENCES sessions (id) ON DELETE CASCADE
+);
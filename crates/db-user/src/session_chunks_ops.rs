@@ -0,0 +1,475 @@
+use hypr_db_core::SqlTable;
+
+use super::{
+    Session, SessionChunk, SessionChunkFilter, SessionChunkHit, SessionSearchHit, UserDatabase,
+    EMBEDDING_DIMENSIONS,
+};
+
+// libsql stores a vector of any size in the column, and only fails when comparing it at search time.
+fn check_embedding(embedding: &[f32]) -> Result<(), crate::Error> {
+    if embedding.len() != EMBEDDING_DIMENSIONS {
+        return Err(crate::Error::EmbeddingDimensions {
+            expected: EMBEDDING_DIMENSIONS,
+            actual: embedding.len(),
+        });
+    }
+    Ok(())
+}
+
+impl UserDatabase {
+    pub async fn replace_session_chunks(
+        &self,
+        session_id: impl Into<String>,
+        chunks: Vec<SessionChunk>,
+    ) -> Result<(), crate::Error> {
+        let session_id = session_id.into();
+        for chunk in &chunks {
+            check_embedding(&chunk.embedding)?;
+        }
+
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            &format!(
+                "DELETE FROM {} WHERE session_id = ?",
+                SessionChunk::sql_table()
+            ),
+            vec![session_id.clone()],
+        )
+        .await?;
+
+        for chunk in chunks {
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, session_id, kind, text, embedding, start_ms, end_ms) VALUES (?, ?, ?, ?, vector32(?), ?, ?)",
+                    SessionChunk::sql_table()
+                ),
+                (
+                    chunk.id,
+                    session_id.clone(),
+                    chunk.kind.to_string(),
+                    chunk.text,
+                    serde_json::to_string(&chunk.embedding).unwrap(),
+                    chunk.start_ms.map(|ms| ms as i64),
+                    chunk.end_ms.map(|ms| ms as i64),
+                ),
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Sessions ranked by their chunk closest to `embedding`, which must come from the same model as the chunks'.
+    pub async fn search_sessions(
+        &self,
+        user_id: impl Into<String>,
+        embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<SessionSearchHit>, crate::Error> {
+        check_embedding(embedding)?;
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT s.*, best.text, best.score FROM sessions s
+            JOIN (
+                SELECT
+                    c.session_id,
+                    c.text,
+                    1 - vector_distance_cos(c.embedding, vector32(:embedding)) AS score,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY c.session_id
+                        ORDER BY vector_distance_cos(c.embedding, vector32(:embedding))
+                    ) AS chunk_rank
+                FROM {} c
+                JOIN sessions u ON u.id = c.session_id
+                WHERE u.user_id = :user_id
+            ) best ON best.session_id = s.id AND best.chunk_rank = 1
+            ORDER BY best.score DESC
+            LIMIT :limit",
+            SessionChunk::sql_table()
+        );
+
+        let mut rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":user_id": user_id.into(),
+                    ":embedding": serde_json::to_string(embedding).unwrap(),
+                    ":limit": limit as i64,
+                },
+            )
+            .await?;
+
+        let mut hits = Vec::new();
+        while let Some(row) = rows.next().await? {
+            // `snippet` and `score` come after the columns of `sessions`.
+            let columns = row.column_count();
+
+            hits.push(SessionSearchHit {
+                session: Session::from_row(&row)?,
+                snippet: row.get(columns - 2)?,
+                score: row.get::<f64>(columns - 1)? as f32,
+            });
+        }
+        Ok(hits)
+    }
+
+    // Chunks closest to `embedding`, from the sessions matching the filter.
+    pub async fn search_session_chunks(
+        &self,
+        filter: SessionChunkFilter,
+        embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<SessionChunkHit>, crate::Error> {
+        check_embedding(embedding)?;
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT
+                c.id,
+                c.session_id,
+                c.kind,
+                c.text,
+                vector_extract(c.embedding) AS embedding,
+                c.start_ms,
+                c.end_ms,
+                s.title,
+                s.created_at,
+                1 - vector_distance_cos(c.embedding, vector32(:embedding)) AS score
+            FROM {} c
+            JOIN sessions s ON s.id = c.session_id
+            WHERE
+                s.user_id = :user_id
+                AND (:participant_id IS NULL OR EXISTS (
+                    SELECT 1 FROM session_participants p
+                    WHERE p.session_id = s.id AND p.human_id = :participant_id
+                ))
+                AND (:tag_id IS NULL OR EXISTS (
+                    SELECT 1 FROM tags_sessions t
+                    WHERE t.session_id = s.id AND t.tag_id = :tag_id
+                ))
+                AND (:start IS NULL OR s.created_at >= :start)
+                AND (:end IS NULL OR s.created_at <= :end)
+            ORDER BY score DESC
+            LIMIT :limit",
+            SessionChunk::sql_table()
+        );
+
+        let mut rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":user_id": filter.user_id,
+                    ":participant_id": filter.participant_id,
+                    ":tag_id": filter.tag_id,
+                    ":start": filter.start.map(|start| start.to_rfc3339()),
+                    ":end": filter.end.map(|end| end.to_rfc3339()),
+                    ":embedding": serde_json::to_string(embedding).unwrap(),
+                    ":limit": limit as i64,
+                },
+            )
+            .await?;
+
+        let mut hits = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let chunk: SessionChunk = libsql::de::from_row(&row)?;
+            let created_at = chrono::DateTime::parse_from_rfc3339(row.get_str(8)?)
+                .unwrap()
+                .with_timezone(&chrono::Utc);
+
+            hits.push(SessionChunkHit {
+                chunk,
+                title: row.get(7)?,
+                created_at,
+                score: row.get::<f64>(9)? as f32,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::setup_db, Human, ListSessionFilter, ListSessionFilterCommon,
+        ListSessionFilterSpecific, Session, SessionChunk, SessionChunkFilter, SessionChunkKind,
+        Tag, UserDatabase,
+    };
+    use hypr_listener_interface::Word;
+
+    fn session(user_id: &str, title: &str, note: &str, transcript: &str) -> Session {
+        Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            created_at: chrono::Utc::now(),
+            visited_at: chrono::Utc::now(),
+            calendar_event_id: None,
+            title: title.to_string(),
+            raw_memo_html: "".to_string(),
+            enhanced_memo_html: Some(note.to_string()),
+            conversations: vec![],
+            words: transcript
+                .split_whitespace()
+                .enumerate()
+                .map(|(i, text)| Word {
+                    text: text.to_string(),
+                    speaker: None,
+                    confidence: None,
+                    start_ms: Some(i as u64 * 1000),
+                    end_ms: Some(i as u64 * 1000 + 500),
+                })
+                .collect(),
+            record_start: None,
+            record_end: None,
+            language: None,
+        }
+    }
+
+    // Stand-in for embeddings: the first axis means "release", the second "hiring".
+    fn embedding(release: f32, hiring: f32) -> Vec<f32> {
+        let mut embedding = vec![0.0; 768];
+        embedding[0] = release;
+        embedding[1] = hiring;
+        embedding
+    }
+
+    async fn index(db: &UserDatabase, session: &Session, embeddings: [Vec<f32>; 2]) {
+        let mut chunks = SessionChunk::split(session);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].kind, SessionChunkKind::Note);
+
+        for (chunk, embedding) in chunks.iter_mut().zip(embeddings) {
+            chunk.embedding = embedding;
+        }
+        db.replace_session_chunks(&session.id, chunks)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions() {
+        let db = setup_db().await;
+
+        let user = db
+            .upsert_human(Human {
+                full_name: Some("John Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let release = db
+            .upsert_session(session(
+                &user.id,
+                "Weekly",
+                "<h1>Release</h1><p>Ship v2 on Friday</p>",
+                "we ship on friday",
+            ))
+            .await
+            .unwrap();
+        let hiring = db
+            .upsert_session(session(
+                &user.id,
+                "Hiring",
+                "<p>Two new engineers</p>",
+                "interviews next week",
+            ))
+            .await
+            .unwrap();
+
+        index(&db, &release, [embedding(1.0, 0.0), embedding(0.9, 0.1)]).await;
+        index(&db, &hiring, [embedding(0.1, 0.9), embedding(0.0, 1.0)]).await;
+
+        let hits = db
+            .search_sessions(&user.id, &embedding(1.0, 0.0), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].session.id, release.id);
+        assert_eq!(hits[0].snippet, "Release Ship v2 on Friday");
+        assert!(hits[0].score > hits[1].score);
+
+        // Without a model to embed the query, titles are matched instead.
+        let sessions = db
+            .list_sessions(Some(ListSessionFilter {
+                common: ListSessionFilterCommon {
+                    user_id: user.id.clone(),
+                    limit: Some(1),
+                },
+                specific: ListSessionFilterSpecific::Semantic {
+                    query: "Hiring".to_string(),
+                },
+            }))
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, hiring.id);
+
+        // Indexing again replaces the previous chunks.
+        db.replace_session_chunks(&release.id, vec![])
+            .await
+            .unwrap();
+        let hits = db
+            .search_sessions(&user.id, &embedding(1.0, 0.0), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_session_chunks() {
+        let db = setup_db().await;
+
+        let user = db
+            .upsert_human(Human {
+                full_name: Some("John Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+        let guest = db
+            .upsert_human(Human {
+                full_name: Some("Jane Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let release = db
+            .upsert_session(session(
+                &user.id,
+                "Weekly",
+                "<p>Ship v2 on Friday</p>",
+                "we ship on friday",
+            ))
+            .await
+            .unwrap();
+        let hiring = db
+            .upsert_session(session(
+                &user.id,
+                "Hiring",
+                "<p>Two new engineers</p>",
+                "interviews next week",
+            ))
+            .await
+            .unwrap();
+
+        index(&db, &release, [embedding(0.8, 0.2), embedding(1.0, 0.0)]).await;
+        index(&db, &hiring, [embedding(0.1, 0.9), embedding(0.0, 1.0)]).await;
+
+        let hits = db
+            .search_session_chunks(
+                SessionChunkFilter {
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                &embedding(1.0, 0.0),
+                3,
+            )
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].title, "Weekly");
+        assert_eq!(hits[0].chunk.text, "we ship on friday");
+        assert_eq!(hits[0].chunk.start_ms, Some(0));
+        assert_eq!(hits[0].chunk.end_ms, Some(3500));
+        assert_eq!(hits[0].chunk.embedding, embedding(1.0, 0.0));
+        assert_eq!(hits[1].chunk.kind, SessionChunkKind::Note);
+        assert_eq!(hits[1].chunk.start_ms, None);
+
+        db.session_add_participant(&hiring.id, &guest.id)
+            .await
+            .unwrap();
+        let hits = db
+            .search_session_chunks(
+                SessionChunkFilter {
+                    user_id: user.id.clone(),
+                    participant_id: Some(guest.id.clone()),
+                    ..Default::default()
+                },
+                &embedding(1.0, 0.0),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.chunk.session_id == hiring.id));
+
+        let tag = db
+            .upsert_tag(Tag {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: "release".to_string(),
+            })
+            .await
+            .unwrap();
+        db.assign_tag_to_session(&tag.id, &release.id)
+            .await
+            .unwrap();
+        let hits = db
+            .search_session_chunks(
+                SessionChunkFilter {
+                    user_id: user.id.clone(),
+                    tag_id: Some(tag.id.clone()),
+                    ..Default::default()
+                },
+                &embedding(0.0, 1.0),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.chunk.session_id == release.id));
+
+        let hits = db
+            .search_session_chunks(
+                SessionChunkFilter {
+                    user_id: user.id.clone(),
+                    start: Some(chrono::Utc::now() + chrono::Duration::days(1)),
+                    ..Default::default()
+                },
+                &embedding(1.0, 0.0),
+                10,
+            )
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_embedding_dimensions() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let session = db
+            .upsert_session(session(&user.id, "Weekly", "<p>Ship</p>", "we ship"))
+            .await
+            .unwrap();
+
+        let mut chunks = SessionChunk::split(&session);
+        chunks[0].embedding = vec![0.0; 384];
+        assert!(matches!(
+            db.replace_session_chunks(&session.id, chunks).await,
+            Err(crate::Error::EmbeddingDimensions { actual: 384, .. })
+        ));
+
+        assert!(matches!(
+            db.search_sessions(&user.id, &[1.0; 384], 10).await,
+            Err(crate::Error::EmbeddingDimensions { actual: 384, .. })
+        ));
+        assert!(matches!(
+            db.search_session_chunks(
+                SessionChunkFilter {
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                &[],
+                10
+            )
+            .await,
+            Err(crate::Error::EmbeddingDimensions { actual: 0, .. })
+        ));
+    }
+}
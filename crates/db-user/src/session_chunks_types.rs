@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+
+use crate::{user_common_derives, Session};
+
+// Words per chunk. Small enough for a snippet, and well within the context of embedding models.
+const CHUNK_WORDS: usize = 120;
+
+// The size of the `F32_BLOB` column.
+pub const EMBEDDING_DIMENSIONS: usize = 768;
+
+// A part of a session's transcript or note, embedded for semantic search.
+user_common_derives! {
+    #[sql_table("session_chunks")]
+    pub struct SessionChunk {
+        pub id: String,
+        pub session_id: String,
+        pub kind: SessionChunkKind,
+        pub text: String,
+        // `EMBEDDING_DIMENSIONS` long.
+        #[serde(deserialize_with = "crate::voice_profiles_types::deserialize_embedding")]
+        pub embedding: Vec<f32>,
+        // Only set for transcript chunks.
+        pub start_ms: Option<u64>,
+        pub end_ms: Option<u64>,
+    }
+}
+
+user_common_derives! {
+    #[derive(strum::Display)]
+    pub enum SessionChunkKind {
+        #[serde(rename = "transcript")]
+        #[strum(serialize = "transcript")]
+        Transcript,
+        #[serde(rename = "note")]
+        #[strum(serialize = "note")]
+        Note,
+    }
+}
+
+user_common_derives! {
+    #[derive(Default)]
+    pub struct SessionChunkFilter {
+        pub user_id: String,
+        pub participant_id: Option<String>,
+        pub tag_id: Option<String>,
+        pub start: Option<DateTime<Utc>>,
+        pub end: Option<DateTime<Utc>>,
+    }
+}
+
+user_common_derives! {
+    pub struct SessionChunkHit {
+        pub chunk: SessionChunk,
+        pub title: String,
+        pub created_at: DateTime<Utc>,
+        pub score: f32,
+    }
+}
+
+user_common_derives! {
+    pub struct SessionSearchHit {
+        pub session: Session,
+        // Text of the chunk that matched best.
+        pub snippet: String,
+        pub score: f32,
+    }
+}
+
+impl SessionChunk {
+    // Chunks of the session's note and transcript, with embeddings left empty.
+    pub fn split(session: &Session) -> Vec<Self> {
+        let note = session
+            .enhanced_memo_html
+            .as_deref()
+            .filter(|html| !html.is_empty())
+            .unwrap_or(&session.raw_memo_html);
+        let note = strip_tags(note);
+
+        let note_words = note
+            .split_whitespace()
+            .map(|text| (text, None, None))
+            .collect();
+        let transcript_words = session
+            .words
+            .iter()
+            .map(|word| (word.text.trim(), word.start_ms, word.end_ms))
+            .collect();
+
+        let note_chunks = split_words(note_words)
+            .into_iter()
+            .map(|chunk| (SessionChunkKind::Note, chunk));
+        let transcript_chunks = split_words(transcript_words)
+            .into_iter()
+            .map(|chunk| (SessionChunkKind::Transcript, chunk));
+
+        note_chunks
+            .chain(transcript_chunks)
+            .map(|(kind, (text, start_ms, end_ms))| Self {
+                id: uuid::Uuid::new_v4().to_string(),
+                session_id: session.id.clone(),
+                kind,
+                text,
+                embedding: vec![],
+                start_ms,
+                end_ms,
+            })
+            .collect()
+    }
+}
+
+type TimedWord<'a> = (&'a str, Option<u64>, Option<u64>);
+
+fn split_words(words: Vec<TimedWord>) -> Vec<(String, Option<u64>, Option<u64>)> {
+    words
+        .into_iter()
+        .filter(|(text, _, _)| !text.is_empty())
+        .collect::<Vec<_>>()
+        .chunks(CHUNK_WORDS)
+        .map(|chunk| {
+            let text = chunk
+                .iter()
+                .map(|(text, _, _)| *text)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let start_ms = chunk.iter().find_map(|(_, start_ms, _)| *start_ms);
+            let end_ms = chunk.iter().rev().find_map(|(_, _, end_ms)| *end_ms);
+
+            (text, start_ms, end_ms)
+        })
+        .collect()
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
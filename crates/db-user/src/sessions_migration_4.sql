@@ -0,0 +1,4 @@
+ALTER TABLE
+  sessions
+ADD
+  COLUMN language TEXT;
@@ -1,3 +1,6 @@
+use hypr_db_core::SqlTable;
+use hypr_listener_interface::{SpeakerIdentity, Word};
+
 use super::{
     Event, GetSessionFilter, Human, ListSessionFilter, ListSessionFilterCommon,
     ListSessionFilterSpecific, Session, UserDatabase,
@@ -165,6 +168,17 @@ impl UserDatabase {
                 )
                 .await?
             }
+            Some(ListSessionFilter {
+                common: ListSessionFilterCommon { user_id, limit },
+                specific: ListSessionFilterSpecific::Semantic { query },
+            }) => {
+                // Embedding the query needs a model, so the db plugin handles this before it gets here.
+                conn.query(
+                    "SELECT * FROM sessions WHERE user_id = ? AND title LIKE ? ORDER BY created_at DESC LIMIT ?",
+                    vec![user_id, format!("%{}%", query), limit.unwrap_or(100).to_string()],
+                )
+                .await?
+            }
             None => {
                 conn.query(
                     "SELECT * FROM sessions ORDER BY created_at DESC LIMIT 100",
@@ -199,7 +213,8 @@ impl UserDatabase {
                     conversations,
                     words,
                     record_start,
-                    record_end
+                    record_end,
+                    language
                 ) VALUES (
                     :id,
                     :created_at,
@@ -212,7 +227,8 @@ impl UserDatabase {
                     :conversations,
                     :words,
                     :record_start,
-                    :record_end
+                    :record_end,
+                    :language
                 )
                 ON CONFLICT(id) DO UPDATE SET
                     created_at = :created_at,
@@ -225,7 +241,8 @@ impl UserDatabase {
                     conversations = :conversations,
                     words = :words,
                     record_start = :record_start,
-                    record_end = :record_end
+                    record_end = :record_end,
+                    language = :language
                 RETURNING *",
                 libsql::named_params! {
                     ":id": session.id.clone(),
@@ -240,6 +257,7 @@ impl UserDatabase {
                     ":words": serde_json::to_string(&session.words).unwrap(),
                     ":record_start": session.record_start.map(|dt| dt.to_rfc3339()),
                     ":record_end": session.record_end.map(|dt| dt.to_rfc3339()),
+                    ":language": session.language.as_ref().map(|l| l.iso639().code().to_string()),
                 },
             )
             .await?;
@@ -345,11 +363,188 @@ impl UserDatabase {
             }
         }
     }
+
+    // Labels every word of the unassigned speaker as the human.
+    pub async fn session_rename_speaker(
+        &self,
+        session_id: impl Into<String>,
+        index: u8,
+        human_id: impl Into<String>,
+    ) -> Result<Option<Session>, crate::Error> {
+        let from = SpeakerIdentity::Unassigned { index };
+
+        self.session_update_words(session_id, Some(human_id.into()), None, |words, to| {
+            for word in words
+                .iter_mut()
+                .filter(|w| w.speaker.as_ref() == Some(&from))
+            {
+                word.speaker = to.cloned();
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    // Relabels the words of speaker `from` as speaker `into`, for when diarization split one person in two.
+    pub async fn session_merge_speakers(
+        &self,
+        session_id: impl Into<String>,
+        from: u8,
+        into: u8,
+    ) -> Result<Option<Session>, crate::Error> {
+        let from = SpeakerIdentity::Unassigned { index: from };
+        let into = SpeakerIdentity::Unassigned { index: into };
+
+        self.session_update_words(session_id, None, None, |words, _| {
+            for word in words
+                .iter_mut()
+                .filter(|w| w.speaker.as_ref() == Some(&from))
+            {
+                word.speaker = Some(into.clone());
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    // Moves the words of the speaker starting at `from_ms` to a new speaker index, for when diarization
+    // mistook a second person for the first. Fails if every index is taken.
+    pub async fn session_split_speaker(
+        &self,
+        session_id: impl Into<String>,
+        index: u8,
+        from_ms: u64,
+    ) -> Result<Option<Session>, crate::Error> {
+        let session_id = session_id.into();
+        let from = SpeakerIdentity::Unassigned { index };
+
+        self.session_update_words(session_id.clone(), None, None, |words, _| {
+            let next = match words
+                .iter()
+                .filter_map(|w| match w.speaker {
+                    Some(SpeakerIdentity::Unassigned { index }) => Some(index),
+                    _ => None,
+                })
+                .max()
+            {
+                None => 0,
+                Some(max) => max
+                    .checked_add(1)
+                    .ok_or(crate::Error::SpeakerIndexOverflow(session_id))?,
+            };
+
+            for word in words.iter_mut().filter(|w| {
+                w.speaker.as_ref() == Some(&from) && w.start_ms.is_some_and(|s| s >= from_ms)
+            }) {
+                word.speaker = Some(SpeakerIdentity::Unassigned { index: next });
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    // Labels the words in `start..end`, by position in the transcript, as the human.
+    pub async fn session_reassign_words(
+        &self,
+        session_id: impl Into<String>,
+        start: usize,
+        end: usize,
+        human_id: impl Into<String>,
+    ) -> Result<Option<Session>, crate::Error> {
+        self.session_update_words(session_id, Some(human_id.into()), None, |words, to| {
+            let end = end.min(words.len());
+            for word in words.iter_mut().take(end).skip(start) {
+                word.speaker = to.cloned();
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    // For the listener, whose copy of the session goes stale as soon as the user edits it. `language` is only
+    // set if none was detected yet. Returns `None` if the session doesn't exist.
+    pub async fn session_update_transcript(
+        &self,
+        session_id: impl Into<String>,
+        language: Option<hypr_language::Language>,
+        f: impl FnOnce(&mut Vec<Word>),
+    ) -> Result<Option<Session>, crate::Error> {
+        self.session_update_words(session_id, None, language, |words, _| {
+            f(words);
+            Ok(())
+        })
+        .await
+    }
+
+    // Edits the stored words in one transaction, so concurrent edits by the user and the listener aren't lost.
+    // `f` gets the human as a speaker when `human_id` is set. Returns `None` if the session or human doesn't exist.
+    async fn session_update_words(
+        &self,
+        session_id: impl Into<String>,
+        human_id: Option<String>,
+        language: Option<hypr_language::Language>,
+        f: impl FnOnce(&mut Vec<Word>, Option<&SpeakerIdentity>) -> Result<(), crate::Error>,
+    ) -> Result<Option<Session>, crate::Error> {
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        let speaker = match human_id {
+            None => None,
+            Some(human_id) => {
+                let sql = format!("SELECT * FROM {} WHERE id = ?", Human::sql_table());
+                let mut rows = tx.query(&sql, vec![human_id]).await?;
+                let human: Human = match rows.next().await? {
+                    Some(row) => libsql::de::from_row(&row)?,
+                    None => return Ok(None),
+                };
+
+                Some(SpeakerIdentity::Assigned {
+                    id: human.id,
+                    label: human.full_name.unwrap_or_default(),
+                })
+            }
+        };
+
+        let mut session = {
+            let mut rows = tx
+                .query(
+                    "SELECT * FROM sessions WHERE id = ?",
+                    vec![session_id.into()],
+                )
+                .await?;
+            match rows.next().await? {
+                Some(row) => Session::from_row(&row)?,
+                None => return Ok(None),
+            }
+        };
+
+        f(&mut session.words, speaker.as_ref())?;
+        if session.language.is_none() {
+            session.language = language;
+        }
+
+        tx.execute(
+            "UPDATE sessions SET words = ?, language = ? WHERE id = ?",
+            libsql::params![
+                serde_json::to_string(&session.words).unwrap(),
+                session
+                    .language
+                    .as_ref()
+                    .map(|l| l.iso639().code().to_string()),
+                session.id.clone(),
+            ],
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(session))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{tests::setup_db, Human, Session};
+    use hypr_listener_interface::{SpeakerIdentity, Word};
 
     #[tokio::test]
     async fn test_sessions() {
@@ -385,6 +580,7 @@ mod tests {
             }],
             record_start: None,
             record_end: None,
+            language: None,
         };
 
         let mut session = db.upsert_session(session).await.unwrap();
@@ -392,6 +588,11 @@ mod tests {
         assert_eq!(session.enhanced_memo_html, None);
         assert_eq!(session.title, "test");
         assert_eq!(session.words.len(), 1);
+        assert_eq!(session.language, None);
+
+        session.language = Some(hypr_language::ISO639::Ko.into());
+        let mut session = db.upsert_session(session).await.unwrap();
+        assert_eq!(session.language, Some(hypr_language::ISO639::Ko.into()));
 
         let sessions = db.list_sessions(None).await.unwrap();
         assert_eq!(sessions.len(), 1);
@@ -412,4 +613,142 @@ mod tests {
 
         assert_eq!(db.session_get_event(&session.id).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_session_speakers() {
+        let db = setup_db().await;
+
+        let user = db
+            .upsert_human(Human {
+                full_name: Some("John Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+        let jane = db
+            .upsert_human(Human {
+                full_name: Some("Jane Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let word = |start_ms: u64, index: u8| Word {
+            text: format!("word {}", start_ms),
+            speaker: Some(SpeakerIdentity::Unassigned { index }),
+            confidence: None,
+            start_ms: Some(start_ms),
+            end_ms: Some(start_ms + 100),
+        };
+        let speakers = |session: &Session| -> Vec<Option<SpeakerIdentity>> {
+            session.words.iter().map(|w| w.speaker.clone()).collect()
+        };
+        let unassigned = |index: u8| Some(SpeakerIdentity::Unassigned { index });
+        let jane_speaker = Some(SpeakerIdentity::Assigned {
+            id: jane.id.clone(),
+            label: "Jane Doe".to_string(),
+        });
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "test".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![word(0, 0), word(1000, 1), word(2000, 2), word(3000, 0)],
+                record_start: None,
+                record_end: None,
+                language: None,
+            })
+            .await
+            .unwrap();
+
+        let session = db
+            .session_merge_speakers(&session.id, 2, 1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            speakers(&session),
+            vec![unassigned(0), unassigned(1), unassigned(1), unassigned(0)]
+        );
+
+        let session = db
+            .session_split_speaker(&session.id, 0, 2500)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            speakers(&session),
+            vec![unassigned(0), unassigned(1), unassigned(1), unassigned(2)]
+        );
+
+        let session = db
+            .session_rename_speaker(&session.id, 1, &jane.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            speakers(&session),
+            vec![
+                unassigned(0),
+                jane_speaker.clone(),
+                jane_speaker.clone(),
+                unassigned(2)
+            ]
+        );
+
+        // The range is clamped to the transcript.
+        let session = db
+            .session_reassign_words(&session.id, 3, 10, &jane.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.words[3].speaker, jane_speaker);
+
+        let stored = db.get_words(&session.id).await.unwrap();
+        assert_eq!(stored, session.words);
+
+        assert!(db
+            .session_rename_speaker(&session.id, 0, "unknown")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(db
+            .session_merge_speakers("unknown", 0, 1)
+            .await
+            .unwrap()
+            .is_none());
+
+        // The listener only touches words and language, so edits made meanwhile survive.
+        db.upsert_session(Session {
+            title: "renamed".to_string(),
+            ..session.clone()
+        })
+        .await
+        .unwrap();
+        let session = db
+            .session_update_transcript(
+                &session.id,
+                Some(hypr_language::ISO639::Ko.into()),
+                |words| words.push(word(4000, 255)),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.title, "renamed");
+        assert_eq!(session.words.len(), 5);
+        assert_eq!(session.language, Some(hypr_language::ISO639::Ko.into()));
+
+        // Every index is taken once speaker 255 shows up.
+        assert!(matches!(
+            db.session_split_speaker(&session.id, 0, 0).await,
+            Err(crate::Error::SpeakerIndexOverflow(_))
+        ));
+    }
 }
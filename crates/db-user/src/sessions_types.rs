@@ -1,4 +1,6 @@
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::str::FromStr;
 
 use crate::user_common_derives;
 
@@ -18,6 +20,14 @@ user_common_derives! {
         pub words: Vec<hypr_listener_interface::Word>,
         pub record_start: Option<DateTime<Utc>>,
         pub record_end: Option<DateTime<Utc>>,
+        #[specta(type = Option<String>)]
+        #[schemars(with = "Option<String>")]
+        #[serde(
+            default,
+            serialize_with = "serialize_language",
+            deserialize_with = "deserialize_language"
+        )]
+        pub language: Option<hypr_language::Language>,
     }
 }
 
@@ -57,6 +67,11 @@ impl Session {
                     .map(|dt| dt.with_timezone(&Utc))
                     .ok()
             }),
+            language: row
+                .get_str(12)
+                .ok()
+                .and_then(|str| hypr_language::ISO639::from_str(str).ok())
+                .map(Into::into),
         })
     }
 
@@ -105,5 +120,29 @@ user_common_derives! {
         RecentlyVisited {},
         #[serde(rename = "dateRange")]
         DateRange { start: DateTime<Utc>, end: DateTime<Utc> },
+        // Ranked by similarity to the query, once the db plugin has embedded it. See `search_sessions`.
+        #[serde(rename = "semantic")]
+        Semantic { query: String },
     }
 }
+
+fn serialize_language<S: serde::Serializer>(
+    lang: &Option<hypr_language::Language>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match lang {
+        Some(lang) => serializer.serialize_str(lang.iso639().code()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_language<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<hypr_language::Language>, D::Error> {
+    let str = Option::<String>::deserialize(deserializer)?;
+    str.map(|s| {
+        let iso639 = hypr_language::ISO639::from_str(&s).map_err(serde::de::Error::custom)?;
+        Ok(iso639.into())
+    })
+    .transpose()
+}
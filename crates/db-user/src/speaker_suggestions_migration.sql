@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS speaker_suggestions (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  speaker_index INTEGER NOT NULL,
+  human_id TEXT NOT NULL,
+  similarity REAL NOT NULL,
+  embedding TEXT NOT NULL,
+  FOREIGN KEY (session_id) REFERENCES sessions (id) ON DELETE CASCADE,
+  FOREIGN KEY (human_id) REFERENCES humans (id) ON DELETE CASCADE,
+  UNIQUE (session_id, speaker_index)
+);
@@ -123,6 +123,7 @@ mod tests {
                 words: vec![],
                 record_start: None,
                 record_end: None,
+                language: None,
             })
             .await
             .unwrap();
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS voice_profiles (
+  id TEXT PRIMARY KEY,
+  human_id TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  embedding TEXT NOT NULL,
+  FOREIGN KEY (human_id) REFERENCES humans (id) ON DELETE CASCADE
+);
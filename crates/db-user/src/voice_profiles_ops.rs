@@ -0,0 +1,382 @@
+use hypr_db_core::SqlTable;
+use hypr_listener_interface::SpeakerIdentity;
+
+use super::{Human, Session, SpeakerSuggestion, UserDatabase, VoiceProfile};
+
+impl UserDatabase {
+    pub async fn upsert_voice_profile(
+        &self,
+        profile: VoiceProfile,
+    ) -> Result<VoiceProfile, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "INSERT INTO {} (
+                id,
+                human_id,
+                created_at,
+                embedding
+            ) VALUES (?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                human_id = excluded.human_id,
+                embedding = excluded.embedding
+            RETURNING *",
+            VoiceProfile::sql_table()
+        );
+
+        let params = (
+            profile.id,
+            profile.human_id,
+            profile.created_at.to_rfc3339(),
+            serde_json::to_string(&profile.embedding).unwrap(),
+        );
+
+        let mut rows = conn.query(&sql, params).await?;
+        let row = rows.next().await?.unwrap();
+        let profile: VoiceProfile = libsql::de::from_row(&row)?;
+        Ok(profile)
+    }
+
+    pub async fn delete_voice_profile(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!("DELETE FROM {} WHERE id = ?", VoiceProfile::sql_table());
+        conn.execute(&sql, vec![id.into()]).await?;
+        Ok(())
+    }
+
+    pub async fn list_voice_profiles(
+        &self,
+        human_id: Option<String>,
+    ) -> Result<Vec<VoiceProfile>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = match human_id {
+            None => {
+                let sql = format!("SELECT * FROM {}", VoiceProfile::sql_table());
+                conn.query(&sql, ()).await?
+            }
+            Some(human_id) => {
+                let sql = format!(
+                    "SELECT * FROM {} WHERE human_id = ? ORDER BY created_at ASC",
+                    VoiceProfile::sql_table()
+                );
+                conn.query(&sql, vec![human_id]).await?
+            }
+        };
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: VoiceProfile = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    pub async fn upsert_speaker_suggestion(
+        &self,
+        suggestion: SpeakerSuggestion,
+    ) -> Result<SpeakerSuggestion, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "INSERT INTO {} (
+                id,
+                session_id,
+                speaker_index,
+                human_id,
+                similarity,
+                embedding
+            ) VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (session_id, speaker_index) DO UPDATE SET
+                human_id = excluded.human_id,
+                similarity = excluded.similarity,
+                embedding = excluded.embedding
+            RETURNING *",
+            SpeakerSuggestion::sql_table()
+        );
+
+        let params = libsql::params![
+            suggestion.id,
+            suggestion.session_id,
+            suggestion.speaker_index as i64,
+            suggestion.human_id,
+            suggestion.similarity as f64,
+            serde_json::to_string(&suggestion.embedding).unwrap(),
+        ];
+
+        let mut rows = conn.query(&sql, params).await?;
+        let row = rows.next().await?.unwrap();
+        let suggestion: SpeakerSuggestion = libsql::de::from_row(&row)?;
+        Ok(suggestion)
+    }
+
+    pub async fn list_speaker_suggestions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<SpeakerSuggestion>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY speaker_index ASC",
+            SpeakerSuggestion::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: SpeakerSuggestion = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    pub async fn reject_speaker_suggestion(
+        &self,
+        id: impl Into<String>,
+    ) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "DELETE FROM {} WHERE id = ?",
+            SpeakerSuggestion::sql_table()
+        );
+        conn.execute(&sql, vec![id.into()]).await?;
+        Ok(())
+    }
+
+    // Speaker indices change when a session is diarized again, which makes its suggestions stale.
+    pub async fn clear_speaker_suggestions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "DELETE FROM {} WHERE session_id = ?",
+            SpeakerSuggestion::sql_table()
+        );
+        conn.execute(&sql, vec![session_id.into()]).await?;
+        Ok(())
+    }
+
+    // Labels the suggested speaker's words as the human, and enrolls the voice so it is recognized next time.
+    pub async fn accept_speaker_suggestion(
+        &self,
+        id: impl Into<String>,
+    ) -> Result<Option<Session>, crate::Error> {
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        let suggestion: SpeakerSuggestion = {
+            let sql = format!(
+                "SELECT * FROM {} WHERE id = ?",
+                SpeakerSuggestion::sql_table()
+            );
+            let mut rows = tx.query(&sql, vec![id.into()]).await?;
+            match rows.next().await? {
+                Some(row) => libsql::de::from_row(&row)?,
+                None => return Ok(None),
+            }
+        };
+
+        let human: Human = {
+            let sql = format!("SELECT * FROM {} WHERE id = ?", Human::sql_table());
+            let mut rows = tx.query(&sql, vec![suggestion.human_id.clone()]).await?;
+            match rows.next().await? {
+                Some(row) => libsql::de::from_row(&row)?,
+                None => return Ok(None),
+            }
+        };
+
+        let mut session = {
+            let mut rows = tx
+                .query(
+                    "SELECT * FROM sessions WHERE id = ?",
+                    vec![suggestion.session_id.clone()],
+                )
+                .await?;
+            match rows.next().await? {
+                Some(row) => Session::from_row(&row)?,
+                None => return Ok(None),
+            }
+        };
+
+        let from = SpeakerIdentity::Unassigned {
+            index: suggestion.speaker_index,
+        };
+        let to = SpeakerIdentity::Assigned {
+            id: human.id.clone(),
+            label: human.full_name.unwrap_or_default(),
+        };
+        for word in &mut session.words {
+            if word.speaker.as_ref() == Some(&from) {
+                word.speaker = Some(to.clone());
+            }
+        }
+
+        tx.execute(
+            "UPDATE sessions SET words = ? WHERE id = ?",
+            vec![
+                serde_json::to_string(&session.words).unwrap(),
+                session.id.clone(),
+            ],
+        )
+        .await?;
+
+        let profile = VoiceProfile::new(human.id, suggestion.embedding);
+        tx.execute(
+            &format!(
+                "INSERT INTO {} (id, human_id, created_at, embedding) VALUES (?, ?, ?, ?)",
+                VoiceProfile::sql_table()
+            ),
+            vec![
+                profile.id,
+                profile.human_id,
+                profile.created_at.to_rfc3339(),
+                serde_json::to_string(&profile.embedding).unwrap(),
+            ],
+        )
+        .await?;
+
+        tx.execute(
+            &format!(
+                "DELETE FROM {} WHERE id = ?",
+                SpeakerSuggestion::sql_table()
+            ),
+            vec![suggestion.id],
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(session))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::setup_db, Human, Session, SpeakerSuggestion, VoiceProfile};
+    use hypr_listener_interface::{SpeakerIdentity, Word};
+
+    #[tokio::test]
+    async fn test_voice_profiles() {
+        let db = setup_db().await;
+
+        let human = db
+            .upsert_human(Human {
+                full_name: Some("Jane Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let profile = db
+            .upsert_voice_profile(VoiceProfile::new(&human.id, vec![0.1, 0.2, 0.3]))
+            .await
+            .unwrap();
+        assert_eq!(profile.embedding, vec![0.1, 0.2, 0.3]);
+
+        assert_eq!(db.list_voice_profiles(None).await.unwrap().len(), 1);
+        assert_eq!(
+            db.list_voice_profiles(Some(human.id.clone()))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        db.delete_voice_profile(profile.id).await.unwrap();
+        assert_eq!(db.list_voice_profiles(None).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_speaker_suggestions() {
+        let db = setup_db().await;
+
+        let human = db
+            .upsert_human(Human {
+                full_name: Some("Jane Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let word = |text: &str, index: u8| Word {
+            text: text.to_string(),
+            speaker: Some(SpeakerIdentity::Unassigned { index }),
+            confidence: None,
+            start_ms: None,
+            end_ms: None,
+        };
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: human.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Test Session".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![word("hello", 0), word("hi", 1)],
+                record_start: None,
+                record_end: None,
+                language: None,
+            })
+            .await
+            .unwrap();
+
+        let suggestion = |similarity: f32| SpeakerSuggestion {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session.id.clone(),
+            speaker_index: 1,
+            human_id: human.id.clone(),
+            similarity,
+            embedding: vec![0.1, 0.2, 0.3],
+        };
+
+        db.upsert_speaker_suggestion(suggestion(0.5)).await.unwrap();
+        // One suggestion per speaker, updated as the speaker's voice gets clearer.
+        db.upsert_speaker_suggestion(suggestion(0.6)).await.unwrap();
+
+        let suggestions = db.list_speaker_suggestions(&session.id).await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].similarity, 0.6);
+
+        db.clear_speaker_suggestions(&session.id).await.unwrap();
+        assert!(db
+            .list_speaker_suggestions(&session.id)
+            .await
+            .unwrap()
+            .is_empty());
+        let updated = db.upsert_speaker_suggestion(suggestion(0.6)).await.unwrap();
+
+        let session = db
+            .accept_speaker_suggestion(updated.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.words[0], word("hello", 0));
+        assert_eq!(
+            session.words[1].speaker,
+            Some(SpeakerIdentity::Assigned {
+                id: human.id.clone(),
+                label: "Jane Doe".to_string(),
+            })
+        );
+
+        let stored = db.get_session(crate::GetSessionFilter::Id(session.id.clone()));
+        assert_eq!(stored.await.unwrap().unwrap().words, session.words);
+        assert!(db
+            .list_speaker_suggestions(&session.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.list_voice_profiles(Some(human.id)).await.unwrap().len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("voice_profiles")]
+    pub struct VoiceProfile {
+        pub id: String,
+        pub human_id: String,
+        pub created_at: DateTime<Utc>,
+        #[serde(deserialize_with = "deserialize_embedding")]
+        pub embedding: Vec<f32>,
+    }
+}
+
+// A diarized speaker that looks like a known human, but not enough to label them without asking.
+user_common_derives! {
+    #[sql_table("speaker_suggestions")]
+    pub struct SpeakerSuggestion {
+        pub id: String,
+        pub session_id: String,
+        pub speaker_index: u8,
+        pub human_id: String,
+        pub similarity: f32,
+        #[serde(deserialize_with = "deserialize_embedding")]
+        pub embedding: Vec<f32>,
+    }
+}
+
+impl VoiceProfile {
+    pub fn new(human_id: impl Into<String>, embedding: Vec<f32>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            human_id: human_id.into(),
+            created_at: Utc::now(),
+            embedding,
+        }
+    }
+}
+
+// Stored as JSON text, but arrives as a plain array from the frontend.
+pub(crate) fn deserialize_embedding<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<f32>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Json(String),
+        Value(Vec<f32>),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Json(s) => serde_json::from_str(&s).map_err(serde::de::Error::custom),
+        Raw::Value(v) => Ok(v),
+    }
+}
@@ -0,0 +1,411 @@
+use std::path::{Path, PathBuf};
+
+use futures_util::StreamExt;
+use reqwest::{header::RANGE, StatusCode};
+use tokio::{io::AsyncWriteExt, sync::Semaphore};
+
+// Model downloads are large, so running many at once only splits the bandwidth.
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+
+static DOWNLOAD_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_DOWNLOADS);
+
+pub enum DownloadProgress {
+    Started,
+    Progress(u64, u64),
+    Finished,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Checksum {
+    Crc32(u32),
+    // Lowercase hex.
+    Sha256(String),
+}
+
+// What the downloaded file is expected to be. Unknown fields are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    pub size: Option<u64>,
+    pub checksum: Option<Checksum>,
+}
+
+impl Manifest {
+    pub fn builder() -> Self {
+        Self::default()
+    }
+
+    pub fn size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn checksum(mut self, checksum: impl Into<Option<Checksum>>) -> Self {
+        self.checksum = checksum.into();
+        self
+    }
+
+    pub fn verify(&self, path: impl AsRef<Path>) -> Result<(), crate::Error> {
+        let path = path.as_ref();
+
+        if let Some(expected) = self.size {
+            let actual = crate::file_size(path)?;
+            if actual != expected {
+                return Err(crate::Error::SizeMismatch(expected, actual));
+            }
+        }
+
+        let matches = match &self.checksum {
+            Some(Checksum::Crc32(expected)) => crate::calculate_file_checksum(path)? == *expected,
+            Some(Checksum::Sha256(expected)) => {
+                crate::calculate_file_sha256(path)?.eq_ignore_ascii_case(expected)
+            }
+            None => true,
+        };
+
+        if !matches {
+            return Err(crate::Error::ChecksumMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+// Size and SHA-256 of a Git LFS file on Hugging Face, from the headers of its `resolve` URL.
+// The URL redirects to the CDN, so the redirect itself is what carries them.
+pub async fn huggingface_manifest(url: impl reqwest::IntoUrl) -> Result<Manifest, crate::Error> {
+    let url = url.into_url()?;
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let res = client.head(url.clone()).send().await?;
+    if !res.status().is_redirection() {
+        res.error_for_status_ref()?;
+    }
+
+    let header = |name: &str| res.headers().get(name).and_then(|v| v.to_str().ok());
+
+    let size = header("x-linked-size").and_then(|v| v.parse().ok());
+    let sha256 = header("x-linked-etag")
+        .map(|v| v.trim_matches('"').to_ascii_lowercase())
+        .filter(|v| v.len() == 64 && v.bytes().all(|b| b.is_ascii_hexdigit()));
+
+    match (size, sha256) {
+        (Some(size), Some(sha256)) => Ok(Manifest::builder()
+            .size(size)
+            .checksum(Checksum::Sha256(sha256))),
+        _ => Err(crate::Error::NotLfsFile(url.to_string())),
+    }
+}
+
+// Where a download is written until it is complete and verified.
+pub fn partial_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut name = path.as_ref().file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.as_ref().with_file_name(name)
+}
+
+pub async fn download_file_with_callback<F: Fn(DownloadProgress)>(
+    url: impl reqwest::IntoUrl,
+    output_path: impl AsRef<Path>,
+    progress_callback: F,
+) -> Result<(), crate::Error> {
+    download_file_with_manifest(url, output_path, &Manifest::default(), progress_callback).await
+}
+
+// Resumes from a previous partial download if the server supports range requests,
+// and only moves the file to `output_path` once it matches `manifest`.
+pub async fn download_file_with_manifest<F: Fn(DownloadProgress)>(
+    url: impl reqwest::IntoUrl,
+    output_path: impl AsRef<Path>,
+    manifest: &Manifest,
+    progress_callback: F,
+) -> Result<(), crate::Error> {
+    let url = url.into_url()?;
+    let output_path = output_path.as_ref();
+    let partial_path = partial_path(output_path);
+
+    let _permit = DOWNLOAD_PERMITS
+        .acquire()
+        .await
+        .map_err(|e| crate::Error::OtherError(e.to_string()))?;
+
+    if let Some(parent) = output_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut downloaded = match tokio::fs::metadata(&partial_path).await {
+        Ok(metadata)
+            if manifest
+                .size
+                .is_none_or(|expected| metadata.len() <= expected) =>
+        {
+            metadata.len()
+        }
+        _ => 0,
+    };
+
+    progress_callback(DownloadProgress::Started);
+
+    if manifest.size != Some(downloaded) {
+        let mut req = reqwest::Client::new().get(url);
+        if downloaded > 0 {
+            req = req.header(RANGE, format!("bytes={}-", downloaded));
+        }
+        let res = req.send().await?;
+
+        // Nothing left to fetch. Verification below decides whether the partial file is usable.
+        if res.status() != StatusCode::RANGE_NOT_SATISFIABLE {
+            let res = res.error_for_status()?;
+
+            // Servers without range support send the whole file again.
+            if res.status() != StatusCode::PARTIAL_CONTENT {
+                downloaded = 0;
+            }
+
+            let total_size = res
+                .content_length()
+                .map(|len| len + downloaded)
+                .or(manifest.size)
+                .unwrap_or(u64::MAX);
+
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(downloaded > 0)
+                .truncate(downloaded == 0)
+                .open(&partial_path)
+                .await?;
+
+            let mut stream = res.bytes_stream();
+            while let Some(item) = stream.next().await {
+                let chunk = item?;
+                file.write_all(&chunk).await?;
+
+                downloaded += chunk.len() as u64;
+                progress_callback(DownloadProgress::Progress(downloaded, total_size));
+            }
+
+            file.flush().await?;
+        }
+    }
+
+    // Hashing a model takes seconds, so it runs off the async workers.
+    let verified = {
+        let manifest = manifest.clone();
+        let partial_path = partial_path.clone();
+        tokio::task::spawn_blocking(move || manifest.verify(&partial_path))
+            .await
+            .map_err(|e| crate::Error::OtherError(e.to_string()))?
+    };
+
+    if let Err(e) = verified {
+        let _ = tokio::fs::remove_file(&partial_path).await;
+        return Err(e);
+    }
+
+    tokio::fs::rename(&partial_path, output_path).await?;
+    progress_callback(DownloadProgress::Finished);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, head},
+        Router,
+    };
+
+    #[derive(Clone)]
+    struct FileServer {
+        content: Arc<Vec<u8>>,
+        supports_range: bool,
+        ranges: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    async fn serve(State(server): State<FileServer>, headers: HeaderMap) -> impl IntoResponse {
+        let range = headers.get(RANGE).map(|v| v.to_str().unwrap().to_string());
+        server.ranges.lock().unwrap().push(range.clone());
+
+        let start = range
+            .filter(|_| server.supports_range)
+            .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse().ok());
+
+        match start {
+            Some(start) if start >= server.content.len() => {
+                StatusCode::RANGE_NOT_SATISFIABLE.into_response()
+            }
+            Some(start) => (
+                StatusCode::PARTIAL_CONTENT,
+                server.content[start..].to_vec(),
+            )
+                .into_response(),
+            None => (StatusCode::OK, server.content.to_vec()).into_response(),
+        }
+    }
+
+    async fn run_server(server: FileServer) -> String {
+        let app = Router::new()
+            .route("/model.bin", get(serve))
+            .with_state(server);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{}/model.bin", addr)
+    }
+
+    fn file_server(supports_range: bool) -> FileServer {
+        FileServer {
+            content: Arc::new((0..100_000u32).map(|i| (i % 251) as u8).collect()),
+            supports_range,
+            ranges: Default::default(),
+        }
+    }
+
+    fn manifest(content: &[u8]) -> Manifest {
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(content);
+
+        Manifest::builder()
+            .size(content.len() as u64)
+            .checksum(Checksum::Crc32(hasher.finalize()))
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file() {
+        let server = file_server(true);
+        let url = run_server(server.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("model.bin");
+        std::fs::write(partial_path(&output_path), &server.content[..30_000]).unwrap();
+
+        download_file_with_manifest(&url, &output_path, &manifest(&server.content), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&output_path).unwrap(), *server.content);
+        assert!(!partial_path(&output_path).exists());
+        assert_eq!(
+            *server.ranges.lock().unwrap(),
+            vec![Some("bytes=30000-".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_without_range_support() {
+        let server = file_server(false);
+        let url = run_server(server.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("model.bin");
+        std::fs::write(partial_path(&output_path), vec![0u8; 30_000]).unwrap();
+
+        download_file_with_manifest(&url, &output_path, &manifest(&server.content), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&output_path).unwrap(), *server.content);
+    }
+
+    #[tokio::test]
+    async fn test_download_skips_request_for_complete_partial_file() {
+        let server = file_server(true);
+        let url = run_server(server.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("model.bin");
+        std::fs::write(partial_path(&output_path), &*server.content).unwrap();
+
+        download_file_with_manifest(&url, &output_path, &manifest(&server.content), |_| {})
+            .await
+            .unwrap();
+
+        assert!(output_path.exists());
+        assert!(server.ranges.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_rejects_checksum_mismatch() {
+        let server = file_server(true);
+        let url = run_server(server.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("model.bin");
+        let manifest = Manifest::builder().checksum(Checksum::Sha256("0".repeat(64)));
+
+        let result = download_file_with_manifest(&url, &output_path, &manifest, |_| {}).await;
+
+        assert!(matches!(result, Err(crate::Error::ChecksumMismatch)));
+        assert!(!output_path.exists());
+        assert!(!partial_path(&output_path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_reports_progress() {
+        let server = file_server(true);
+        let url = run_server(server.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("model.bin");
+
+        let last = Mutex::new(None);
+        download_file_with_callback(&url, &output_path, |progress| {
+            if let DownloadProgress::Progress(downloaded, total) = progress {
+                *last.lock().unwrap() = Some((downloaded, total));
+            }
+        })
+        .await
+        .unwrap();
+
+        let len = server.content.len() as u64;
+        assert_eq!(*last.lock().unwrap(), Some((len, len)));
+    }
+
+    #[tokio::test]
+    async fn test_huggingface_manifest() {
+        let sha256 = "a".repeat(64);
+
+        let app = Router::new()
+            .route(
+                "/lfs.bin",
+                head({
+                    let sha256 = sha256.clone();
+                    move || async move {
+                        (
+                            StatusCode::FOUND,
+                            [
+                                ("location", "https://cdn-lfs.hf.co/lfs.bin".to_string()),
+                                ("x-linked-size", "100000".to_string()),
+                                ("x-linked-etag", format!("\"{}\"", sha256.to_uppercase())),
+                            ],
+                        )
+                    }
+                }),
+            )
+            .route("/small.json", head(|| async { StatusCode::OK }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let manifest = huggingface_manifest(format!("http://{}/lfs.bin", addr))
+            .await
+            .unwrap();
+        assert_eq!(manifest.size, Some(100_000));
+        assert_eq!(manifest.checksum, Some(Checksum::Sha256(sha256)));
+
+        let result = huggingface_manifest(format!("http://{}/small.json", addr)).await;
+        assert!(matches!(result, Err(crate::Error::NotLfsFile(_))));
+    }
+}
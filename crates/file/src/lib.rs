@@ -1,56 +1,20 @@
+mod download;
 mod local;
 mod remote;
 mod types;
 
+pub use download::*;
 pub use local::*;
 pub use remote::*;
 pub use types::*;
 
-use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
 use std::{
     fs::File,
-    io::{BufReader, Read, Write},
+    io::{BufReader, Read},
     path::Path,
 };
 
-pub enum DownloadProgress {
-    Started,
-    Progress(u64, u64),
-    Finished,
-}
-
-pub async fn download_file_with_callback<F: Fn(DownloadProgress)>(
-    url: impl reqwest::IntoUrl,
-    output_path: impl AsRef<Path>,
-    progress_callback: F,
-) -> Result<(), crate::Error> {
-    let client = reqwest::Client::new();
-
-    let res = client.get(url.into_url()?).send().await?;
-    let total_size = res.content_length().unwrap_or(u64::MAX);
-
-    if let Some(parent) = output_path.as_ref().parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let mut file = std::fs::File::create(output_path.as_ref())?;
-    let mut downloaded: u64 = 0;
-    let mut stream = res.bytes_stream();
-
-    progress_callback(DownloadProgress::Started);
-    while let Some(item) = stream.next().await {
-        let chunk = item?;
-        file.write_all(&chunk)?;
-
-        downloaded += chunk.len() as u64;
-        progress_callback(DownloadProgress::Progress(downloaded, total_size));
-    }
-
-    progress_callback(DownloadProgress::Finished);
-
-    Ok(())
-}
-
 pub fn file_size(path: impl AsRef<Path>) -> Result<u64, Error> {
     let metadata = std::fs::metadata(path.as_ref())?;
     Ok(metadata.len())
@@ -75,6 +39,14 @@ pub fn calculate_file_checksum(path: impl AsRef<Path>) -> Result<u32, Error> {
     Ok(hasher.finalize())
 }
 
+pub fn calculate_file_sha256(path: impl AsRef<Path>) -> Result<String, Error> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
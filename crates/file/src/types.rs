@@ -4,6 +4,12 @@ pub enum Error {
     ReqwestError(#[from] reqwest::Error),
     #[error("Error while reading file: {0}")]
     FileIOError(#[from] std::io::Error),
+    #[error("Unexpected file size: expected {0}, got {1}")]
+    SizeMismatch(u64, u64),
+    #[error("Checksum mismatch")]
+    ChecksumMismatch,
+    #[error("Not a Git LFS file: {0}")]
+    NotLfsFile(String),
     #[error("Other error: {0}")]
     OtherError(String),
 }
@@ -0,0 +1,469 @@
+// https://github.com/ggml-org/llama.cpp/blob/master/grammars/README.md#json-schemas--gbnf
+
+use serde_json::Value;
+
+const PRIMITIVES: &[(&str, &str)] = &[
+    ("ws", r#"| " " | "\n" [ \t]{0,20}"#),
+    (
+        "char",
+        r#"[^"\\\x7F\x00-\x1F] | "\\" (["\\/bfnrt] | "u" hex hex hex hex)"#,
+    ),
+    ("hex", "[0-9a-fA-F]"),
+    ("string", r#""\"" char* "\"""#),
+    ("integer", r#""-"? ("0" | [1-9] [0-9]*)"#),
+    ("number", r#"integer ("." [0-9]+)? ([eE] [-+]? [0-9]+)?"#),
+    ("boolean", r#""true" | "false""#),
+    ("null", r#""null""#),
+    ("value", "object | array | string | number | boolean | null"),
+    (
+        "object",
+        r#""{" ws (string ws ":" ws value (ws "," ws string ws ":" ws value)*)? ws "}""#,
+    ),
+    ("array", r#""[" ws (value (ws "," ws value)*)? ws "]""#),
+];
+
+// Collects the rules needed to match JSON documents of one or more schemas.
+#[derive(Default)]
+pub struct JsonSchemaConverter {
+    rules: Vec<(String, String)>,
+    // Schema `$ref`s are resolved against, with the prefix of the rules they become.
+    root: (String, Value),
+}
+
+impl JsonSchemaConverter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds a rule, or replaces the one with the same name. Returns its name.
+    pub fn add_rule(&mut self, name: impl AsRef<str>, body: impl Into<String>) -> String {
+        let name = rule_name(name.as_ref());
+        let body = body.into();
+
+        match self.rules.iter_mut().find(|(n, _)| *n == name) {
+            Some(rule) => rule.1 = body,
+            None => self.rules.push((name.clone(), body)),
+        }
+
+        name
+    }
+
+    // Like `visit`, with `$ref`s in `schema` resolved against `schema` itself.
+    pub fn visit_root(&mut self, schema: &Value, name: &str) -> String {
+        let root = std::mem::replace(&mut self.root, (name.to_string(), schema.clone()));
+        let value = self.visit(schema, name);
+        self.root = root;
+        value
+    }
+
+    // Adds the rules matching `schema`. Returns the expression to reference it.
+    pub fn visit(&mut self, schema: &Value, name: &str) -> String {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.visit_ref(reference);
+        }
+
+        if let Some(value) = schema.get("const") {
+            return self.add_rule(name, literal(value));
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            let body = values.iter().map(literal).collect::<Vec<_>>().join(" | ");
+            return self.add_rule(name, body);
+        }
+
+        if let Some(schemas) = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            let alternatives = schemas
+                .iter()
+                .enumerate()
+                .map(|(i, s)| self.visit(s, &format!("{}-{}", name, i)))
+                .collect::<Vec<_>>();
+            return self.add_rule(name, alternatives.join(" | "));
+        }
+
+        if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+            let merged = match schemas.as_slice() {
+                [schema] => schema.clone(),
+                _ => self.merge(schemas),
+            };
+            return self.visit(&merged, name);
+        }
+
+        if let Some(types) = schema.get("type").and_then(Value::as_array) {
+            let alternatives = types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|ty| {
+                    let mut schema = schema.clone();
+                    schema["type"] = ty.into();
+                    self.visit(&schema, &format!("{}-{}", name, ty))
+                })
+                .collect::<Vec<_>>();
+            return self.add_rule(name, alternatives.join(" | "));
+        }
+
+        let ty = match schema.get("type").and_then(Value::as_str) {
+            None if schema.get("properties").is_some() => Some("object"),
+            ty => ty,
+        };
+
+        match ty {
+            Some("object") => match schema.get("properties").and_then(Value::as_object) {
+                Some(properties) if !properties.is_empty() => {
+                    self.visit_object(schema, properties, name)
+                }
+                _ => self.primitive("object"),
+            },
+            Some("array") => match schema.get("items") {
+                Some(items) => {
+                    let item = self.visit(items, &format!("{}-item", name));
+                    self.add_rule(
+                        name,
+                        format!(r#""[" ws ({0} (ws "," ws {0})*)? ws "]""#, item),
+                    )
+                }
+                None => self.primitive("array"),
+            },
+            Some(ty @ ("string" | "integer" | "number" | "boolean" | "null")) => self.primitive(ty),
+            _ => self.primitive("value"),
+        }
+    }
+
+    // Each definition becomes a rule of its own, so recursive schemas work.
+    fn visit_ref(&mut self, reference: &str) -> String {
+        let (scope, root) = self.root.clone();
+        let name = rule_name(&format!(
+            "{}-{}",
+            scope,
+            reference.rsplit('/').next().unwrap_or_default()
+        ));
+
+        if self.rules.iter().any(|(n, _)| *n == name) {
+            return name;
+        }
+
+        let Some(schema) = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+        else {
+            return self.primitive("value");
+        };
+
+        // Reserves the name before visiting the definition, which may refer to itself.
+        self.add_rule(&name, "");
+        let value = self.visit(schema, &name);
+        if value != name {
+            self.add_rule(&name, value);
+        }
+
+        name
+    }
+
+    // Combines the properties of `allOf` objects into one.
+    fn merge(&self, schemas: &[Value]) -> Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        for schema in schemas {
+            let schema = match schema.get("$ref").and_then(Value::as_str) {
+                Some(reference) => reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| self.root.1.pointer(pointer))
+                    .unwrap_or(schema),
+                None => schema,
+            };
+
+            if let Some(p) = schema.get("properties").and_then(Value::as_object) {
+                properties.extend(p.clone());
+            }
+            if let Some(r) = schema.get("required").and_then(Value::as_array) {
+                required.extend(r.clone());
+            }
+        }
+
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    fn visit_object(
+        &mut self,
+        schema: &Value,
+        properties: &serde_json::Map<String, Value>,
+        name: &str,
+    ) -> String {
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // Keys can come in any order in JSON, so required ones go first, in the order they are listed.
+        let optional = properties
+            .iter()
+            .filter(|(key, _)| !required.contains(&key.as_str()))
+            .collect::<Vec<_>>();
+        let required = required
+            .iter()
+            .filter_map(|key| properties.get_key_value(*key))
+            .collect::<Vec<_>>();
+
+        let mut pair = |key: &String, schema: &Value| {
+            let value = self.visit(schema, &format!("{}-{}", name, key));
+            format!(
+                r#"{} ws ":" ws {}"#,
+                literal(&Value::String(key.clone())),
+                value
+            )
+        };
+
+        let required = required
+            .into_iter()
+            .map(|(k, v)| pair(k, v))
+            .collect::<Vec<_>>();
+        let optional = optional
+            .into_iter()
+            .map(|(k, v)| pair(k, v))
+            .collect::<Vec<_>>();
+
+        let rest = |pairs: &[String]| {
+            pairs
+                .iter()
+                .map(|p| format!(r#" (ws "," ws {})?"#, p))
+                .collect::<String>()
+        };
+
+        let body = if required.is_empty() {
+            // The first pair present can't be preceded by a comma.
+            let alternatives = (0..optional.len())
+                .map(|i| format!("{}{}", optional[i], rest(&optional[i + 1..])))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            format!(r#""{{" ws ({})? ws "}}""#, alternatives)
+        } else {
+            format!(
+                r#""{{" ws {}{} ws "}}""#,
+                required.join(r#" ws "," ws "#),
+                rest(&optional)
+            )
+        };
+
+        self.add_rule(name, body)
+    }
+
+    // Adds one of the predefined rules, with the ones it refers to.
+    pub fn primitive(&mut self, name: &str) -> String {
+        let (_, body) = PRIMITIVES.iter().find(|(n, _)| *n == name).unwrap();
+        let name = self.add_rule(name, *body);
+
+        for dep in PRIMITIVES.iter().map(|(n, _)| *n) {
+            if dep != name
+                && body.split(|c: char| !c.is_alphanumeric()).any(|w| w == dep)
+                && !self.rules.iter().any(|(n, _)| n == dep)
+            {
+                self.primitive(dep);
+            }
+        }
+
+        name
+    }
+
+    pub fn format(&self) -> String {
+        self.rules
+            .iter()
+            .map(|(name, body)| format!("{} ::= {}\n", name, body))
+            .collect()
+    }
+}
+
+pub fn json_schema_grammar(schema: &Value) -> String {
+    let mut converter = JsonSchemaConverter::new();
+    let value = converter.visit_root(schema, "root-value");
+    converter.add_rule("root", value);
+    converter.format()
+}
+
+// Grammar of the JSON documents `T` deserializes from, per its `schemars` schema.
+pub fn schema_grammar<T: schemars::JsonSchema>() -> String {
+    json_schema_grammar(&serde_json::to_value(schemars::schema_for!(T)).unwrap())
+}
+
+// A GBNF string literal matching `value` as compact JSON.
+pub fn literal(value: &Value) -> String {
+    let json = value.to_string();
+    format!("\"{}\"", json.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn rule_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_schema_grammar() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+
+        let grammar = json_schema_grammar(&json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer" },
+                "sort": { "enum": ["newest", "oldest"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["query"]
+        }));
+
+        for (input, expected) in vec![
+            (r#"{"query": "roadmap"}"#, true),
+            (r#"{"query":"roadmap","limit":3}"#, true),
+            (
+                r#"{"query": "roadmap", "sort": "newest", "tags": ["a", "b"]}"#,
+                true,
+            ),
+            (r#"{"query": "a \"quoted\" word"}"#, true),
+            (r#"{"limit": 3}"#, false),
+            (r#"{"query": 3}"#, false),
+            (r#"{"query": "roadmap", "sort": "random"}"#, false),
+            (r#"{"query": "roadmap", "limit": 1.5}"#, false),
+        ] {
+            let result = gbnf.validate(&grammar, input).unwrap();
+            assert_eq!(result, expected, "failed: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_json_schema_grammar_optional() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+
+        let grammar = json_schema_grammar(&json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "boolean" },
+                "b": { "type": "null" }
+            }
+        }));
+
+        for (input, expected) in vec![
+            ("{}", true),
+            (r#"{"a": true}"#, true),
+            (r#"{"b": null}"#, true),
+            (r#"{"a": false, "b": null}"#, true),
+            (r#"{, "b": null}"#, false),
+        ] {
+            let result = gbnf.validate(&grammar, input).unwrap();
+            assert_eq!(result, expected, "failed: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_json_schema_grammar_combinators() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+
+        let grammar = json_schema_grammar(&json!({
+            "type": "object",
+            "properties": {
+                "kind": { "const": "note" },
+                "value": { "anyOf": [{ "type": "integer" }, { "type": "string" }] },
+                "label": { "type": ["string", "null"] },
+                "meta": {
+                    "allOf": [
+                        { "$ref": "#/$defs/created" },
+                        { "properties": { "by": { "type": "string" } }, "required": ["by"] }
+                    ]
+                }
+            },
+            "required": ["kind", "value", "label"],
+            "$defs": {
+                "created": {
+                    "type": "object",
+                    "properties": { "at": { "type": "integer" } },
+                    "required": ["at"]
+                }
+            }
+        }));
+
+        for (input, expected) in vec![
+            (r#"{"kind": "note", "value": 1, "label": null}"#, true),
+            (r#"{"kind": "note", "value": "x", "label": "a"}"#, true),
+            (
+                r#"{"kind": "note", "value": 1, "label": null, "meta": {"at": 0, "by": "me"}}"#,
+                true,
+            ),
+            (r#"{"kind": "task", "value": 1, "label": null}"#, false),
+            (r#"{"kind": "note", "value": true, "label": null}"#, false),
+            (r#"{"kind": "note", "value": 1}"#, false),
+            (
+                r#"{"kind": "note", "value": 1, "label": null, "meta": {"by": "me"}}"#,
+                false,
+            ),
+        ] {
+            let result = gbnf.validate(&grammar, input).unwrap();
+            assert_eq!(result, expected, "failed: {}", input);
+        }
+    }
+
+    #[allow(dead_code)]
+    #[derive(schemars::JsonSchema)]
+    struct Task {
+        title: String,
+        assignee: Option<String>,
+        priority: Priority,
+        subtasks: Vec<Task>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(schemars::JsonSchema)]
+    enum Priority {
+        Low,
+        High,
+    }
+
+    #[test]
+    fn test_schema_grammar() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        let grammar = schema_grammar::<Task>();
+
+        // schemars lists required fields alphabetically.
+        for (input, expected) in vec![
+            (
+                r#"{"priority": "High", "subtasks": [], "title": "Ship"}"#,
+                true,
+            ),
+            (
+                r#"{"priority": "Low", "subtasks": [{"priority": "Low", "subtasks": [], "title": "Test", "assignee": null}], "title": "Ship", "assignee": "Alice"}"#,
+                true,
+            ),
+            (
+                r#"{"priority": "Urgent", "subtasks": [], "title": "Ship"}"#,
+                false,
+            ),
+            (
+                r#"{"priority": "High", "subtasks": [{}], "title": "Ship"}"#,
+                false,
+            ),
+            (r#"{"priority": "High", "title": "Ship"}"#, false),
+        ] {
+            let result = gbnf.validate(&grammar, input).unwrap();
+            assert_eq!(result, expected, "failed: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(literal(&json!("name")), r#""\"name\"""#);
+        assert_eq!(literal(&json!(1)), r#""1""#);
+        assert_eq!(literal(&json!("a\nb")), r#""\"a\\nb\"""#);
+    }
+}
@@ -2,9 +2,25 @@ pub const ENHANCE_AUTO: &str = include_str!("../assets/enhance-auto.gbnf");
 pub const ENHANCE_TEMPLATE: &str = include_str!("../assets/enhance-template.gbnf");
 pub const TITLE: &str = include_str!("../assets/title.gbnf");
 
+mod json_schema;
+pub use json_schema::*;
+
+pub const TOOL_CALL_START: &str = "<tool_call>";
+pub const TOOL_CALL_END: &str = "</tool_call>";
+
 pub enum GBNF {
     Enhance(Option<Vec<String>>),
     Title,
+    // Any JSON document matching the schema.
+    JsonSchema(serde_json::Value),
+    // Unless `required`, plain text is allowed instead of tool calls.
+    ToolCall { tools: Vec<Tool>, required: bool },
+}
+
+pub struct Tool {
+    pub name: String,
+    // JSON schema of the arguments.
+    pub parameters: serde_json::Value,
 }
 
 impl GBNF {
@@ -13,10 +29,59 @@ impl GBNF {
             GBNF::Enhance(Some(_)) => ENHANCE_TEMPLATE.to_string(),
             GBNF::Enhance(None) => ENHANCE_AUTO.to_string(),
             GBNF::Title => TITLE.to_string(),
+            GBNF::JsonSchema(schema) => json_schema_grammar(schema),
+            GBNF::ToolCall { tools, required } => tool_call_grammar(tools, *required),
         }
     }
 }
 
+// Each call is `<tool_call>{"name": ..., "arguments": ...}</tool_call>`.
+fn tool_call_grammar(tools: &[Tool], required: bool) -> String {
+    let mut converter = JsonSchemaConverter::new();
+    converter.primitive("ws");
+
+    let calls = tools
+        .iter()
+        .enumerate()
+        .map(|(i, tool)| {
+            let name = format!("tool-{}", i);
+            let arguments = converter.visit_root(&tool.parameters, &format!("{}-arguments", name));
+
+            converter.add_rule(
+                &name,
+                format!(
+                    r#""{{" ws "\"name\"" ws ":" ws {} ws "," ws "\"arguments\"" ws ":" ws {} ws "}}""#,
+                    literal(&serde_json::Value::String(tool.name.clone())),
+                    arguments
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    converter.add_rule(
+        "tool-call",
+        format!(
+            "{} ws ({}) ws {}",
+            literal_str(TOOL_CALL_START),
+            calls.join(" | "),
+            literal_str(TOOL_CALL_END)
+        ),
+    );
+
+    if required {
+        converter.add_rule("root", "tool-call (ws tool-call)*");
+    } else {
+        converter.add_rule("text", r"[^<] [^\x00]*");
+        converter.add_rule("root", "tool-call (ws tool-call)* | text");
+    }
+
+    converter.format()
+}
+
+fn literal_str(s: &str) -> String {
+    format!("\"{}\"", s)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,6 +149,80 @@ mod tests {
         // assert!(gbnf.validate(ENHANCE_AUTO, input_1).unwrap());
     }
 
+    #[test]
+    fn test_tool_call_grammar() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+
+        let tools = || {
+            vec![
+                Tool {
+                    name: "search_sessions".to_string(),
+                    parameters: serde_json::json!({
+                        "type": "object",
+                        "properties": { "query": { "type": "string" } },
+                        "required": ["query"]
+                    }),
+                },
+                Tool {
+                    name: "list_tags".to_string(),
+                    parameters: serde_json::json!({ "type": "object" }),
+                },
+            ]
+        };
+
+        let required = GBNF::ToolCall {
+            tools: tools(),
+            required: true,
+        }
+        .build();
+        let auto = GBNF::ToolCall {
+            tools: tools(),
+            required: false,
+        }
+        .build();
+
+        for (input, expected_required, expected_auto) in vec![
+            (
+                r#"<tool_call>{"name": "search_sessions", "arguments": {"query": "roadmap"}}</tool_call>"#,
+                true,
+                true,
+            ),
+            (
+                concat!(
+                    r#"<tool_call>{"name": "list_tags", "arguments": {}}</tool_call>"#,
+                    "\n",
+                    r#"<tool_call>{"name": "search_sessions", "arguments": {"query": "q1"}}</tool_call>"#,
+                ),
+                true,
+                true,
+            ),
+            (
+                r#"<tool_call>{"name": "search_sessions", "arguments": {}}</tool_call>"#,
+                false,
+                false,
+            ),
+            (
+                r#"<tool_call>{"name": "delete_sessions", "arguments": {}}</tool_call>"#,
+                false,
+                false,
+            ),
+            ("The meeting was about the roadmap.", false, true),
+        ] {
+            assert_eq!(
+                gbnf.validate(&required, input).unwrap(),
+                expected_required,
+                "failed: {}",
+                input
+            );
+            assert_eq!(
+                gbnf.validate(&auto, input).unwrap(),
+                expected_auto,
+                "failed: {}",
+                input
+            );
+        }
+    }
+
     #[allow(dead_code)]
     fn debug_grammar_failure_point(gbnf: &gbnf_validator::Validator, grammar: &str, text: &str) {
         use colored::Colorize;
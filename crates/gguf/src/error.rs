@@ -15,6 +15,9 @@ pub enum Error {
     #[error("Unsupported metadata value type: {0}")]
     UnsupportedValueType(u32),
 
+    #[error("Unsupported tensor type: {0}")]
+    UnsupportedTensorType(u32),
+
     #[error("Invalid UTF-8 sequence")]
     InvalidUtf8,
 }
@@ -17,8 +17,22 @@ pub use value::*;
 mod utils;
 pub use utils::*;
 
+const DEFAULT_ALIGNMENT: u64 = 32;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GgufMetadata {
+    pub architecture: Option<String>,
+    pub name: Option<String>,
+    // Context length the model was trained with.
+    pub context_length: Option<u64>,
+    pub has_chat_template: bool,
+    // Where the data of the last tensor ends. Files shorter than this are truncated.
+    pub min_file_size: u64,
+}
+
 pub trait GgufExt {
     fn gguf_chat_format(&self) -> Result<Option<ChatTemplate>>;
+    fn gguf_metadata(&self) -> Result<GgufMetadata>;
 }
 
 impl<T: AsRef<Path>> GgufExt for T {
@@ -27,30 +41,7 @@ impl<T: AsRef<Path>> GgufExt for T {
         let map = unsafe { Mmap::map(&file)? };
         let mut reader = Cursor::new(&map[..]);
 
-        let magic = reader.read_u32::<LittleEndian>()?;
-        if magic != GGUF_MAGIC {
-            return Err(Error::InvalidMagic);
-        }
-
-        let (version, is_little_endian) = {
-            reader.seek(SeekFrom::Start(4))?;
-            let version_le = reader.read_u32::<LittleEndian>()?;
-
-            if version_le & 65535 != 0 {
-                (version_le, true)
-            } else {
-                reader.seek(SeekFrom::Start(4))?;
-                let version_be = reader.read_u32::<BigEndian>()?;
-                (version_be, false)
-            }
-        };
-
-        if version > 3 {
-            return Err(Error::UnsupportedVersion(version));
-        }
-
-        // Reset position to after version
-        reader.seek(SeekFrom::Start(8))?;
+        let (version, is_little_endian) = read_header(&mut reader)?;
 
         let _tensor_count = read_versioned_size(&mut reader, version, is_little_endian)?;
         let metadata_kv_count = read_versioned_size(&mut reader, version, is_little_endian)?;
@@ -130,11 +121,121 @@ impl<T: AsRef<Path>> GgufExt for T {
             Ok(None)
         }
     }
+
+    fn gguf_metadata(&self) -> Result<GgufMetadata> {
+        let file = File::open(self.as_ref())?;
+        let map = unsafe { Mmap::map(&file)? };
+        let mut reader = Cursor::new(&map[..]);
+
+        let (version, is_little_endian) = read_header(&mut reader)?;
+        let read_u32 = |reader: &mut Cursor<&[u8]>| -> Result<u32> {
+            Ok(if is_little_endian {
+                reader.read_u32::<LittleEndian>()?
+            } else {
+                reader.read_u32::<BigEndian>()?
+            })
+        };
+
+        let tensor_count = read_versioned_size(&mut reader, version, is_little_endian)?;
+        let metadata_kv_count = read_versioned_size(&mut reader, version, is_little_endian)?;
+
+        let mut metadata = GgufMetadata::default();
+        let mut context_lengths = Vec::new();
+        let mut alignment = DEFAULT_ALIGNMENT;
+
+        for _ in 0..metadata_kv_count {
+            let key = read_string(&mut reader, version, is_little_endian)?;
+            let value_type = GGUFMetadataValueType::try_from(read_u32(&mut reader)?)?;
+
+            match (key.as_str(), value_type) {
+                ("general.architecture", GGUFMetadataValueType::String) => {
+                    metadata.architecture =
+                        Some(read_string(&mut reader, version, is_little_endian)?);
+                }
+                ("general.name", GGUFMetadataValueType::String) => {
+                    metadata.name = Some(read_string(&mut reader, version, is_little_endian)?);
+                }
+                ("general.alignment", _) => {
+                    alignment = read_uint(&mut reader, value_type, version, is_little_endian)?
+                        .unwrap_or(DEFAULT_ALIGNMENT);
+                }
+                ("tokenizer.chat_template", _) => {
+                    metadata.has_chat_template = true;
+                    skip_value(&mut reader, value_type, version, is_little_endian)?;
+                }
+                // Prefixed with the architecture, which may not have been read yet.
+                (key, _) if key.ends_with(".context_length") => {
+                    let prefix = key.trim_end_matches(".context_length").to_string();
+                    if let Some(length) =
+                        read_uint(&mut reader, value_type, version, is_little_endian)?
+                    {
+                        context_lengths.push((prefix, length));
+                    }
+                }
+                _ => skip_value(&mut reader, value_type, version, is_little_endian)?,
+            }
+        }
+
+        metadata.context_length = context_lengths
+            .into_iter()
+            .find(|(prefix, _)| Some(prefix) == metadata.architecture.as_ref())
+            .map(|(_, length)| length);
+
+        let mut data_end = 0;
+        for _ in 0..tensor_count {
+            let _name = read_string(&mut reader, version, is_little_endian)?;
+            let n_dims = read_u32(&mut reader)?;
+            let dims = (0..n_dims)
+                .map(|_| read_versioned_size(&mut reader, version, is_little_endian))
+                .collect::<Result<Vec<_>>>()?;
+            let tensor_type = read_u32(&mut reader)?;
+            let offset = if is_little_endian {
+                reader.read_u64::<LittleEndian>()?
+            } else {
+                reader.read_u64::<BigEndian>()?
+            };
+            data_end = data_end.max(offset + tensor_size(tensor_type, &dims)?);
+        }
+
+        let data_start = reader.position().div_ceil(alignment.max(1)) * alignment.max(1);
+        metadata.min_file_size = data_start + data_end;
+
+        Ok(metadata)
+    }
+}
+
+// Checks the magic number, and leaves the reader right after the version.
+fn read_header(reader: &mut Cursor<&[u8]>) -> Result<(u32, bool)> {
+    let magic = reader.read_u32::<LittleEndian>()?;
+    if magic != GGUF_MAGIC {
+        return Err(Error::InvalidMagic);
+    }
+
+    let (version, is_little_endian) = {
+        reader.seek(SeekFrom::Start(4))?;
+        let version_le = reader.read_u32::<LittleEndian>()?;
+
+        if version_le & 65535 != 0 {
+            (version_le, true)
+        } else {
+            reader.seek(SeekFrom::Start(4))?;
+            let version_be = reader.read_u32::<BigEndian>()?;
+            (version_be, false)
+        }
+    };
+
+    if version > 3 {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    // Reset position to after version
+    reader.seek(SeekFrom::Start(8))?;
+    Ok((version, is_little_endian))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::GgufExt;
+    use super::*;
 
     #[test]
     fn test_gguf_chat_format() {
@@ -146,4 +247,81 @@ mod tests {
         assert!(test_path.exists());
         assert!(test_path.gguf_chat_format().is_ok());
     }
+
+    fn write_string(buf: &mut Vec<u8>, s: &str) {
+        buf.extend((s.len() as u64).to_le_bytes());
+        buf.extend(s.as_bytes());
+    }
+
+    // Version 3, with two F32 tensors of 16 bytes each.
+    fn gguf_bytes() -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend(GGUF_MAGIC.to_le_bytes());
+        buf.extend(3u32.to_le_bytes());
+        buf.extend(2u64.to_le_bytes());
+        buf.extend(4u64.to_le_bytes());
+
+        // Context length before the architecture, to check it is resolved afterwards.
+        write_string(&mut buf, "llama.context_length");
+        buf.extend((GGUFMetadataValueType::Uint32 as u32).to_le_bytes());
+        buf.extend(131072u32.to_le_bytes());
+
+        write_string(&mut buf, "general.architecture");
+        buf.extend((GGUFMetadataValueType::String as u32).to_le_bytes());
+        write_string(&mut buf, "llama");
+
+        write_string(&mut buf, "general.name");
+        buf.extend((GGUFMetadataValueType::String as u32).to_le_bytes());
+        write_string(&mut buf, "Test");
+
+        write_string(&mut buf, "tokenizer.chat_template");
+        buf.extend((GGUFMetadataValueType::String as u32).to_le_bytes());
+        write_string(&mut buf, "{{ messages }}");
+
+        for (name, offset) in [("a", 0u64), ("b", 16u64)] {
+            write_string(&mut buf, name);
+            buf.extend(1u32.to_le_bytes());
+            buf.extend(4u64.to_le_bytes());
+            buf.extend(0u32.to_le_bytes());
+            buf.extend(offset.to_le_bytes());
+        }
+
+        while buf.len() % DEFAULT_ALIGNMENT as usize != 0 {
+            buf.push(0);
+        }
+        buf.extend([0u8; 32]);
+        buf
+    }
+
+    #[test]
+    fn test_gguf_metadata() {
+        let bytes = gguf_bytes();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let metadata = path.gguf_metadata().unwrap();
+        assert_eq!(metadata.architecture.as_deref(), Some("llama"));
+        assert_eq!(metadata.name.as_deref(), Some("Test"));
+        assert_eq!(metadata.context_length, Some(131072));
+        assert!(metadata.has_chat_template);
+        assert_eq!(metadata.min_file_size, bytes.len() as u64);
+
+        std::fs::write(&path, b"not a gguf").unwrap();
+        assert!(matches!(path.gguf_metadata(), Err(Error::InvalidMagic)));
+    }
+
+    #[test]
+    fn test_tensor_size() {
+        assert_eq!(tensor_size(0, &[4]).unwrap(), 16);
+        // Q4_K: 144 bytes for each block of 256.
+        assert_eq!(
+            tensor_size(12, &[3072, 8192]).unwrap(),
+            3072 * 8192 / 256 * 144
+        );
+        assert!(matches!(
+            tensor_size(4, &[32]),
+            Err(Error::UnsupportedTensorType(4))
+        ));
+    }
 }
@@ -32,6 +32,41 @@ pub fn read_string<R: Read + Seek>(
     String::from_utf8(buf).map_err(|_| Error::InvalidUtf8)
 }
 
+// Integer values as u64, or `None` for other types and negative values.
+pub fn read_uint<R: Read + Seek>(
+    reader: &mut R,
+    value_type: GGUFMetadataValueType,
+    version: u32,
+    is_little_endian: bool,
+) -> Result<Option<u64>, Error> {
+    macro_rules! read {
+        ($method:ident) => {
+            if is_little_endian {
+                reader.$method::<LittleEndian>()?
+            } else {
+                reader.$method::<BigEndian>()?
+            }
+        };
+    }
+
+    let value = match value_type {
+        GGUFMetadataValueType::Uint8 => Some(reader.read_u8()? as u64),
+        GGUFMetadataValueType::Int8 => u64::try_from(reader.read_i8()?).ok(),
+        GGUFMetadataValueType::Uint16 => Some(read!(read_u16) as u64),
+        GGUFMetadataValueType::Int16 => u64::try_from(read!(read_i16)).ok(),
+        GGUFMetadataValueType::Uint32 => Some(read!(read_u32) as u64),
+        GGUFMetadataValueType::Int32 => u64::try_from(read!(read_i32)).ok(),
+        GGUFMetadataValueType::Uint64 => Some(read!(read_u64)),
+        GGUFMetadataValueType::Int64 => u64::try_from(read!(read_i64)).ok(),
+        _ => {
+            skip_value(reader, value_type, version, is_little_endian)?;
+            None
+        }
+    };
+
+    Ok(value)
+}
+
 pub fn skip_value<R: Read + Seek>(
     reader: &mut R,
     value_type: GGUFMetadataValueType,
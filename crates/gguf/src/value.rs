@@ -40,3 +40,52 @@ impl TryFrom<u32> for GGUFMetadataValueType {
         }
     }
 }
+
+// Elements per block and bytes per block of each `ggml_type`, from `type_traits` in ggml.
+// Numbers missing here were removed from ggml.
+pub fn ggml_type_block(tensor_type: u32) -> Result<(u64, u64), crate::Error> {
+    let block = match tensor_type {
+        0 => (1, 4),      // F32
+        1 => (1, 2),      // F16
+        2 => (32, 18),    // Q4_0
+        3 => (32, 20),    // Q4_1
+        6 => (32, 22),    // Q5_0
+        7 => (32, 24),    // Q5_1
+        8 => (32, 34),    // Q8_0
+        9 => (32, 36),    // Q8_1
+        10 => (256, 84),  // Q2_K
+        11 => (256, 110), // Q3_K
+        12 => (256, 144), // Q4_K
+        13 => (256, 176), // Q5_K
+        14 => (256, 210), // Q6_K
+        15 => (256, 292), // Q8_K
+        16 => (256, 66),  // IQ2_XXS
+        17 => (256, 74),  // IQ2_XS
+        18 => (256, 98),  // IQ3_XXS
+        19 => (256, 50),  // IQ1_S
+        20 => (32, 18),   // IQ4_NL
+        21 => (256, 110), // IQ3_S
+        22 => (256, 82),  // IQ2_S
+        23 => (256, 136), // IQ4_XS
+        24 => (1, 1),     // I8
+        25 => (1, 2),     // I16
+        26 => (1, 4),     // I32
+        27 => (1, 8),     // I64
+        28 => (1, 8),     // F64
+        29 => (256, 56),  // IQ1_M
+        30 => (1, 2),     // BF16
+        34 => (256, 54),  // TQ1_0
+        35 => (256, 66),  // TQ2_0
+        _ => return Err(crate::Error::UnsupportedTensorType(tensor_type)),
+    };
+
+    Ok(block)
+}
+
+// Bytes of a tensor's data. Rows are whole blocks, so the element count divides evenly.
+pub fn tensor_size(tensor_type: u32, dims: &[u64]) -> Result<u64, crate::Error> {
+    let (block_size, block_bytes) = ggml_type_block(tensor_type)?;
+    let elements = dims.iter().product::<u64>();
+
+    Ok(elements / block_size * block_bytes)
+}
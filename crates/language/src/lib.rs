@@ -298,6 +298,41 @@ impl Language {
         }
     }
 
+    #[cfg(feature = "clova")]
+    pub fn for_clova_realtime(self) -> Result<hypr_clova::realtime::interface::Language, Error> {
+        use hypr_clova::realtime::interface::Language as CL;
+
+        match self.iso639 {
+            ISO639::En => Ok(CL::English),
+            ISO639::Ja => Ok(CL::Japanese),
+            ISO639::Ko => Ok(CL::Korean),
+            _ => Err(Error::NotSupportedLanguage(self.to_string())),
+        }
+    }
+
+    #[cfg(feature = "clova")]
+    pub fn for_clova_recorded(self) -> Result<hypr_clova::recorded::Language, Error> {
+        use hypr_clova::recorded::Language as CL;
+
+        match self.iso639 {
+            ISO639::En => Ok(CL::English),
+            ISO639::Ja => Ok(CL::Japanese),
+            // Korean meetings are full of English terms.
+            ISO639::Ko => Ok(CL::KoreanWithEnglish),
+            ISO639::Zh => Ok(CL::ChineseSimplified),
+            _ => Err(Error::NotSupportedLanguage(self.to_string())),
+        }
+    }
+
+    // `DecoderConfig.model_name` in crates/rtzr/proto/rtzr.proto
+    pub fn for_rtzr(self) -> Result<&'static str, Error> {
+        match self.iso639 {
+            ISO639::Ja => Ok("sommers_ja"),
+            ISO639::Ko => Ok("default"),
+            _ => Err(Error::NotSupportedLanguage(self.to_string())),
+        }
+    }
+
     pub fn text_transcript(&self) -> Result<String, Error> {
         match self.iso639 {
             ISO639::Bg => Ok(String::from("транскрипт")),
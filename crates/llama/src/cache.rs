@@ -0,0 +1,101 @@
+// Contexts kept across requests, so prompts sharing a prefix (system prompt, transcript)
+// only decode what comes after it. Most recently used first.
+pub(crate) struct PromptCache<C, T> {
+    entries: Vec<(C, Vec<T>)>,
+    capacity: usize,
+}
+
+impl<C, T: PartialEq> PromptCache<C, T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    // Takes out the context sharing the longest prefix with `tokens`, with the length of that prefix, if it covers
+    // at least half of the prompt. Sharing only the chat template header or system prompt isn't worth evicting
+    // the context for, so a fresh one is made instead until the cache is full. Then the least recently used
+    // context is handed out.
+    pub fn take(&mut self, tokens: &[T]) -> Option<(C, usize)> {
+        let best = self
+            .entries
+            .iter()
+            .map(|(_, cached)| common_prefix(cached, tokens))
+            .enumerate()
+            .max_by_key(|(_, len)| *len);
+
+        match best {
+            Some((i, len)) if len > 0 && len * 2 >= tokens.len() => {
+                Some((self.entries.remove(i).0, len))
+            }
+            _ if self.capacity > 0 && self.entries.len() >= self.capacity => self
+                .entries
+                .pop()
+                .map(|(ctx, cached)| (ctx, common_prefix(&cached, tokens))),
+            _ => None,
+        }
+    }
+
+    // `tokens` are the ones decoded in the context, in order.
+    pub fn put(&mut self, ctx: C, tokens: Vec<T>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.entries.insert(0, (ctx, tokens));
+        self.entries.truncate(self.capacity);
+    }
+}
+
+fn common_prefix<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_cache() {
+        let mut cache = PromptCache::<&str, u32>::new(2);
+        assert_eq!(cache.take(&[1, 2, 3]), None);
+
+        cache.put("a", vec![1, 2, 3, 4]);
+        cache.put("b", vec![1, 5]);
+
+        // Longest shared prefix wins.
+        assert_eq!(cache.take(&[1, 2, 3, 9]), Some(("a", 3)));
+        cache.put("a", vec![1, 2, 3, 9]);
+
+        // Nothing shared and full, so the least recently used one is reused.
+        assert_eq!(cache.take(&[7]), Some(("b", 0)));
+        cache.put("b", vec![7]);
+
+        // Evicts "a".
+        cache.put("c", vec![8]);
+        assert_eq!(cache.take(&[8]), Some(("c", 1)));
+        assert_eq!(cache.take(&[1, 2]), None);
+        assert_eq!(cache.take(&[7]), Some(("b", 1)));
+    }
+
+    #[test]
+    fn test_prompt_cache_shared_header() {
+        let mut cache = PromptCache::<&str, u32>::new(2);
+        cache.put("a", vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        // Only the template header is shared, so "a" is kept for prompts that continue it.
+        assert_eq!(cache.take(&[1, 2, 9, 9, 9, 9, 9, 9]), None);
+        cache.put("b", vec![1, 2, 9, 9, 9, 9, 9, 9]);
+
+        // Full, so the least recently used one is reused, with whatever it shares.
+        assert_eq!(cache.take(&[1, 2, 7, 7, 7, 7, 7, 7]), Some(("a", 2)));
+    }
+
+    #[test]
+    fn test_prompt_cache_disabled() {
+        let mut cache = PromptCache::<&str, u32>::new(0);
+        cache.put("a", vec![1]);
+        assert_eq!(cache.take(&[1]), None);
+    }
+}
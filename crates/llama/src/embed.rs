@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use llama_cpp_2::{
+    context::params::LlamaContextParams,
+    llama_batch::LlamaBatch,
+    model::{params::LlamaModelParams, AddBos, LlamaModel},
+};
+
+const DEFAULT_EMBEDDING_CONTEXT_SIZE: u32 = 2048;
+
+struct EmbedTask {
+    texts: Vec<String>,
+    response_sender: tokio::sync::oneshot::Sender<Result<Vec<Vec<f32>>, crate::Error>>,
+}
+
+// Runs a GGUF embedding model in embeddings mode. Texts longer than the context are truncated.
+pub struct LlamaEmbedder {
+    model: Arc<LlamaModel>,
+    task_sender: tokio::sync::mpsc::UnboundedSender<EmbedTask>,
+}
+
+impl LlamaEmbedder {
+    pub fn new(model_path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
+        let backend = crate::backend();
+
+        let params = LlamaModelParams::default();
+        let model = Arc::new(LlamaModel::load_from_file(
+            &backend,
+            model_path.as_ref(),
+            &params,
+        )?);
+
+        let context_size = model.n_ctx_train().min(DEFAULT_EMBEDDING_CONTEXT_SIZE);
+
+        let (task_sender, mut task_receiver) = tokio::sync::mpsc::unbounded_channel::<EmbedTask>();
+
+        std::thread::spawn({
+            let model = model.clone();
+
+            move || {
+                // Non-causal models need the whole input in a single ubatch.
+                let mut ctx = model
+                    .new_context(
+                        &backend,
+                        LlamaContextParams::default()
+                            .with_n_ctx(std::num::NonZeroU32::new(context_size))
+                            .with_n_batch(context_size)
+                            .with_n_ubatch(context_size)
+                            .with_embeddings(true),
+                    )
+                    .unwrap();
+
+                while let Some(EmbedTask {
+                    texts,
+                    response_sender,
+                }) = task_receiver.blocking_recv()
+                {
+                    let result = texts
+                        .iter()
+                        .map(|text| {
+                            let mut tokens = model.str_to_token(text, AddBos::Always)?;
+                            tokens.truncate(context_size as usize);
+
+                            let mut batch = LlamaBatch::new(tokens.len(), 1);
+                            batch.add_sequence(&tokens, 0, false)?;
+
+                            ctx.clear_kv_cache();
+                            ctx.decode(&mut batch)?;
+
+                            Ok(normalize(ctx.embeddings_seq_ith(0)?))
+                        })
+                        .collect::<Result<Vec<_>, crate::Error>>();
+
+                    let _ = response_sender.send(result);
+                }
+            }
+        });
+
+        Ok(Self { model, task_sender })
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.model.n_embd() as usize
+    }
+
+    pub fn count_tokens(&self, text: &str) -> Result<usize, crate::Error> {
+        Ok(self.model.str_to_token(text, AddBos::Always)?.len())
+    }
+
+    // One L2-normalized vector per text, in order.
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, crate::Error> {
+        let (response_sender, response_receiver) = tokio::sync::oneshot::channel();
+
+        self.task_sender
+            .send(EmbedTask {
+                texts,
+                response_sender,
+            })
+            .map_err(|_| crate::Error::WorkerStopped)?;
+
+        response_receiver
+            .await
+            .map_err(|_| crate::Error::WorkerStopped)?
+    }
+}
+
+fn normalize(embedding: &[f32]) -> Vec<f32> {
+    let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return embedding.to_vec();
+    }
+
+    embedding.iter().map(|v| v / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(&[3.0, 4.0]), vec![0.6, 0.8]);
+        assert_eq!(normalize(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(a, b)| a * b).sum()
+    }
+
+    // cargo test test_embed -p llama -- --ignored --nocapture
+    #[ignore]
+    #[tokio::test]
+    async fn test_embed() {
+        let model_path = dirs::data_dir()
+            .unwrap()
+            .join("com.hyprnote.dev")
+            .join("embedding.gguf");
+
+        let embedder = LlamaEmbedder::new(model_path).unwrap();
+
+        let embeddings = embedder
+            .embed(vec![
+                "search_query: when do we ship the release?".to_string(),
+                "search_document: We agreed to ship version 2 next Friday.".to_string(),
+                "search_document: The office plants need watering.".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        assert!(embeddings.iter().all(|e| e.len() == embedder.dimensions()));
+        assert!(cosine(&embeddings[0], &embeddings[1]) > cosine(&embeddings[0], &embeddings[2]));
+    }
+}
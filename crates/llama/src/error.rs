@@ -11,6 +11,8 @@ pub enum Error {
     #[error(transparent)]
     LlamaContextLoadError(#[from] llama_cpp_2::LlamaContextLoadError),
     #[error(transparent)]
+    ApplyChatTemplateError(#[from] llama_cpp_2::ApplyChatTemplateError),
+    #[error(transparent)]
     StringToTokenError(#[from] llama_cpp_2::StringToTokenError),
     #[error(transparent)]
     TokenToStringError(#[from] llama_cpp_2::TokenToStringError),
@@ -19,7 +21,13 @@ pub enum Error {
     #[error(transparent)]
     DecodeError(#[from] llama_cpp_2::DecodeError),
     #[error(transparent)]
+    EmbeddingsError(#[from] llama_cpp_2::EmbeddingsError),
+    #[error("prompt has {tokens} tokens, but only {limit} fit in the context")]
+    ContextOverflow { tokens: usize, limit: usize },
+    #[error(transparent)]
     TaskSendError(#[from] tokio::sync::mpsc::error::SendError<crate::Task>),
+    #[error("worker thread stopped")]
+    WorkerStopped,
 }
 
 impl Serialize for Error {
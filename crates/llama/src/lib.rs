@@ -6,114 +6,166 @@ use llama_cpp_2::{
     llama_batch::LlamaBatch,
     model::{params::LlamaModelParams, AddBos, LlamaChatTemplate, LlamaModel, Special},
     sampling::LlamaSampler,
-    send_logs_to_tracing, LogOptions,
+    send_logs_to_tracing,
+    token::LlamaToken,
+    LogOptions,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use hypr_gguf::GgufExt;
 
+mod cache;
+mod embed;
 mod error;
 mod stream;
+mod tools;
 mod types;
 
+pub use embed::*;
 pub use error::*;
 pub use stream::filter_tag;
+pub use tools::*;
 pub use types::*;
 
-const DEFAULT_MAX_INPUT_TOKENS: u32 = 1024 * 8;
+use cache::PromptCache;
+use stream::{match_stop, StopMatch};
+
+pub const DEFAULT_CONTEXT_SIZE: u32 = 1024 * 9;
 const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 1024;
+const DEFAULT_TEMPERATURE: f32 = 0.8;
+const DEFAULT_SEED: u32 = 1234;
+const DEFAULT_CACHE_SIZE: usize = 2;
 
 static LLAMA_BACKEND: OnceLock<Arc<LlamaBackend>> = OnceLock::new();
 
 pub struct Llama {
+    model: Arc<LlamaModel>,
+    tpl: LlamaChatTemplate,
+    context_size: u32,
     task_sender: tokio::sync::mpsc::UnboundedSender<Task>,
 }
 
 pub enum Task {
     Generate {
         request: LlamaRequest,
+        tokens: Vec<LlamaToken>,
         response_sender: tokio::sync::mpsc::UnboundedSender<String>,
     },
 }
 
-impl Llama {
-    pub fn new(model_path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
-        send_logs_to_tracing(LogOptions::default().with_logs_enabled(false));
+#[derive(Default)]
+pub struct LlamaBuilder {
+    model_path: Option<std::path::PathBuf>,
+    context_size: Option<u32>,
+    cache_size: Option<usize>,
+    chat_template: Option<hypr_gguf::ChatTemplate>,
+}
+
+impl LlamaBuilder {
+    pub fn model_path(mut self, model_path: impl Into<std::path::PathBuf>) -> Self {
+        self.model_path = Some(model_path.into());
+        self
+    }
+
+    // Prompt and completion tokens together.
+    pub fn context_size(mut self, context_size: u32) -> Self {
+        self.context_size = Some(context_size);
+        self
+    }
+
+    // Contexts kept around to reuse their KV cache. Each holds `context_size` tokens in memory.
+    pub fn cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size = Some(cache_size);
+        self
+    }
 
-        let backend = LLAMA_BACKEND
-            .get_or_init(|| {
-                let backend = LlamaBackend::init().unwrap();
-                Arc::new(backend)
-            })
-            .clone();
+    // Defaults to the template embedded in the GGUF.
+    pub fn chat_template(mut self, chat_template: hypr_gguf::ChatTemplate) -> Self {
+        self.chat_template = Some(chat_template);
+        self
+    }
+
+    pub fn build(self) -> Result<Llama, crate::Error> {
+        let backend = backend();
+
+        let model_path = self.model_path.unwrap();
+        let context_size = self.context_size.unwrap_or(DEFAULT_CONTEXT_SIZE);
+        let cache_size = self.cache_size.unwrap_or(DEFAULT_CACHE_SIZE);
 
-        let fmt = model_path.gguf_chat_format()?.unwrap();
+        let fmt = match self.chat_template {
+            Some(chat_template) => chat_template,
+            None => model_path.gguf_chat_format()?.unwrap(),
+        };
         let tpl = LlamaChatTemplate::new(fmt.as_ref()).unwrap();
 
         let params = LlamaModelParams::default();
-        let model = LlamaModel::load_from_file(&backend, model_path, &params)?;
+        let model = Arc::new(LlamaModel::load_from_file(&backend, &model_path, &params)?);
 
         let (task_sender, mut task_receiver) = tokio::sync::mpsc::unbounded_channel::<Task>();
 
         std::thread::spawn({
+            let model = model.clone();
+
             move || {
+                let mut cache = PromptCache::new(cache_size);
+
                 while let Some(task) = task_receiver.blocking_recv() {
                     match task {
                         Task::Generate {
                             request,
+                            tokens,
                             response_sender,
                         } => {
-                            let prompt = model
-                                .apply_chat_template(&tpl, &request.messages, true)
-                                .unwrap();
+                            let max_tokens =
+                                request.max_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS) as i32;
+
+                            let (mut ctx, reused) = match cache.take(&tokens) {
+                                Some(cached) => cached,
+                                None => (
+                                    model
+                                        .new_context(
+                                            &backend,
+                                            // https://github.com/ggml-org/llama.cpp/blob/492d7f1/src/llama-context.cpp#L2261
+                                            LlamaContextParams::default()
+                                                .with_n_ctx(std::num::NonZeroU32::new(context_size))
+                                                .with_n_batch(context_size)
+                                                .with_n_ubatch(512)
+                                                .with_embeddings(false)
+                                                .with_flash_attention(true),
+                                        )
+                                        .unwrap(),
+                                    0,
+                                ),
+                            };
 
-                            let mut ctx = model
-                                .new_context(
-                                    &backend,
-                                    // https://github.com/ggml-org/llama.cpp/blob/492d7f1/src/llama-context.cpp#L2261
-                                    LlamaContextParams::default()
-                                        .with_n_ctx(std::num::NonZeroU32::new(
-                                            DEFAULT_MAX_INPUT_TOKENS + DEFAULT_MAX_OUTPUT_TOKENS,
-                                        ))
-                                        .with_n_batch(DEFAULT_MAX_INPUT_TOKENS)
-                                        .with_n_ubatch(512)
-                                        .with_embeddings(false)
-                                        .with_flash_attention(true),
-                                )
+                            // Logits are only kept for the last batch,
+                            // so the last prompt token is decoded again.
+                            let reused = reused.min(tokens.len() - 1);
+                            ctx.clear_kv_cache_seq(Some(0), Some(reused as u32), None)
                                 .unwrap();
 
-                            let mut tokens_list =
-                                model.str_to_token(&prompt, AddBos::Always).unwrap();
-                            tokens_list.truncate(DEFAULT_MAX_INPUT_TOKENS as usize);
-
-                            let batch_size = tokens_list.len().max(512);
+                            let batch_size = (tokens.len() - reused).max(512);
                             let mut batch = LlamaBatch::new(batch_size, 1);
 
-                            let last_index = (tokens_list.len() - 1) as i32;
-                            for (i, token) in (0_i32..).zip(tokens_list.into_iter()) {
+                            let last_index = (tokens.len() - 1) as i32;
+                            for (i, token) in (0_i32..).zip(tokens.iter()).skip(reused) {
                                 let is_last = i == last_index;
-                                batch.add(token, i, &[0], is_last).unwrap();
+                                batch.add(*token, i, &[0], is_last).unwrap();
                             }
 
                             ctx.decode(&mut batch).unwrap();
 
-                            let mut n_cur = batch.n_tokens();
+                            // Everything in the KV cache of `ctx`, for the next requests.
+                            let mut decoded = tokens;
+
+                            let mut n_cur = last_index + 1;
                             let mut decoder = encoding_rs::UTF_8.new_decoder();
+                            let mut sampler = build_sampler(&model, &request);
 
-                            let mut sampler = match request.grammar {
-                                Some(grammar) => LlamaSampler::chain_simple([
-                                    LlamaSampler::grammar(&model, grammar.as_str(), "root"),
-                                    LlamaSampler::temp(0.8),
-                                    LlamaSampler::penalties(0, 1.4, 0.1, 0.0),
-                                    LlamaSampler::mirostat_v2(1234, 3.0, 0.2),
-                                ]),
-                                None => LlamaSampler::chain_simple([
-                                    LlamaSampler::temp(0.8),
-                                    LlamaSampler::penalties(0, 1.4, 0.1, 0.0),
-                                    LlamaSampler::mirostat_v2(1234, 3.0, 0.2),
-                                ]),
-                            };
-                            while n_cur <= last_index + DEFAULT_MAX_OUTPUT_TOKENS as i32 {
+                            // Held back while it could still turn into a stop sequence.
+                            let mut pending = String::new();
+
+                            while n_cur <= last_index + max_tokens {
                                 let token = sampler.sample(&ctx, batch.n_tokens() - 1);
 
                                 if model.is_eog_token(token) {
@@ -122,15 +174,24 @@ impl Llama {
 
                                 let output_bytes =
                                     model.token_to_bytes(token, Special::Tokenize).unwrap();
-                                let mut output_string = String::with_capacity(32);
-                                let _decode_result = decoder.decode_to_string(
-                                    &output_bytes,
-                                    &mut output_string,
-                                    false,
-                                );
-
-                                if response_sender.send(output_string).is_err() {
-                                    break;
+                                let _decode_result =
+                                    decoder.decode_to_string(&output_bytes, &mut pending, false);
+
+                                match match_stop(&pending, &request.stop) {
+                                    StopMatch::Found(end) => {
+                                        pending.truncate(end);
+                                        break;
+                                    }
+                                    StopMatch::Safe(end) => {
+                                        let rest = pending.split_off(end);
+                                        let output_string = std::mem::replace(&mut pending, rest);
+
+                                        if !output_string.is_empty()
+                                            && response_sender.send(output_string).is_err()
+                                        {
+                                            break;
+                                        }
+                                    }
                                 }
 
                                 batch.clear();
@@ -138,26 +199,109 @@ impl Llama {
 
                                 n_cur += 1;
                                 ctx.decode(&mut batch).unwrap();
+                                decoded.push(token);
+                            }
+
+                            if !pending.is_empty() {
+                                let _ = response_sender.send(pending);
                             }
 
                             drop(response_sender);
+                            cache.put(ctx, decoded);
                         }
                     }
                 }
             }
         });
 
-        Ok(Self { task_sender })
+        Ok(Llama {
+            model,
+            tpl,
+            context_size,
+            task_sender,
+        })
+    }
+}
+
+fn backend() -> Arc<LlamaBackend> {
+    send_logs_to_tracing(LogOptions::default().with_logs_enabled(false));
+
+    LLAMA_BACKEND
+        .get_or_init(|| {
+            let backend = LlamaBackend::init().unwrap();
+            Arc::new(backend)
+        })
+        .clone()
+}
+
+fn build_sampler(model: &LlamaModel, request: &LlamaRequest) -> LlamaSampler {
+    let seed = request.seed.unwrap_or(DEFAULT_SEED);
+
+    let mut samplers = Vec::new();
+
+    if let Some(grammar) = &request.grammar {
+        samplers.push(LlamaSampler::grammar(model, grammar.as_str(), "root"));
+    }
+
+    samplers.push(LlamaSampler::penalties(0, 1.4, 0.1, 0.0));
+
+    if let Some(top_k) = request.top_k {
+        samplers.push(LlamaSampler::top_k(top_k));
+    }
+    if let Some(top_p) = request.top_p {
+        samplers.push(LlamaSampler::top_p(top_p, 1));
+    }
+
+    samplers.push(LlamaSampler::temp(
+        request.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+    ));
+
+    // Mirostat does its own truncation, so it only makes sense when top-k/top-p are not asked for.
+    if request.top_k.is_none() && request.top_p.is_none() {
+        samplers.push(LlamaSampler::mirostat_v2(seed, 3.0, 0.2));
+    } else {
+        samplers.push(LlamaSampler::dist(seed));
+    }
+
+    LlamaSampler::chain_simple(samplers)
+}
+
+impl Llama {
+    pub fn builder() -> LlamaBuilder {
+        LlamaBuilder::default()
+    }
+
+    pub fn new(model_path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
+        Self::builder().model_path(model_path.as_ref()).build()
+    }
+
+    pub fn context_size(&self) -> u32 {
+        self.context_size
     }
 
     pub fn generate_stream(
         &self,
         request: LlamaRequest,
     ) -> Result<impl futures_util::Stream<Item = String>, crate::Error> {
+        let prompt = self
+            .model
+            .apply_chat_template(&self.tpl, &request.messages, true)?;
+        let tokens = self.model.str_to_token(&prompt, AddBos::Always)?;
+
+        let max_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS);
+        let limit = self.context_size.saturating_sub(max_tokens) as usize;
+        if tokens.len() > limit {
+            return Err(crate::Error::ContextOverflow {
+                tokens: tokens.len(),
+                limit,
+            });
+        }
+
         let (response_sender, response_receiver) = tokio::sync::mpsc::unbounded_channel::<String>();
 
         let task = Task::Generate {
             request,
+            tokens,
             response_sender,
         };
 
@@ -220,8 +364,90 @@ mod tests {
         let request = LlamaRequest {
             messages: vec![],
             grammar: Some(hypr_gbnf::GBNF::Enhance(Some(vec!["header".to_string()])).build()),
+            ..Default::default()
         };
 
         run(&llama, request, true).await;
     }
+
+    fn count_request(stop: Vec<String>) -> LlamaRequest {
+        LlamaRequest {
+            messages: vec![LlamaChatMessage::new(
+                "user".into(),
+                "Count from 1 to 10, separated by commas. Reply with the numbers only.".into(),
+            )
+            .unwrap()],
+            stop,
+            ..Default::default()
+        }
+    }
+
+    // cargo test test_sampling_params -p llama -- --nocapture --ignored
+    #[ignore]
+    #[tokio::test]
+    async fn test_sampling_params() {
+        let llama = get_model();
+        let request = || LlamaRequest {
+            temperature: Some(0.7),
+            top_k: Some(40),
+            top_p: Some(0.9),
+            seed: Some(42),
+            max_tokens: Some(8),
+            ..count_request(vec![])
+        };
+
+        // Same seed, same output, also when the second run reuses the cached prompt.
+        let first = run(&llama, request(), false).await;
+        let second = run(&llama, request(), false).await;
+        assert_eq!(first, second);
+
+        let tokens = llama.model.str_to_token(&first, AddBos::Never).unwrap();
+        assert!(tokens.len() <= 8, "{:?}", first);
+    }
+
+    // cargo test test_stop -p llama -- --nocapture --ignored
+    #[ignore]
+    #[tokio::test]
+    async fn test_stop() {
+        let llama = get_model();
+
+        let full = run(&llama, count_request(vec![]), false).await;
+        assert!(full.contains('5') && full.contains('7'), "{:?}", full);
+
+        // Neither the stop sequence nor anything after it is streamed.
+        let stopped = run(&llama, count_request(vec!["5".to_string()]), false).await;
+        assert!(stopped.contains('4'), "{:?}", stopped);
+        assert!(
+            !stopped.contains('5') && !stopped.contains('7'),
+            "{:?}",
+            stopped
+        );
+    }
+
+    // cargo test test_prompt_cache_speedup -p llama -- --nocapture --ignored
+    #[ignore]
+    #[tokio::test]
+    async fn test_prompt_cache_speedup() {
+        let llama = get_model();
+
+        let transcript = hypr_template::timeline(hypr_data::english_3::WORDS_JSON.to_string());
+        let request = || LlamaRequest {
+            messages: vec![
+                LlamaChatMessage::new("system".into(), "Summarize the transcript.".into()).unwrap(),
+                LlamaChatMessage::new("user".into(), transcript.clone()).unwrap(),
+            ],
+            max_tokens: Some(1),
+            ..Default::default()
+        };
+
+        let mut elapsed = Vec::new();
+        for _ in 0..3 {
+            let start = std::time::Instant::now();
+            run(&llama, request(), false).await;
+            elapsed.push(start.elapsed());
+        }
+
+        assert!(elapsed[1] * 2 < elapsed[0], "{:?}", elapsed);
+        assert!(elapsed[2] * 2 < elapsed[0], "{:?}", elapsed);
+    }
 }
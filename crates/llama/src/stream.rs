@@ -184,6 +184,30 @@ where
     })
 }
 
+#[derive(Debug, PartialEq)]
+pub(crate) enum StopMatch {
+    // Text before the first stop sequence. Generation should end here.
+    Found(usize),
+    // Text that can be emitted without cutting a stop sequence that is still being generated.
+    Safe(usize),
+}
+
+pub(crate) fn match_stop(text: &str, stop: &[String]) -> StopMatch {
+    let stop = stop.iter().filter(|s| !s.is_empty());
+
+    if let Some(pos) = stop.clone().filter_map(|s| text.find(s.as_str())).min() {
+        return StopMatch::Found(pos);
+    }
+
+    let safe = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| stop.clone().any(|s| s.starts_with(&text[i..])))
+        .unwrap_or(text.len());
+
+    StopMatch::Safe(safe)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,4 +260,17 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_match_stop() {
+        let stop = vec!["</s>".to_string(), "END".to_string()];
+
+        assert_eq!(match_stop("hello", &stop), StopMatch::Safe(5));
+        assert_eq!(match_stop("hello </", &stop), StopMatch::Safe(6));
+        assert_eq!(match_stop("hello E", &stop), StopMatch::Safe(6));
+        assert_eq!(match_stop("hello </s> world", &stop), StopMatch::Found(6));
+        assert_eq!(match_stop("a END b </s>", &stop), StopMatch::Found(2));
+        assert_eq!(match_stop("안녕", &stop), StopMatch::Safe(6));
+        assert_eq!(match_stop("hello", &[]), StopMatch::Safe(5));
+    }
 }
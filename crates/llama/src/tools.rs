@@ -0,0 +1,81 @@
+use async_openai::types::ChatCompletionTool;
+use hypr_gbnf::{TOOL_CALL_END, TOOL_CALL_START};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ToolCall {
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+// Describes the tools in the format `hypr_gbnf::GBNF::ToolCall` constrains the output to.
+pub fn tools_prompt(tools: &[ChatCompletionTool]) -> String {
+    let tools = tools
+        .iter()
+        .map(|tool| serde_json::to_string(&tool.function).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "You can call the tools below. To call one, respond only with {}{{\"name\": <tool name>, \"arguments\": <arguments object>}}{} for each call. Results come back as messages from the `tool` role.\n\n<tools>\n{}\n</tools>",
+        TOOL_CALL_START, TOOL_CALL_END, tools
+    )
+}
+
+pub fn format_tool_call(call: &ToolCall) -> String {
+    format!(
+        "{}{}{}",
+        TOOL_CALL_START,
+        serde_json::to_string(call).unwrap(),
+        TOOL_CALL_END
+    )
+}
+
+// Calls that fail to parse are skipped. Empty if the output is plain text.
+pub fn parse_tool_calls(text: &str) -> Vec<ToolCall> {
+    let mut calls = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(TOOL_CALL_START) {
+        let after = &rest[start + TOOL_CALL_START.len()..];
+        let Some(end) = after.find(TOOL_CALL_END) else {
+            break;
+        };
+
+        if let Ok(call) = serde_json::from_str(after[..end].trim()) {
+            calls.push(call);
+        }
+        rest = &after[end + TOOL_CALL_END.len()..];
+    }
+
+    calls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_calls() {
+        let call = ToolCall {
+            name: "search_sessions".to_string(),
+            arguments: serde_json::json!({ "query": "roadmap" }),
+        };
+
+        assert_eq!(
+            parse_tool_calls(&format_tool_call(&call)),
+            vec![call.clone()]
+        );
+        assert_eq!(
+            parse_tool_calls(&format!(
+                "{}\n{}",
+                format_tool_call(&call),
+                format_tool_call(&call)
+            )),
+            vec![call.clone(), call.clone()]
+        );
+
+        assert!(parse_tool_calls("The meeting was about the roadmap.").is_empty());
+        assert!(parse_tool_calls("<tool_call>{\"name\": ").is_empty());
+        assert!(parse_tool_calls("<tool_call>not json</tool_call>").is_empty());
+    }
+}
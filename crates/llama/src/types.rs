@@ -1,7 +1,4 @@
-use async_openai::types::{
-    ChatCompletionRequestAssistantMessageContent, ChatCompletionRequestMessage,
-    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessageContent,
-};
+use async_openai::types::ChatCompletionRequestMessage;
 
 pub use llama_cpp_2::model::LlamaChatMessage;
 
@@ -11,37 +8,97 @@ pub trait FromOpenAI {
 
 impl FromOpenAI for LlamaChatMessage {
     fn from_openai(message: &ChatCompletionRequestMessage) -> Self {
-        match message {
-            ChatCompletionRequestMessage::System(system) => {
-                let content = match &system.content {
-                    ChatCompletionRequestSystemMessageContent::Text(text) => text,
-                    _ => todo!(),
-                };
-
-                LlamaChatMessage::new("system".into(), content.into()).unwrap()
-            }
-            ChatCompletionRequestMessage::Assistant(assistant) => {
-                let content = match &assistant.content {
-                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text,
-                    _ => todo!(),
-                };
-                LlamaChatMessage::new("assistant".into(), content.into()).unwrap()
-            }
-            ChatCompletionRequestMessage::User(user) => {
-                let content = match &user.content {
-                    ChatCompletionRequestUserMessageContent::Text(text) => text,
-                    _ => todo!(),
-                };
+        let (role, content) = role_and_content(message);
+        LlamaChatMessage::new(role, content).unwrap()
+    }
+}
+
+// Goes through the wire format, which is the same for every role and content type.
+// Non-text parts are dropped, and tool calls are written the way `crate::tools_prompt` asks for.
+fn role_and_content(message: &ChatCompletionRequestMessage) -> (String, String) {
+    let value = serde_json::to_value(message).unwrap();
+
+    let role = value["role"].as_str().unwrap_or("user").to_string();
+
+    let mut content = match &value["content"] {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str().or(part["refusal"].as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+
+    if let Some(tool_calls) = value["tool_calls"].as_array() {
+        for call in tool_calls {
+            let function = &call["function"];
+            let arguments = function["arguments"]
+                .as_str()
+                .and_then(|args| serde_json::from_str(args).ok())
+                .unwrap_or(serde_json::Value::Object(Default::default()));
 
-                LlamaChatMessage::new("user".into(), content.into()).unwrap()
+            if !content.is_empty() {
+                content.push('\n');
             }
-            _ => todo!(),
+            content.push_str(&crate::format_tool_call(&crate::ToolCall {
+                name: function["name"].as_str().unwrap_or_default().to_string(),
+                arguments,
+            }));
         }
     }
+
+    (role, content)
 }
 
 #[derive(Default)]
 pub struct LlamaRequest {
     pub grammar: Option<String>,
     pub messages: Vec<LlamaChatMessage>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<i32>,
+    pub max_tokens: Option<u32>,
+    pub stop: Vec<String>,
+    pub seed: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_and_content() {
+        let messages: Vec<ChatCompletionRequestMessage> =
+            serde_json::from_value(serde_json::json!([
+                { "role": "system", "content": "You are helpful." },
+                { "role": "user", "content": [{ "type": "text", "text": "find the roadmap meeting" }] },
+                {
+                    "role": "assistant",
+                    "tool_calls": [{
+                        "id": "call_0",
+                        "type": "function",
+                        "function": { "name": "search_sessions", "arguments": "{\"query\":\"roadmap\"}" }
+                    }]
+                },
+                { "role": "tool", "tool_call_id": "call_0", "content": "[]" }
+            ]))
+            .unwrap();
+
+        let converted = messages.iter().map(role_and_content).collect::<Vec<_>>();
+
+        assert_eq!(
+            converted,
+            vec![
+                ("system".to_string(), "You are helpful.".to_string()),
+                ("user".to_string(), "find the roadmap meeting".to_string()),
+                (
+                    "assistant".to_string(),
+                    r#"<tool_call>{"name":"search_sessions","arguments":{"query":"roadmap"}}</tool_call>"#
+                        .to_string()
+                ),
+                ("tool".to_string(), "[]".to_string()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,53 @@
+mod openai;
+
+pub use openai::*;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+pub struct MockServer {
+    addr: SocketAddr,
+    api_base: String,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Pass this to the `api_base` of the client under test.
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+pub(crate) async fn serve(router: axum::Router) -> MockServer {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+    tokio::spawn(async move {
+        axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = rx.await;
+            })
+            .await
+            .unwrap();
+    });
+
+    MockServer {
+        addr,
+        api_base: format!("http://{}", addr),
+        shutdown: Some(tx),
+    }
+}
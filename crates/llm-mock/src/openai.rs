@@ -0,0 +1,74 @@
+// https://platform.openai.com/docs/api-reference/chat/create
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    response::{sse, IntoResponse},
+    routing::post,
+    Json, Router,
+};
+use serde_json::json;
+
+use crate::MockServer;
+
+type Reply = Arc<dyn Fn(&serde_json::Value) -> String + Send + Sync>;
+
+// Speaks the chat completions API. Each request is answered with `reply(request)`,
+// as a single chunk when streaming is asked for.
+pub async fn serve_openai(
+    reply: impl Fn(&serde_json::Value) -> String + Send + Sync + 'static,
+) -> MockServer {
+    let reply: Reply = Arc::new(reply);
+
+    let router = Router::new()
+        .route("/chat/completions", post(chat_completions))
+        .with_state(reply);
+
+    crate::serve(router).await
+}
+
+async fn chat_completions(
+    State(reply): State<Reply>,
+    Json(request): Json<serde_json::Value>,
+) -> axum::response::Response {
+    let content = reply(&request);
+    let model = request["model"].clone();
+
+    if !request["stream"].as_bool().unwrap_or(false) {
+        return Json(json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion",
+            "created": 0,
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": content },
+                "finish_reason": "stop"
+            }]
+        }))
+        .into_response();
+    }
+
+    let chunk = json!({
+        "id": "chatcmpl-mock",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }]
+    });
+
+    let events = [
+        sse::Event::default().data(chunk.to_string()),
+        sse::Event::default().data("[DONE]"),
+    ];
+
+    sse::Sse::new(futures_util::stream::iter(
+        events.into_iter().map(Ok::<_, std::convert::Infallible>),
+    ))
+    .into_response()
+}
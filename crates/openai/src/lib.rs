@@ -25,11 +25,14 @@ impl OpenAIClientBuilder {
     pub fn build(self) -> OpenAIClient {
         let mut headers = reqwest::header::HeaderMap::new();
 
-        let auth_str = format!("Bearer {}", self.api_key.unwrap());
-        let mut auth_value = reqwest::header::HeaderValue::from_str(&auth_str).unwrap();
-        auth_value.set_sensitive(true);
+        // Local servers don't need one.
+        if let Some(api_key) = self.api_key {
+            let auth_str = format!("Bearer {}", api_key);
+            let mut auth_value = reqwest::header::HeaderValue::from_str(&auth_str).unwrap();
+            auth_value.set_sensitive(true);
 
-        headers.insert(reqwest::header::AUTHORIZATION, auth_value);
+            headers.insert(reqwest::header::AUTHORIZATION, auth_value);
+        }
 
         let reqwest_client = reqwest::Client::builder()
             .default_headers(headers)
@@ -65,6 +68,7 @@ impl OpenAIClient {
         let mut url = self.api_base.clone();
         url.path_segments_mut()
             .unwrap()
+            .pop_if_empty()
             .push("chat")
             .push("completions");
 
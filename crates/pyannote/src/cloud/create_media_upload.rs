@@ -0,0 +1,39 @@
+use super::PyannoteClient;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[specta(rename = "MediaUploadRequest")]
+pub struct Request {
+    // `media://<key>`, which jobs then take as their `url`.
+    pub url: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(untagged)]
+#[specta(rename = "MediaUploadResponse")]
+pub enum Response {
+    // Presigned, to `PUT` the audio to.
+    Ok { url: String },
+    Error { message: String },
+}
+
+pub fn media_url(key: &str) -> String {
+    format!("media://{}", key)
+}
+
+// https://docs.pyannote.ai/api-reference/create-media-upload-url
+impl PyannoteClient {
+    pub async fn create_media_upload(&self, req: Request) -> Result<Response, reqwest::Error> {
+        let mut url = self.api_base.clone();
+        url.set_path("/v1/media/input");
+
+        let res = self
+            .client
+            .post(url)
+            .json(&req)
+            .send()
+            .await?
+            .json::<Response>()
+            .await?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+
+use super::{
+    get_job::{self, JobResult, JobStatus},
+    submit_diarization_job, DiarizationSegment, Error, PyannoteClient,
+};
+
+// Jobs take about as long as a fraction of the audio, so polling starts fast and slows down for long recordings.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_POLLS: usize = 60;
+
+impl PyannoteClient {
+    // Submits the audio and waits for its diarization, polling with exponential backoff.
+    pub async fn diarize(
+        &self,
+        audio_url: impl Into<String>,
+    ) -> Result<Vec<DiarizationSegment>, Error> {
+        let res = self
+            .submit_diarization_job(submit_diarization_job::Request {
+                url: audio_url.into(),
+                webhook: None,
+                num_speakers: None,
+                confidence: None,
+            })
+            .await?;
+
+        let job_id = match res {
+            submit_diarization_job::Response::Ok { job_id, .. } => job_id,
+            submit_diarization_job::Response::Error { message } => {
+                return Err(Error::ApiError(message))
+            }
+        };
+
+        (|| self.poll_job(&job_id))
+            .retry(
+                ExponentialBuilder::default()
+                    .with_min_delay(MIN_POLL_INTERVAL)
+                    .with_max_delay(MAX_POLL_INTERVAL)
+                    .with_max_times(MAX_POLLS),
+            )
+            .sleep(tokio::time::sleep)
+            .when(|e| matches!(e, Error::JobPending(_)))
+            .await
+    }
+
+    // Returns the segments once the job is done, or `Error::JobPending` until then.
+    pub async fn poll_job(&self, job_id: &str) -> Result<Vec<DiarizationSegment>, Error> {
+        let res = self
+            .get_job(get_job::Request {
+                job_id: job_id.to_string(),
+            })
+            .await?;
+
+        match res {
+            get_job::Response::Ok { status, output, .. } => job_output(job_id, status, output),
+            get_job::Response::Error { message } => Err(Error::ApiError(message)),
+        }
+    }
+}
+
+// Shared with webhook payloads, which carry the same status and output as a polled job.
+pub(crate) fn job_output(
+    job_id: &str,
+    status: JobStatus,
+    output: Option<JobResult>,
+) -> Result<Vec<DiarizationSegment>, Error> {
+    match (status, output) {
+        (JobStatus::Succeeded, Some(JobResult::Diarization(result))) => Ok(result.diarization),
+        (JobStatus::Succeeded, None) => Err(Error::ApiError(format!(
+            "job {} succeeded without output",
+            job_id
+        ))),
+        (JobStatus::Failed, _) => Err(Error::JobFailed(job_id.to_string())),
+        (JobStatus::Canceled, _) => Err(Error::JobCanceled(job_id.to_string())),
+        (JobStatus::Pending | JobStatus::Created | JobStatus::Running, _) => {
+            Err(Error::JobPending(job_id.to_string()))
+        }
+    }
+}
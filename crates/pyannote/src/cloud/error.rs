@@ -0,0 +1,13 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    #[error("pyannote api error: {0}")]
+    ApiError(String),
+    #[error("job {0} is still running")]
+    JobPending(String),
+    #[error("job {0} failed")]
+    JobFailed(String),
+    #[error("job {0} was canceled")]
+    JobCanceled(String),
+}
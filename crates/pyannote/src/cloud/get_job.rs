@@ -18,19 +18,23 @@ pub enum Response {
         created_at: String,
         #[serde(rename = "updatedAt")]
         updated_at: String,
-        output: JobResult,
+        // Only once the job succeeded.
+        #[serde(default)]
+        output: Option<JobResult>,
     },
     Error {
         message: String,
     },
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum JobStatus {
     #[serde(rename = "pending")]
     Pending,
     #[serde(rename = "created")]
     Created,
+    #[serde(rename = "running")]
+    Running,
     #[serde(rename = "succeeded")]
     Succeeded,
     #[serde(rename = "canceled")]
@@ -39,26 +43,26 @@ pub enum JobStatus {
     Failed,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 #[serde(untagged)]
 pub enum JobResult {
     Diarization(DiarizationResult),
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct DiarizationResult {
     pub diarization: Vec<DiarizationSegment>,
     pub confidence: Option<DiarizationConfidence>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct DiarizationSegment {
     pub speaker: String,
     pub start: f32,
     pub end: f32,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct DiarizationConfidence {
     pub resolution: f32,
     pub score: Vec<f32>,
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use hypr_listener_interface::{SpeakerIdentity, Word};
+
+use super::DiarizationSegment;
+
+// Words this close to a segment, but outside it, still get its speaker.
+const MAX_GAP_MS: u64 = 500;
+
+// Labels words with the speaker of the segment they overlap the most. Speakers like `SPEAKER_00` become
+// `Unassigned` indices in order of first appearance. Words assigned to a human are left alone.
+// Returns whether any word changed.
+pub fn assign_speakers(words: &mut [Word], segments: &[DiarizationSegment]) -> bool {
+    let mut indices: HashMap<&str, u8> = HashMap::new();
+    let mut ordered: Vec<&DiarizationSegment> = segments.iter().collect();
+    ordered.sort_by(|a, b| a.start.total_cmp(&b.start));
+    for segment in &ordered {
+        let next = indices.len() as u8;
+        indices.entry(segment.speaker.as_str()).or_insert(next);
+    }
+
+    let mut changed = false;
+    for word in words.iter_mut() {
+        if matches!(word.speaker, Some(SpeakerIdentity::Assigned { .. })) {
+            continue;
+        }
+        let (Some(start_ms), Some(end_ms)) = (word.start_ms, word.end_ms) else {
+            continue;
+        };
+
+        let closest = ordered
+            .iter()
+            .map(|s| ((s.start * 1000.0) as u64, (s.end * 1000.0) as u64, s))
+            .filter(|(start, end, _)| *start <= end_ms + MAX_GAP_MS && start_ms <= end + MAX_GAP_MS)
+            .max_by_key(|(start, end, _)| end_ms.min(*end) as i64 - start_ms.max(*start) as i64);
+
+        if let Some((_, _, segment)) = closest {
+            let speaker = Some(SpeakerIdentity::Unassigned {
+                index: indices[segment.speaker.as_str()],
+            });
+            if word.speaker != speaker {
+                word.speaker = speaker;
+                changed = true;
+            }
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> Word {
+        Word {
+            text: text.to_string(),
+            speaker: None,
+            confidence: None,
+            start_ms: Some(start_ms),
+            end_ms: Some(end_ms),
+        }
+    }
+
+    fn segment(speaker: &str, start: f32, end: f32) -> DiarizationSegment {
+        DiarizationSegment {
+            speaker: speaker.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_assign_speakers() {
+        let segments = vec![
+            segment("SPEAKER_01", 0.0, 1.0),
+            segment("SPEAKER_00", 1.0, 2.0),
+            segment("SPEAKER_01", 2.0, 3.0),
+        ];
+
+        let jane = SpeakerIdentity::Assigned {
+            id: "jane".to_string(),
+            label: "Jane".to_string(),
+        };
+
+        let mut words = vec![
+            word("a", 100, 300),
+            word("b", 900, 1400),
+            word("c", 2100, 2500),
+            word("d", 2600, 2900),
+            word("e", 9000, 9100),
+        ];
+        words[3].speaker = Some(jane.clone());
+
+        assert!(assign_speakers(&mut words, &segments));
+        let speakers: Vec<_> = words.iter().map(|w| w.speaker.clone()).collect();
+        assert_eq!(
+            speakers,
+            vec![
+                Some(SpeakerIdentity::Unassigned { index: 0 }),
+                Some(SpeakerIdentity::Unassigned { index: 1 }),
+                Some(SpeakerIdentity::Unassigned { index: 0 }),
+                Some(jane),
+                None,
+            ]
+        );
+
+        assert!(!assign_speakers(&mut words, &segments));
+    }
+}
@@ -1,6 +1,16 @@
+mod diarize;
+mod error;
+mod merge;
+
+pub mod create_media_upload;
 pub mod get_job;
 pub mod submit_diarization_job;
 pub mod test_key;
+pub mod webhook;
+
+pub use error::*;
+pub use get_job::DiarizationSegment;
+pub use merge::*;
 
 #[derive(Debug, Clone)]
 pub struct PyannoteClient {
@@ -10,12 +20,16 @@ pub struct PyannoteClient {
 
 impl PyannoteClient {
     pub fn builder() -> PyannoteClientBuilder {
-        PyannoteClientBuilder { api_key: None }
+        PyannoteClientBuilder {
+            api_key: None,
+            api_base: None,
+        }
     }
 }
 
 pub struct PyannoteClientBuilder {
     api_key: Option<String>,
+    api_base: Option<String>,
 }
 
 impl PyannoteClientBuilder {
@@ -24,6 +38,11 @@ impl PyannoteClientBuilder {
         self
     }
 
+    pub fn api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
     pub fn build(self) -> PyannoteClient {
         let mut headers = reqwest::header::HeaderMap::new();
 
@@ -39,7 +58,11 @@ impl PyannoteClientBuilder {
             .build()
             .unwrap();
 
-        let api_base = "https://api.pyannote.ai".parse().unwrap();
+        let api_base = self
+            .api_base
+            .unwrap_or("https://api.pyannote.ai".to_string())
+            .parse()
+            .unwrap();
         PyannoteClient { client, api_base }
     }
 }
@@ -88,6 +111,84 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_diarize_mock() {
+        let script = hypr_stt_mock::DiarizationScript::english();
+        let server = hypr_stt_mock::serve_pyannote(script.clone(), 2).await;
+
+        let client = PyannoteClient::builder()
+            .api_key("test")
+            .api_base(server.api_base())
+            .build();
+
+        let segments = client
+            .diarize("https://example.com/audio.wav")
+            .await
+            .unwrap();
+        assert_eq!(segments.len(), script.segments.len());
+        assert_eq!(segments[0].speaker, script.segments[0].speaker);
+
+        assert!(matches!(
+            client.poll_job("unknown").await,
+            Err(Error::ApiError(_))
+        ));
+        assert!(matches!(client.diarize("").await, Err(Error::ApiError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_media_upload_mock() {
+        let script = hypr_stt_mock::DiarizationScript::english();
+        let server = hypr_stt_mock::serve_pyannote(script.clone(), 0).await;
+
+        let client = PyannoteClient::builder()
+            .api_key("test")
+            .api_base(server.api_base())
+            .build();
+
+        let audio_url = create_media_upload::media_url("session/audio.wav");
+
+        // Jobs can't read media that wasn't uploaded yet.
+        assert!(matches!(
+            client.diarize(&audio_url).await,
+            Err(Error::ApiError(_))
+        ));
+
+        let res = client
+            .create_media_upload(create_media_upload::Request {
+                url: audio_url.clone(),
+            })
+            .await
+            .unwrap();
+        let create_media_upload::Response::Ok { url: upload_url } = res else {
+            panic!("{:?}", res);
+        };
+
+        let res = reqwest::Client::new()
+            .put(upload_url)
+            .body(vec![0u8; 16])
+            .send()
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+
+        let segments = client.diarize(&audio_url).await.unwrap();
+        assert_eq!(segments.len(), script.segments.len());
+    }
+
+    #[test]
+    fn test_webhook_signature() {
+        let body = br#"{"jobId":"job-0","status":"succeeded","output":{"diarization":[]}}"#;
+        let signature = webhook::sign("secret", "1700000000", body);
+
+        assert!(webhook::verify("secret", "1700000000", body, &signature));
+        assert!(!webhook::verify("other", "1700000000", body, &signature));
+        assert!(!webhook::verify("secret", "1700000001", body, &signature));
+        assert!(!webhook::verify("secret", "1700000000", body, "not hex"));
+
+        let payload: webhook::WebhookPayload = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload.segments().unwrap(), vec![]);
+    }
+
     // cargo test test_get_job -p pyannote --features cloud --  --ignored --nocapture
     #[ignore]
     #[tokio::test]
@@ -0,0 +1,50 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::{
+    get_job::{JobResult, JobStatus},
+    DiarizationSegment, Error,
+};
+
+// https://docs.pyannote.ai/webhooks/verify
+pub const TIMESTAMP_HEADER: &str = "X-Request-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WebhookPayload {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub output: Option<JobResult>,
+}
+
+impl WebhookPayload {
+    pub fn segments(self) -> Result<Vec<DiarizationSegment>, Error> {
+        super::diarize::job_output(&self.job_id, self.status, self.output)
+    }
+}
+
+// Hex-encoded HMAC-SHA256 of `v0:{timestamp}:{body}`, keyed with the webhook secret.
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    hex::encode(mac(secret, timestamp, body).finalize().into_bytes())
+}
+
+pub fn verify(secret: &str, timestamp: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    // Constant time, so the signature can't be guessed byte by byte.
+    mac(secret, timestamp, body)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+fn mac(secret: &str, timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(b"v0:");
+    mac.update(timestamp.as_bytes());
+    mac.update(b":");
+    mac.update(body);
+    mac
+}
@@ -0,0 +1,236 @@
+// Incremental agglomerative clustering of speaker embeddings. Each embedding joins the closest cluster
+// if it is similar enough, otherwise it starts a new one, so speaker indices stay stable as audio comes in.
+#[derive(Debug, Clone)]
+pub struct OnlineClustering {
+    threshold: f32,
+    max_speakers: usize,
+    clusters: Vec<Cluster>,
+}
+
+#[derive(Debug, Clone)]
+struct Cluster {
+    centroid: Vec<f32>,
+    count: usize,
+}
+
+impl Default for OnlineClustering {
+    fn default() -> Self {
+        Self::new(0.5, 8)
+    }
+}
+
+impl OnlineClustering {
+    pub fn new(threshold: f32, max_speakers: usize) -> Self {
+        Self {
+            threshold,
+            max_speakers,
+            clusters: Vec::new(),
+        }
+    }
+
+    pub fn num_speakers(&self) -> usize {
+        self.clusters.len()
+    }
+
+    // Centroid of each speaker, by index, with the number of embeddings it was averaged from.
+    pub fn speakers(&self) -> impl Iterator<Item = (&[f32], usize)> {
+        self.clusters
+            .iter()
+            .map(|c| (c.centroid.as_slice(), c.count))
+    }
+
+    // Returns the speaker index of the embedding.
+    pub fn assign(&mut self, embedding: &[f32]) -> usize {
+        let closest = self
+            .clusters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, cosine_similarity(&c.centroid, embedding)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        match closest {
+            Some((i, similarity))
+                if similarity >= self.threshold || self.clusters.len() >= self.max_speakers =>
+            {
+                self.clusters[i].add(embedding);
+                i
+            }
+            _ => {
+                self.clusters.push(Cluster {
+                    centroid: embedding.to_vec(),
+                    count: 1,
+                });
+                self.clusters.len() - 1
+            }
+        }
+    }
+}
+
+impl Cluster {
+    // Running mean, so early embeddings don't dominate the centroid.
+    fn add(&mut self, embedding: &[f32]) {
+        self.count += 1;
+        let weight = 1.0 / self.count as f32;
+
+        for (c, e) in self.centroid.iter_mut().zip(embedding) {
+            *c += (e - *c) * weight;
+        }
+    }
+}
+
+// Agglomerative clustering of all embeddings at once, for when the whole recording is available.
+// The two most similar clusters are merged until none are similar enough, or while there are more than `max_speakers`.
+// Returns the speaker index of each embedding, in order of first appearance.
+pub fn cluster(embeddings: &[Vec<f32>], threshold: f32, max_speakers: usize) -> Vec<usize> {
+    let n = embeddings.len();
+
+    let mut clusters: Vec<Option<Cluster>> = embeddings
+        .iter()
+        .map(|e| {
+            Some(Cluster {
+                centroid: e.clone(),
+                count: 1,
+            })
+        })
+        .collect();
+    let mut members: Vec<usize> = (0..n).collect();
+
+    // Merged-away clusters and the diagonal are `f32::MIN`, so they are never the closest.
+    let mut similarities = vec![vec![f32::MIN; n]; n];
+    for (i, a) in embeddings.iter().enumerate() {
+        for (j, b) in embeddings.iter().enumerate().skip(i + 1) {
+            let similarity = cosine_similarity(a, b);
+            similarities[i][j] = similarity;
+            similarities[j][i] = similarity;
+        }
+    }
+
+    // Closest cluster to each one, so finding the pair to merge doesn't rescan the whole matrix.
+    let closest_in = |row: &[f32]| {
+        row.iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((0, f32::MIN))
+    };
+    let mut closest: Vec<(usize, f32)> = similarities.iter().map(|row| closest_in(row)).collect();
+
+    let mut remaining = n;
+    while remaining > 1 {
+        let (i, (j, similarity)) = closest
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(i, _)| clusters[*i].is_some())
+            .max_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+            .unwrap();
+
+        if similarity < threshold && remaining <= max_speakers {
+            break;
+        }
+
+        let merged = clusters[j].take().unwrap();
+        let cluster = clusters[i].as_mut().unwrap();
+        for (c, m) in cluster.centroid.iter_mut().zip(&merged.centroid) {
+            *c = (*c * cluster.count as f32 + m * merged.count as f32)
+                / (cluster.count + merged.count) as f32;
+        }
+        cluster.count += merged.count;
+        remaining -= 1;
+
+        for m in members.iter_mut().filter(|m| **m == j) {
+            *m = i;
+        }
+
+        similarities[j].fill(f32::MIN);
+        for row in similarities.iter_mut() {
+            row[j] = f32::MIN;
+        }
+
+        let centroid = clusters[i].as_ref().unwrap().centroid.clone();
+        for (k, other) in clusters.iter().enumerate() {
+            match other {
+                Some(other) if k != i => {
+                    let similarity = cosine_similarity(&centroid, &other.centroid);
+                    similarities[i][k] = similarity;
+                    similarities[k][i] = similarity;
+                }
+                _ => {}
+            }
+        }
+
+        closest[i] = closest_in(&similarities[i]);
+        for (k, other) in clusters.iter().enumerate() {
+            if k == i || other.is_none() {
+                continue;
+            }
+            if closest[k].0 == i || closest[k].0 == j {
+                closest[k] = closest_in(&similarities[k]);
+            } else if similarities[k][i] > closest[k].1 {
+                closest[k] = (i, similarities[k][i]);
+            }
+        }
+    }
+
+    let mut indices = std::collections::HashMap::new();
+    members
+        .into_iter()
+        .map(|m| {
+            let next = indices.len();
+            *indices.entry(m).or_insert(next)
+        })
+        .collect()
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        approx::assert_abs_diff_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), 1.0);
+        approx::assert_abs_diff_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        approx::assert_abs_diff_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_online_clustering() {
+        let mut clustering = OnlineClustering::new(0.8, 2);
+
+        assert_eq!(clustering.assign(&[1.0, 0.1, 0.0]), 0);
+        assert_eq!(clustering.assign(&[0.0, 1.0, 0.1]), 1);
+        assert_eq!(clustering.assign(&[0.9, 0.0, 0.1]), 0);
+        assert_eq!(clustering.assign(&[0.1, 0.9, 0.0]), 1);
+
+        // At `max_speakers`, a new voice goes to the closest speaker.
+        assert_eq!(clustering.assign(&[0.0, 0.2, 1.0]), 1);
+        assert_eq!(clustering.num_speakers(), 2);
+    }
+
+    #[test]
+    fn test_cluster() {
+        let embeddings = vec![
+            vec![0.0, 1.0, 0.1],
+            vec![1.0, 0.1, 0.0],
+            vec![0.1, 0.9, 0.0],
+            vec![0.9, 0.0, 0.1],
+            vec![0.0, 0.1, 1.0],
+        ];
+
+        assert_eq!(cluster(&embeddings, 0.8, 8), vec![0, 1, 0, 1, 2]);
+        // Past `max_speakers`, the closest clusters are merged anyway.
+        assert_eq!(cluster(&embeddings, 0.8, 2).iter().max(), Some(&1));
+        assert!(cluster(&[], 0.8, 2).is_empty());
+    }
+}
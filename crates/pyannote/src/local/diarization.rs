@@ -0,0 +1,352 @@
+use anyhow::Result;
+
+use super::{
+    clustering::{cluster, OnlineClustering},
+    embedding::EmbeddingExtractor,
+    segmentation::Segmenter,
+};
+
+// Long segments can hold several speakers, so they are embedded in pieces of at most this long.
+const MAX_EMBEDDING_MS: u64 = 3000;
+// Too short to embed reliably. These keep the speaker of the turn before them.
+const MIN_EMBEDDING_MS: u64 = 500;
+
+// Same as `OnlineClustering::default()`, but compared against every embedding of the recording at once.
+const OFFLINE_THRESHOLD: f32 = 0.5;
+const OFFLINE_MAX_SPEAKERS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SpeakerTurn {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speaker: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerEmbedding {
+    pub speaker: usize,
+    pub embedding: Vec<f32>,
+    // How many embeddings were averaged, which is how much to trust it.
+    pub count: usize,
+}
+
+// Online diarization: audio is segmented and embedded one segmentation window at a time,
+// and every window's turns are returned as soon as it is full.
+pub struct Diarizer {
+    segmenter: Segmenter,
+    extractor: EmbeddingExtractor,
+    clustering: OnlineClustering,
+    sample_rate: u32,
+    window_size: usize,
+    buffer: Vec<i16>,
+    // Samples already diarized, so turns are relative to the start of the stream.
+    processed: usize,
+    last_speaker: Option<usize>,
+}
+
+impl Diarizer {
+    pub fn new(sample_rate: u32) -> Result<Self> {
+        Self::with_clustering(sample_rate, OnlineClustering::default())
+    }
+
+    pub fn with_clustering(sample_rate: u32, clustering: OnlineClustering) -> Result<Self> {
+        Ok(Self {
+            segmenter: Segmenter::new(sample_rate)?,
+            extractor: EmbeddingExtractor::new(),
+            clustering,
+            sample_rate,
+            window_size: (sample_rate * 10) as usize,
+            buffer: Vec::new(),
+            processed: 0,
+            last_speaker: None,
+        })
+    }
+
+    pub fn push(&mut self, samples: &[f32]) -> Result<Vec<SpeakerTurn>> {
+        self.buffer.extend(
+            samples
+                .iter()
+                .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
+        );
+
+        let mut turns = Vec::new();
+        while self.buffer.len() >= self.window_size {
+            let window: Vec<i16> = self.buffer.drain(..self.window_size).collect();
+            turns.extend(self.process(&window)?);
+        }
+        Ok(turns)
+    }
+
+    pub fn speakers(&self) -> Vec<SpeakerEmbedding> {
+        self.clustering
+            .speakers()
+            .enumerate()
+            .map(|(speaker, (embedding, count))| SpeakerEmbedding {
+                speaker,
+                embedding: embedding.to_vec(),
+                count,
+            })
+            .collect()
+    }
+
+    // Diarizes whatever is left of the last, partial window.
+    pub fn finish(&mut self) -> Result<Vec<SpeakerTurn>> {
+        let window = std::mem::take(&mut self.buffer);
+        if window.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.process(&window)
+    }
+
+    fn process(&mut self, window: &[i16]) -> Result<Vec<SpeakerTurn>> {
+        let offset_ms = samples_to_ms(self.processed, self.sample_rate);
+        self.processed += window.len();
+
+        let mut turns: Vec<SpeakerTurn> = Vec::new();
+        for segment in self.segmenter.process(window, self.sample_rate)? {
+            let piece_len = (MAX_EMBEDDING_MS * self.sample_rate as u64 / 1000) as usize;
+            let segment_start_ms = offset_ms + (segment.start * 1000.0) as u64;
+
+            for (i, piece) in segment.samples.chunks(piece_len).enumerate() {
+                let start_ms = segment_start_ms + i as u64 * MAX_EMBEDDING_MS;
+                let end_ms = start_ms + samples_to_ms(piece.len(), self.sample_rate);
+
+                let speaker = if end_ms - start_ms < MIN_EMBEDDING_MS {
+                    match self.last_speaker {
+                        Some(speaker) => speaker,
+                        None => continue,
+                    }
+                } else {
+                    let embedding = self.extractor.compute(piece)?;
+                    self.clustering.assign(&embedding)
+                };
+                self.last_speaker = Some(speaker);
+
+                push_turn(&mut turns, start_ms, end_ms, speaker);
+            }
+        }
+
+        Ok(turns)
+    }
+}
+
+pub struct Diarization {
+    pub turns: Vec<SpeakerTurn>,
+    pub speakers: Vec<SpeakerEmbedding>,
+}
+
+// A piece of speech, and the index of its embedding unless it was too short to embed.
+struct Piece {
+    start_ms: u64,
+    end_ms: u64,
+    embedding: Option<usize>,
+}
+
+// Offline diarization of a whole recording: every piece of speech is embedded as audio is pushed,
+// and clustered all at once in `finish`, so speakers are not stuck with the first embeddings heard of them.
+pub struct OfflineDiarizer {
+    segmenter: Segmenter,
+    extractor: EmbeddingExtractor,
+    sample_rate: u32,
+    window_size: usize,
+    buffer: Vec<i16>,
+    processed: usize,
+    pieces: Vec<Piece>,
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl OfflineDiarizer {
+    pub fn new(sample_rate: u32) -> Result<Self> {
+        Ok(Self {
+            segmenter: Segmenter::new(sample_rate)?,
+            extractor: EmbeddingExtractor::new(),
+            sample_rate,
+            window_size: (sample_rate * 10) as usize,
+            buffer: Vec::new(),
+            processed: 0,
+            pieces: Vec::new(),
+            embeddings: Vec::new(),
+        })
+    }
+
+    pub fn push(&mut self, samples: &[f32]) -> Result<()> {
+        self.buffer.extend(
+            samples
+                .iter()
+                .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
+        );
+
+        while self.buffer.len() >= self.window_size {
+            let window: Vec<i16> = self.buffer.drain(..self.window_size).collect();
+            self.embed(&window)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Diarization> {
+        let window = std::mem::take(&mut self.buffer);
+        if !window.is_empty() {
+            self.embed(&window)?;
+        }
+
+        let labels = cluster(&self.embeddings, OFFLINE_THRESHOLD, OFFLINE_MAX_SPEAKERS);
+
+        let mut turns: Vec<SpeakerTurn> = Vec::new();
+        let mut last_speaker = None;
+        for piece in &self.pieces {
+            let speaker = match piece.embedding {
+                Some(i) => labels[i],
+                None => match last_speaker {
+                    Some(speaker) => speaker,
+                    None => continue,
+                },
+            };
+            last_speaker = Some(speaker);
+            push_turn(&mut turns, piece.start_ms, piece.end_ms, speaker);
+        }
+
+        let num_speakers = labels.iter().max().map_or(0, |max| max + 1);
+        let speakers = (0..num_speakers)
+            .map(|speaker| {
+                let members: Vec<&Vec<f32>> = self
+                    .embeddings
+                    .iter()
+                    .zip(&labels)
+                    .filter(|(_, label)| **label == speaker)
+                    .map(|(embedding, _)| embedding)
+                    .collect();
+
+                let mut centroid = vec![0.0; members[0].len()];
+                for embedding in &members {
+                    for (c, e) in centroid.iter_mut().zip(embedding.iter()) {
+                        *c += e / members.len() as f32;
+                    }
+                }
+
+                SpeakerEmbedding {
+                    speaker,
+                    embedding: centroid,
+                    count: members.len(),
+                }
+            })
+            .collect();
+
+        Ok(Diarization { turns, speakers })
+    }
+
+    fn embed(&mut self, window: &[i16]) -> Result<()> {
+        let offset_ms = samples_to_ms(self.processed, self.sample_rate);
+        self.processed += window.len();
+
+        let piece_len = (MAX_EMBEDDING_MS * self.sample_rate as u64 / 1000) as usize;
+        for segment in self.segmenter.process(window, self.sample_rate)? {
+            let segment_start_ms = offset_ms + (segment.start * 1000.0) as u64;
+
+            for (i, piece) in segment.samples.chunks(piece_len).enumerate() {
+                let start_ms = segment_start_ms + i as u64 * MAX_EMBEDDING_MS;
+                let end_ms = start_ms + samples_to_ms(piece.len(), self.sample_rate);
+
+                let embedding = if end_ms - start_ms < MIN_EMBEDDING_MS {
+                    None
+                } else {
+                    self.embeddings.push(self.extractor.compute(piece)?);
+                    Some(self.embeddings.len() - 1)
+                };
+
+                self.pieces.push(Piece {
+                    start_ms,
+                    end_ms,
+                    embedding,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn samples_to_ms(samples: usize, sample_rate: u32) -> u64 {
+    samples as u64 * 1000 / sample_rate as u64
+}
+
+// Extends the last turn instead when it is the same speaker and they touch.
+fn push_turn(turns: &mut Vec<SpeakerTurn>, start_ms: u64, end_ms: u64, speaker: usize) {
+    match turns.last_mut() {
+        Some(last) if last.speaker == speaker && last.end_ms >= start_ms => {
+            last.end_ms = end_ms;
+        }
+        _ => turns.push(SpeakerTurn {
+            start_ms,
+            end_ms,
+            speaker,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::metrics::{diarization_error_rate, reference_turns};
+
+    // Bounds are per clip, so a regression on either one fails.
+    // None of them is measured yet: the ONNX models are not checked in, so these tests have not run here.
+    // They are provisional, and should be tightened to a little above the DER each clip actually gets.
+    macro_rules! test_diarization {
+        ($name:ident, $data:ident, $max_der:expr) => {
+            #[test]
+            fn $name() {
+                let audio: Vec<f32> = hypr_data::$data::AUDIO
+                    .chunks_exact(2)
+                    .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / i16::MAX as f32)
+                    .collect();
+
+                let mut diarizer = Diarizer::new(16000).unwrap();
+                let mut turns = Vec::new();
+                // Roughly what the listener feeds it.
+                for chunk in audio.chunks(1024) {
+                    turns.extend(diarizer.push(chunk).unwrap());
+                }
+                turns.extend(diarizer.finish().unwrap());
+
+                let reference = reference_turns(hypr_data::$data::DIARIZATION_JSON).unwrap();
+                let der = diarization_error_rate(&reference, &turns);
+
+                assert!(turns.windows(2).all(|w| w[0].start_ms <= w[1].start_ms));
+                assert!(der < $max_der, "DER {:.3} >= {}", der, $max_der);
+            }
+        };
+    }
+
+    test_diarization!(test_diarization_english_2, english_2, 0.3);
+    test_diarization!(test_diarization_korean_2, korean_2, 0.35);
+
+    macro_rules! test_offline_diarization {
+        ($name:ident, $data:ident, $max_der:expr) => {
+            #[test]
+            fn $name() {
+                let audio: Vec<f32> = hypr_data::$data::AUDIO
+                    .chunks_exact(2)
+                    .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / i16::MAX as f32)
+                    .collect();
+
+                let mut diarizer = OfflineDiarizer::new(16000).unwrap();
+                for chunk in audio.chunks(16000) {
+                    diarizer.push(chunk).unwrap();
+                }
+                let diarization = diarizer.finish().unwrap();
+
+                let reference = reference_turns(hypr_data::$data::DIARIZATION_JSON).unwrap();
+                let der = diarization_error_rate(&reference, &diarization.turns);
+
+                assert!(der < $max_der, "DER {:.3} >= {}", der, $max_der);
+                assert!(diarization
+                    .turns
+                    .iter()
+                    .all(|t| t.speaker < diarization.speakers.len()));
+            }
+        };
+    }
+
+    test_offline_diarization!(test_offline_diarization_english_2, english_2, 0.2);
+    test_offline_diarization!(test_offline_diarization_korean_2, korean_2, 0.25);
+}
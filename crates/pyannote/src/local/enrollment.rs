@@ -0,0 +1,150 @@
+use anyhow::Result;
+
+use super::{embedding::EmbeddingExtractor, segmentation::Segmenter};
+
+// Pieces of speech are embedded separately and averaged, like `Diarizer` does for a speaker.
+const PIECE_MS: u64 = 3000;
+
+// Builds a voice profile embedding from audio of a single speaker, such as the user's own microphone.
+// Silence is dropped, and the embedding is ready once there is `min_speech_ms` of speech.
+pub struct Enrollment {
+    segmenter: Segmenter,
+    extractor: EmbeddingExtractor,
+    sample_rate: u32,
+    window_size: usize,
+    min_speech_ms: u64,
+    buffer: Vec<i16>,
+    speech: Vec<i16>,
+    done: bool,
+}
+
+impl Enrollment {
+    pub fn new(sample_rate: u32, min_speech_ms: u64) -> Result<Self> {
+        Ok(Self {
+            segmenter: Segmenter::new(sample_rate)?,
+            extractor: EmbeddingExtractor::new(),
+            sample_rate,
+            window_size: (sample_rate * 10) as usize,
+            // At least one piece to embed.
+            min_speech_ms: min_speech_ms.max(PIECE_MS),
+            buffer: Vec::new(),
+            speech: Vec::new(),
+            done: false,
+        })
+    }
+
+    // Returns the embedding once, as soon as there is enough speech.
+    pub fn push(&mut self, samples: &[f32]) -> Result<Option<Vec<f32>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        self.buffer.extend(
+            samples
+                .iter()
+                .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
+        );
+
+        while self.buffer.len() >= self.window_size {
+            let window: Vec<i16> = self.buffer.drain(..self.window_size).collect();
+            for segment in self.segmenter.process(&window, self.sample_rate)? {
+                self.speech.extend(segment.samples);
+            }
+        }
+
+        let min_speech = (self.min_speech_ms * self.sample_rate as u64 / 1000) as usize;
+        if self.speech.len() < min_speech {
+            return Ok(None);
+        }
+
+        self.done = true;
+        self.embed().map(Some)
+    }
+
+    fn embed(&mut self) -> Result<Vec<f32>> {
+        let piece_len = (PIECE_MS * self.sample_rate as u64 / 1000) as usize;
+
+        let mut sum: Vec<f32> = Vec::new();
+        let mut count = 0;
+        for piece in self.speech.chunks_exact(piece_len) {
+            let embedding = self.extractor.compute(piece)?;
+            if sum.is_empty() {
+                sum = vec![0.0; embedding.len()];
+            }
+            for (s, e) in sum.iter_mut().zip(&embedding) {
+                *s += e;
+            }
+            count += 1;
+        }
+
+        Ok(sum.into_iter().map(|s| s / count as f32).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::{clustering::cosine_similarity, metrics::reference_turns};
+
+    const SAMPLES_PER_MS: usize = 16;
+
+    fn audio(pcm: &[u8]) -> Vec<i16> {
+        pcm.chunks_exact(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]))
+            .collect()
+    }
+
+    // Samples where `speaker` talks and nobody else does, so each clip holds a single voice.
+    fn solo(audio: &[i16], diarization: &str, speaker: usize) -> Vec<i16> {
+        // Bit `s` of each millisecond is set if speaker `s` talks.
+        let mut active = vec![0u64; audio.len() / SAMPLES_PER_MS + 1];
+        for turn in reference_turns(diarization).unwrap() {
+            let end = (turn.end_ms as usize).min(active.len());
+            for ms in (turn.start_ms as usize).min(end)..end {
+                active[ms] |= 1 << turn.speaker;
+            }
+        }
+
+        audio
+            .chunks(SAMPLES_PER_MS)
+            .zip(active)
+            .filter(|(_, active)| *active == 1 << speaker)
+            .flat_map(|(samples, _)| samples.iter().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_enrollment() {
+        let audio = audio(hypr_data::korean_2::AUDIO);
+        let json = hypr_data::korean_2::DIARIZATION_JSON;
+
+        // About 19s, split into a clip to enroll and a held out one to identify.
+        let speaker = solo(&audio, json, 0);
+        let (enroll, held_out) = speaker.split_at(12_000 * SAMPLES_PER_MS);
+        let other = solo(&audio, json, 1);
+
+        let mut enrollment = Enrollment::new(16000, 6000).unwrap();
+        let mut embedding = None;
+        let enroll: Vec<f32> = enroll.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+        for chunk in enroll.chunks(1024) {
+            if let Some(e) = enrollment.push(chunk).unwrap() {
+                assert!(embedding.is_none());
+                embedding = Some(e);
+            }
+        }
+        let embedding = embedding.unwrap();
+        assert!(embedding.iter().all(|x| x.is_finite()));
+
+        let piece = (PIECE_MS as usize) * SAMPLES_PER_MS;
+        let mut extractor = EmbeddingExtractor::new();
+        let same = extractor.compute(&held_out[..piece]).unwrap();
+        let different = extractor.compute(&other[..piece]).unwrap();
+
+        let same = cosine_similarity(&embedding, &same);
+        let different = cosine_similarity(&embedding, &different);
+        assert!(
+            same > different,
+            "same {same:.3} <= different {different:.3}"
+        );
+    }
+}
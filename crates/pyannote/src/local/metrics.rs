@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
+use super::diarization::SpeakerTurn;
+
+// Diarization error rate is scored on frames of this length.
+const FRAME_MS: u64 = 10;
+
+#[derive(serde::Deserialize)]
+struct ReferenceSegment {
+    start: u64,
+    end: u64,
+    speaker: String,
+}
+
+// Parses the `diarization.json` fixtures of `hypr_data`, numbering speakers by first appearance.
+pub fn reference_turns(json: &str) -> Result<Vec<SpeakerTurn>, serde_json::Error> {
+    let segments: Vec<ReferenceSegment> = serde_json::from_str(json)?;
+
+    let mut speakers: HashMap<String, usize> = HashMap::new();
+    Ok(segments
+        .into_iter()
+        .map(|s| {
+            let next = speakers.len();
+            SpeakerTurn {
+                start_ms: s.start,
+                end_ms: s.end,
+                speaker: *speakers.entry(s.speaker).or_insert(next),
+            }
+        })
+        .collect())
+}
+
+// (missed speech + false alarm + speaker confusion) / total reference speech.
+// Hypothesis speakers are mapped to reference speakers greedily by overlap, rather than with the Hungarian algorithm.
+pub fn diarization_error_rate(reference: &[SpeakerTurn], hypothesis: &[SpeakerTurn]) -> f32 {
+    let reference = frames(reference);
+    let hypothesis = frames(hypothesis);
+    let len = reference.len().max(hypothesis.len());
+
+    let mut overlap: HashMap<(usize, usize), u64> = HashMap::new();
+    for (r, h) in reference.iter().zip(&hypothesis) {
+        for &rs in r {
+            for &hs in h {
+                *overlap.entry((rs, hs)).or_default() += 1;
+            }
+        }
+    }
+
+    let mut pairs: Vec<_> = overlap.into_iter().collect();
+    pairs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut mapping: HashMap<usize, usize> = HashMap::new();
+    let mut mapped: HashSet<usize> = HashSet::new();
+    for ((rs, hs), _) in pairs {
+        if !mapping.contains_key(&hs) && !mapped.contains(&rs) {
+            mapping.insert(hs, rs);
+            mapped.insert(rs);
+        }
+    }
+
+    let empty = HashSet::new();
+    let (mut total, mut errors) = (0, 0);
+    for i in 0..len {
+        let r = reference.get(i).unwrap_or(&empty);
+        let h = hypothesis.get(i).unwrap_or(&empty);
+        let correct = h
+            .iter()
+            .filter(|hs| mapping.get(hs).is_some_and(|rs| r.contains(rs)))
+            .count();
+
+        total += r.len();
+        errors += r.len().max(h.len()) - correct;
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+    errors as f32 / total as f32
+}
+
+fn frames(turns: &[SpeakerTurn]) -> Vec<HashSet<usize>> {
+    let len = turns.iter().map(|t| t.end_ms / FRAME_MS).max().unwrap_or(0);
+
+    let mut frames = vec![HashSet::new(); len as usize];
+    for turn in turns {
+        for frame in
+            &mut frames[(turn.start_ms / FRAME_MS) as usize..(turn.end_ms / FRAME_MS) as usize]
+        {
+            frame.insert(turn.speaker);
+        }
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(start_ms: u64, end_ms: u64, speaker: usize) -> SpeakerTurn {
+        SpeakerTurn {
+            start_ms,
+            end_ms,
+            speaker,
+        }
+    }
+
+    #[test]
+    fn test_reference_turns() {
+        let turns = reference_turns(hypr_data::english_2::DIARIZATION_JSON).unwrap();
+        assert_eq!(turns[0], turn(6725, 7008, 0));
+        assert!(turns.iter().any(|t| t.speaker == 1));
+    }
+
+    #[test]
+    fn test_diarization_error_rate() {
+        let reference = vec![turn(0, 1000, 0), turn(1000, 2000, 1)];
+
+        // Speaker labels don't have to match.
+        let swapped = vec![turn(0, 1000, 1), turn(1000, 2000, 0)];
+        approx::assert_abs_diff_eq!(diarization_error_rate(&reference, &swapped), 0.0);
+
+        approx::assert_abs_diff_eq!(diarization_error_rate(&reference, &[]), 1.0);
+
+        let one_speaker = vec![turn(0, 2000, 0)];
+        approx::assert_abs_diff_eq!(diarization_error_rate(&reference, &one_speaker), 0.5);
+
+        let extra = vec![turn(0, 1000, 0), turn(1000, 3000, 1)];
+        approx::assert_abs_diff_eq!(diarization_error_rate(&reference, &extra), 0.5);
+
+        let fixture = reference_turns(hypr_data::english_2::DIARIZATION_JSON).unwrap();
+        approx::assert_abs_diff_eq!(diarization_error_rate(&fixture, &fixture), 0.0);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod clustering;
+pub mod diarization;
 pub mod embedding;
+pub mod enrollment;
+pub mod metrics;
 pub mod segmentation;
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::Mutex;
+
+// Refreshing a bit early avoids handing out a token that expires mid-request.
+const REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Token {
+    pub access_token: String,
+    // Unix timestamp, in seconds.
+    pub expire_at: u64,
+}
+
+impl Token {
+    fn is_fresh(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        now + REFRESH_MARGIN < Duration::from_secs(self.expire_at)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Auth {
+    api_base: url::Url,
+    client_id: String,
+    client_secret: String,
+    client: reqwest::Client,
+    token: Arc<Mutex<Option<Token>>>,
+}
+
+impl Auth {
+    pub fn new(
+        api_base: url::Url,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            api_base,
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            client: reqwest::Client::new(),
+            token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Reuses the cached token until it is about to expire.
+    pub async fn access_token(&self) -> Result<String, crate::Error> {
+        let mut guard = self.token.lock().await;
+
+        if let Some(token) = guard.as_ref().filter(|t| t.is_fresh()) {
+            return Ok(token.access_token.clone());
+        }
+
+        let token = self.authenticate().await?;
+        let access_token = token.access_token.clone();
+        *guard = Some(token);
+
+        Ok(access_token)
+    }
+
+    async fn authenticate(&self) -> Result<Token, crate::Error> {
+        let url = self.api_base.join("/v1/authenticate")?;
+
+        let token = self
+            .client
+            .post(url)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Token>()
+            .await?;
+
+        Ok(token)
+    }
+}
@@ -0,0 +1,16 @@
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    TonicTransportError(#[from] tonic::transport::Error),
+    #[error(transparent)]
+    TonicErrorStatus(#[from] tonic::Status),
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    #[error(transparent)]
+    InvalidMetadataValue(#[from] tonic::metadata::errors::InvalidMetadataValue),
+    #[error(transparent)]
+    UrlParseError(#[from] url::ParseError),
+    // The response only flags the error, so it is kept whole for the logs.
+    #[error("rtzr decoder error: {0:?}")]
+    DecoderError(Box<crate::realtime::interface::DecoderResponse>),
+}
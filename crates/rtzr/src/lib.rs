@@ -1 +1,7 @@
-mod realtime;
+mod auth;
+mod errors;
+
+pub use auth::*;
+pub use errors::*;
+
+pub mod realtime;
@@ -1,3 +1,5 @@
 mod rtzr {
     include!("./online_decoder.rs");
 }
+
+pub use rtzr::*;
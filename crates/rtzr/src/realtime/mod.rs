@@ -1 +1,335 @@
-mod interface;
+// https://github.com/vito-ai/openapi-grpc/blob/main/protos/vito-stt-client.proto
+
+pub mod interface;
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use interface::{
+    decoder_config::AudioEncoding, decoder_request::StreamingRequest,
+    online_decoder_client::OnlineDecoderClient, DecoderConfig, DecoderRequest, DecoderResponse,
+};
+
+const DEFAULT_API_BASE: &str = "https://openapi.vito.ai";
+const DEFAULT_GRPC_BASE: &str = "https://grpc-openapi.vito.ai:443";
+
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    api_base: Option<String>,
+    grpc_base: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    model_name: Option<String>,
+    keywords: Option<Vec<String>>,
+}
+
+impl ClientBuilder {
+    pub fn api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
+    pub fn grpc_base(mut self, grpc_base: impl Into<String>) -> Self {
+        self.grpc_base = Some(grpc_base.into());
+        self
+    }
+
+    pub fn client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    pub fn client_secret(mut self, client_secret: impl Into<String>) -> Self {
+        self.client_secret = Some(client_secret.into());
+        self
+    }
+
+    // "default" for Korean, "sommers_ja" for Japanese.
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.model_name = Some(model_name.into());
+        self
+    }
+
+    // Each keyword is either "word" or "word:score".
+    pub fn keywords(mut self, keywords: impl Into<Vec<String>>) -> Self {
+        self.keywords = Some(keywords.into());
+        self
+    }
+
+    pub async fn build(self) -> Result<Client, crate::Error> {
+        let api_base: url::Url = self
+            .api_base
+            .as_deref()
+            .unwrap_or(DEFAULT_API_BASE)
+            .parse()?;
+        let grpc_base = self.grpc_base.unwrap_or(DEFAULT_GRPC_BASE.to_string());
+
+        let mut endpoint = Endpoint::from_shared(grpc_base.clone())?;
+        if grpc_base.starts_with("https") {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new().with_native_roots())?;
+        }
+        let channel = endpoint.connect().await?;
+
+        let auth = crate::Auth::new(
+            api_base,
+            self.client_id.unwrap(),
+            self.client_secret.unwrap(),
+        );
+
+        let config = DecoderConfig {
+            sample_rate: 16 * 1000,
+            encoding: AudioEncoding::Linear16.into(),
+            model_name: Some(self.model_name.unwrap_or("default".to_string())),
+            use_itn: Some(true),
+            use_disfluency_filter: Some(true),
+            use_profanity_filter: Some(false),
+            stream_config: None,
+            keywords: self.keywords.unwrap_or_default(),
+        };
+
+        Ok(Client {
+            inner: OnlineDecoderClient::new(channel),
+            auth,
+            config,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: OnlineDecoderClient<Channel>,
+    auth: crate::Auth,
+    config: DecoderConfig,
+}
+
+impl Client {
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    pub async fn from_audio<S, E>(
+        &mut self,
+        audio: S,
+    ) -> Result<impl Stream<Item = Result<DecoderResponse, crate::Error>>, crate::Error>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + Unpin + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let token = self.auth.access_token().await?;
+
+        let config_request = DecoderRequest {
+            streaming_request: Some(StreamingRequest::StreamingConfig(self.config.clone())),
+        };
+        let config_stream = futures_util::stream::once(async move { config_request });
+
+        let audio_request_stream = audio.filter_map(|chunk| async {
+            match chunk {
+                Ok(chunk) => Some(DecoderRequest {
+                    streaming_request: Some(StreamingRequest::AudioContent(chunk.to_vec())),
+                }),
+                Err(e) => {
+                    tracing::error!("rtzr_audio_stream_failed: {:?}", e);
+                    None
+                }
+            }
+        });
+
+        let mut request = tonic::Request::new(config_stream.chain(audio_request_stream));
+        request
+            .metadata_mut()
+            .insert("authorization", format!("bearer {}", token).parse()?);
+
+        let response =
+            self.inner
+                .decode(request)
+                .await?
+                .into_inner()
+                .map(|message| match message {
+                    Ok(res) if res.error => Err(crate::Error::DecoderError(Box::new(res))),
+                    Ok(res) => Ok(res),
+                    Err(e) => Err(e.into()),
+                });
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use interface::{
+        online_decoder_server::{OnlineDecoder, OnlineDecoderServer},
+        SpeechRecognitionAlternative, StreamingRecognitionResult,
+    };
+    use tonic::{Request, Response, Status, Streaming};
+
+    const TOKEN: &str = "test-token";
+
+    struct MockDecoder;
+
+    fn result(text: &str, is_final: bool) -> DecoderResponse {
+        DecoderResponse {
+            error: false,
+            results: vec![StreamingRecognitionResult {
+                alternatives: vec![SpeechRecognitionAlternative {
+                    text: text.to_string(),
+                    confidence: 0.9,
+                    words: vec![],
+                }],
+                is_final,
+                stability: 0.0,
+                duration: 1000,
+                start_at: 0,
+            }],
+            speech_event_type: 0,
+        }
+    }
+
+    #[tonic::async_trait]
+    impl OnlineDecoder for MockDecoder {
+        type DecodeStream =
+            Pin<Box<dyn Stream<Item = Result<DecoderResponse, Status>> + Send + 'static>>;
+
+        async fn decode(
+            &self,
+            request: Request<Streaming<DecoderRequest>>,
+        ) -> Result<Response<Self::DecodeStream>, Status> {
+            let authorization = request
+                .metadata()
+                .get("authorization")
+                .and_then(|v| v.to_str().ok());
+            if authorization != Some(&format!("bearer {}", TOKEN)) {
+                return Err(Status::unauthenticated("invalid token"));
+            }
+
+            let mut inbound = request.into_inner();
+
+            let output = async_stream::try_stream! {
+                match inbound.message().await? {
+                    Some(DecoderRequest {
+                        streaming_request: Some(StreamingRequest::StreamingConfig(config)),
+                    }) => assert_eq!(config.model_name.as_deref(), Some("default")),
+                    _ => Err(Status::invalid_argument("config must come first"))?,
+                }
+
+                let mut chunks = 0;
+                while inbound.message().await?.is_some() {
+                    chunks += 1;
+                    yield result(&vec!["hello"; chunks].join(" "), false);
+                }
+
+                yield result("hello world", true);
+            };
+
+            Ok(Response::new(Box::pin(output)))
+        }
+    }
+
+    async fn mock_server(expires_in: u64) -> (String, String, Arc<AtomicUsize>) {
+        let auth_calls = Arc::new(AtomicUsize::new(0));
+
+        let auth_router = axum::Router::new().route(
+            "/v1/authenticate",
+            axum::routing::post({
+                let auth_calls = auth_calls.clone();
+                move || async move {
+                    auth_calls.fetch_add(1, Ordering::SeqCst);
+
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs();
+                    axum::Json(serde_json::json!({
+                        "access_token": TOKEN,
+                        "expire_at": now + expires_in,
+                    }))
+                }
+            }),
+        );
+        let auth_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let auth_addr = auth_listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(auth_listener, auth_router).await.unwrap() });
+
+        let grpc_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let grpc_addr = grpc_listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(OnlineDecoderServer::new(MockDecoder))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(
+                    grpc_listener,
+                ))
+                .await
+                .unwrap()
+        });
+
+        (
+            format!("http://{}", auth_addr),
+            format!("http://{}", grpc_addr),
+            auth_calls,
+        )
+    }
+
+    fn audio() -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send + Unpin + 'static {
+        futures_util::stream::iter((0..2).map(|_| Ok(Bytes::from(vec![0u8; 3200]))))
+    }
+
+    async fn client(api_base: &str, grpc_base: &str) -> Client {
+        Client::builder()
+            .api_base(api_base)
+            .grpc_base(grpc_base)
+            .client_id("id")
+            .client_secret("secret")
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transcribe() {
+        let (api_base, grpc_base, auth_calls) = mock_server(3600).await;
+        let mut client = client(&api_base, &grpc_base).await;
+
+        let responses: Vec<DecoderResponse> = client
+            .from_audio(audio())
+            .await
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+
+        let texts: Vec<(&str, bool)> = responses
+            .iter()
+            .flat_map(|r| &r.results)
+            .map(|r| (r.alternatives[0].text.as_str(), r.is_final))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                ("hello", false),
+                ("hello hello", false),
+                ("hello world", true)
+            ]
+        );
+
+        let _ = client.from_audio(audio()).await.unwrap();
+        assert_eq!(auth_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_refresh() {
+        // Within `REFRESH_MARGIN`, so every request re-authenticates.
+        let (api_base, grpc_base, auth_calls) = mock_server(60).await;
+        let mut client = client(&api_base, &grpc_base).await;
+
+        let _ = client.from_audio(audio()).await.unwrap();
+        let _ = client.from_audio(audio()).await.unwrap();
+        assert_eq!(auth_calls.load(Ordering::SeqCst), 2);
+    }
+}
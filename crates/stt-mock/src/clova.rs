@@ -0,0 +1,115 @@
+// https://api.ncloud-docs.com/docs/en/ai-application-service-clovaspeech-grpc
+
+use std::pin::Pin;
+
+use futures_util::Stream;
+use serde_json::json;
+use tonic::{Request, Response, Status, Streaming};
+
+use hypr_clova::realtime::interface::{
+    nest_request::Part,
+    nest_service_server::{NestService, NestServiceServer},
+    NestRequest, NestResponse,
+};
+
+use crate::{MockServer, Replay, Script, ScriptWord};
+
+// Speaks the Clova nest gRPC protocol. Timestamps are in milliseconds, as documented.
+pub async fn serve_clova(script: Script) -> MockServer {
+    let listener = crate::bind().await;
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(NestServiceServer::new(MockClova { script }))
+            .serve_with_incoming_shutdown(
+                tokio_stream::wrappers::TcpListenerStream::new(listener),
+                async move {
+                    let _ = rx.await;
+                },
+            )
+            .await
+            .unwrap();
+    });
+
+    MockServer {
+        addr,
+        api_base: format!("http://{}", addr),
+        shutdown: Some(tx),
+    }
+}
+
+struct MockClova {
+    script: Script,
+}
+
+#[tonic::async_trait]
+impl NestService for MockClova {
+    type RecognizeStream =
+        Pin<Box<dyn Stream<Item = Result<NestResponse, Status>> + Send + 'static>>;
+
+    async fn recognize(
+        &self,
+        request: Request<Streaming<NestRequest>>,
+    ) -> Result<Response<Self::RecognizeStream>, Status> {
+        if request.metadata().get("authorization").is_none() {
+            return Err(Status::unauthenticated("missing authorization"));
+        }
+
+        let mut input = request.into_inner();
+        let mut replay = Replay::new(&self.script);
+
+        let output = async_stream::try_stream! {
+            match input.message().await? {
+                Some(NestRequest { part: Some(Part::Config(_)), .. }) => yield config(),
+                _ => Err::<(), _>(Status::invalid_argument("config must be sent first"))?,
+            }
+
+            while let Some(request) = input.message().await? {
+                if let Some(Part::Data(data)) = request.part {
+                    let words = replay.push_audio(data.chunk.len());
+                    if !words.is_empty() {
+                        yield transcription(&words);
+                    }
+                }
+            }
+
+            let words = replay.finish();
+            if !words.is_empty() {
+                yield transcription(&words);
+            }
+        };
+
+        Ok(Response::new(Box::pin(output)))
+    }
+}
+
+fn config() -> NestResponse {
+    let contents = json!({
+        "responseType": ["config"],
+        "uid": "mock",
+        "config": { "status": "Success" },
+    });
+
+    NestResponse {
+        contents: contents.to_string(),
+    }
+}
+
+fn transcription(words: &[ScriptWord]) -> NestResponse {
+    let contents = json!({
+        "responseType": ["transcription"],
+        "uid": "mock",
+        "transcription": {
+            "text": crate::join(words),
+            "startTimestamp": words.first().map(|w| w.start).unwrap_or_default(),
+            "endTimestamp": words.last().map(|w| w.end).unwrap_or_default(),
+            "confidence": 0.99,
+        },
+    });
+
+    NestResponse {
+        contents: contents.to_string(),
+    }
+}
@@ -0,0 +1,165 @@
+// https://developers.deepgram.com/reference/speech-to-text-api/listen-streaming
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+
+use crate::{MockServer, Replay, Script, ScriptWord};
+
+// Speaks the Deepgram live transcription protocol. `api_base` already includes `/v1`.
+pub async fn serve_deepgram(script: Script) -> MockServer {
+    serve_deepgram_multichannel(vec![script]).await
+}
+
+// Like `serve_deepgram`, with one script per channel for `multichannel=true` requests.
+pub async fn serve_deepgram_multichannel(scripts: Vec<Script>) -> MockServer {
+    let router = Router::new()
+        .route("/v1/listen", get(handler))
+        .route("/listen", get(handler))
+        .with_state(scripts);
+
+    let mut server = crate::serve_axum(crate::bind().await, router);
+    server.api_base = format!("{}/v1", server.api_base);
+    server
+}
+
+async fn handler(
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    ws: WebSocketUpgrade,
+    State(scripts): State<Vec<Script>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let authorized = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("Token "));
+
+    if !authorized {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let multichannel = query.get("multichannel").is_some_and(|v| v == "true");
+    let channels = match query.get("channels").and_then(|v| v.parse().ok()) {
+        Some(channels) if multichannel => channels,
+        _ => 1,
+    };
+    if channels > scripts.len() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(ws.on_upgrade(move |socket| websocket(socket, scripts[..channels].to_vec())))
+}
+
+async fn websocket(socket: WebSocket, scripts: Vec<Script>) {
+    let (mut sender, mut receiver) = socket.split();
+    let channels = scripts.len();
+    let mut replays: Vec<_> = scripts.iter().map(Replay::new).collect();
+    let mut received_bytes = 0;
+
+    while let Some(Ok(msg)) = receiver.next().await {
+        let len = match msg {
+            Message::Binary(data) => {
+                received_bytes += data.len();
+                data.len() / channels
+            }
+            Message::Text(text) => {
+                let value: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+
+                match value["type"].as_str() {
+                    Some("CloseStream") => break,
+                    _ => continue,
+                }
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        for (channel, replay) in replays.iter_mut().enumerate() {
+            let words = replay.push_audio(len);
+            if words.is_empty() {
+                continue;
+            }
+
+            let msg = results(&words, channel, channels);
+            if sender.send(msg).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    for (channel, replay) in replays.iter_mut().enumerate() {
+        let words = replay.finish();
+        if !words.is_empty() {
+            let _ = sender.send(results(&words, channel, channels)).await;
+        }
+    }
+
+    let _ = sender
+        .send(metadata(received_bytes / channels, channels))
+        .await;
+    let _ = sender.send(Message::Close(None)).await;
+}
+
+fn results(words: &[ScriptWord], channel: usize, channels: usize) -> Message {
+    let start = words.first().map(|w| w.start).unwrap_or_default() as f64 / 1000.0;
+    let end = words.last().map(|w| w.end).unwrap_or_default() as f64 / 1000.0;
+
+    let value = json!({
+        "type": "Results",
+        "channel_index": [channel, channels],
+        "duration": end - start,
+        "start": start,
+        "is_final": true,
+        "speech_final": true,
+        "from_finalize": false,
+        "channel": {
+            "alternatives": [{
+                "transcript": crate::join(words),
+                "confidence": 0.99,
+                "languages": [],
+                "words": words.iter().map(|w| json!({
+                    "word": w.text.trim().to_lowercase(),
+                    "punctuated_word": w.text.trim(),
+                    "start": w.start as f64 / 1000.0,
+                    "end": w.end as f64 / 1000.0,
+                    "confidence": 0.99,
+                    "speaker": 0,
+                })).collect::<Vec<_>>(),
+            }],
+        },
+        "metadata": {
+            "request_id": "mock",
+            "model_uuid": "mock",
+            "model_info": { "name": "mock", "version": "mock", "arch": "mock" },
+        },
+    });
+
+    Message::Text(value.to_string().into())
+}
+
+fn metadata(received_bytes: usize, channels: usize) -> Message {
+    let value = json!({
+        "type": "Metadata",
+        "transaction_key": "deprecated",
+        "request_id": "mock",
+        "sha256": "mock",
+        "created": "1970-01-01T00:00:00.000Z",
+        "duration": (received_bytes / crate::BYTES_PER_MS) as f64 / 1000.0,
+        "channels": channels,
+        "models": [],
+        "model_info": {},
+    });
+
+    Message::Text(value.to_string().into())
+}
@@ -0,0 +1,171 @@
+mod clova;
+mod deepgram;
+mod listen;
+mod pyannote;
+
+pub use clova::*;
+pub use deepgram::*;
+pub use listen::*;
+pub use pyannote::*;
+
+use std::collections::VecDeque;
+use std::net::{Ipv4Addr, SocketAddr};
+
+// 16kHz, mono, linear16.
+const BYTES_PER_MS: usize = 32;
+
+// Same shape as `transcription.json` in `hypr_data`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ScriptWord {
+    pub text: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    pub words: Vec<ScriptWord>,
+}
+
+impl Script {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            words: serde_json::from_str(json)?,
+        })
+    }
+
+    pub fn english() -> Self {
+        Self::from_json(hypr_data::english_2::TRANSCRIPTION_JSON).unwrap()
+    }
+
+    pub fn korean() -> Self {
+        Self::from_json(hypr_data::korean_2::TRANSCRIPTION_JSON).unwrap()
+    }
+
+    pub fn text(&self) -> String {
+        join(&self.words)
+    }
+}
+
+pub(crate) fn join(words: &[ScriptWord]) -> String {
+    words
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// Releases each word once the audio received so far covers it, so output follows the input pace.
+pub(crate) struct Replay {
+    words: VecDeque<ScriptWord>,
+    received_bytes: usize,
+}
+
+impl Replay {
+    pub fn new(script: &Script) -> Self {
+        Self {
+            words: script.words.iter().cloned().collect(),
+            received_bytes: 0,
+        }
+    }
+
+    pub fn push_audio(&mut self, len: usize) -> Vec<ScriptWord> {
+        self.received_bytes += len;
+        let received_ms = (self.received_bytes / BYTES_PER_MS) as u64;
+
+        let n = self
+            .words
+            .iter()
+            .take_while(|w| w.end <= received_ms)
+            .count();
+        self.words.drain(..n).collect()
+    }
+
+    pub fn finish(&mut self) -> Vec<ScriptWord> {
+        self.words.drain(..).collect()
+    }
+}
+
+pub struct MockServer {
+    addr: SocketAddr,
+    api_base: String,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Pass this to the `api_base` of the client under test.
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+pub(crate) async fn bind() -> tokio::net::TcpListener {
+    tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .unwrap()
+}
+
+pub(crate) fn serve_axum(listener: tokio::net::TcpListener, router: axum::Router) -> MockServer {
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+    tokio::spawn(async move {
+        axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = rx.await;
+            })
+            .await
+            .unwrap();
+    });
+
+    MockServer {
+        addr,
+        api_base: format!("http://{}", addr),
+        shutdown: Some(tx),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures() {
+        assert!(!Script::english().words.is_empty());
+        assert!(!Script::korean().words.is_empty());
+        assert!(Script::english().text().starts_with("Hello?"));
+        assert!(!DiarizationScript::english().segments.is_empty());
+    }
+
+    #[test]
+    fn test_replay() {
+        let script = Script::from_json(
+            r#"[
+                { "start": 0, "end": 500, "text": "Hello" },
+                { "start": 600, "end": 900, "text": " there," },
+                { "start": 1200, "end": 1500, "text": " friend." }
+            ]"#,
+        )
+        .unwrap();
+        let mut replay = Replay::new(&script);
+
+        assert!(replay.push_audio(BYTES_PER_MS * 400).is_empty());
+        assert_eq!(join(&replay.push_audio(BYTES_PER_MS * 600)), "Hello there,");
+        assert!(replay.push_audio(BYTES_PER_MS * 100).is_empty());
+        assert_eq!(join(&replay.finish()), "friend.");
+        assert!(replay.finish().is_empty());
+    }
+}
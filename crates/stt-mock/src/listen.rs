@@ -0,0 +1,126 @@
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use futures_util::{SinkExt, StreamExt};
+
+use hypr_listener_interface::{ListenFrame, ListenOutputChunk, ListenServerMessage, Word};
+
+use crate::{MockServer, Replay, Script, ScriptWord};
+
+// Speaks both versions of the Hyprnote listen protocol, the same one `apps/app/server` and `plugins/local-stt` serve.
+// Sessions can't be resumed here.
+pub async fn serve_listen(script: Script) -> MockServer {
+    let router = Router::new()
+        .route("/api/desktop/listen/realtime", get(handler))
+        .with_state(script);
+
+    crate::serve_axum(crate::bind().await, router)
+}
+
+async fn handler(ws: WebSocketUpgrade, State(script): State<Script>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| websocket(socket, script))
+}
+
+async fn websocket(socket: WebSocket, script: Script) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut replay = Replay::new(&script);
+    // Only v2 clients expect `session` and `ack` messages.
+    let mut v2 = false;
+    let mut seq = 0;
+
+    while let Some(Ok(msg)) = receiver.next().await {
+        let frame = match msg {
+            Message::Text(text) => match ListenFrame::from_text(text.as_str()) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    let frame = CloseFrame {
+                        code: close_code::INVALID,
+                        reason: e.to_string().into(),
+                    };
+                    let _ = sender.send(Message::Close(Some(frame))).await;
+                    return;
+                }
+            },
+            Message::Binary(data) => ListenFrame::from_binary(data.to_vec()),
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let words = match frame {
+            // Assumes the default 16kHz mono linear16 format.
+            ListenFrame::Audio(data) => {
+                seq += 1;
+                let ack = server_message(&ListenServerMessage::Ack { seq });
+                if v2 && sender.send(ack).await.is_err() {
+                    return;
+                }
+
+                replay.push_audio(data.len())
+            }
+            ListenFrame::Start {
+                resume: Some(_), ..
+            } => {
+                let frame = CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "unknown or expired session".into(),
+                };
+                let _ = sender.send(Message::Close(Some(frame))).await;
+                return;
+            }
+            ListenFrame::Start { .. } => {
+                v2 = true;
+                let session = server_message(&ListenServerMessage::Session {
+                    session_id: "mock".to_string(),
+                    next_seq: 0,
+                });
+                if sender.send(session).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+            ListenFrame::KeepAlive => continue,
+            ListenFrame::End => break,
+        };
+
+        if !words.is_empty() && sender.send(output(&words)).await.is_err() {
+            return;
+        }
+    }
+
+    let words = replay.finish();
+    if !words.is_empty() {
+        let _ = sender.send(output(&words)).await;
+    }
+
+    let _ = sender.send(Message::Close(None)).await;
+}
+
+fn output(words: &[ScriptWord]) -> Message {
+    let chunk = ListenOutputChunk {
+        words: words
+            .iter()
+            .map(|w| Word {
+                text: w.text.trim().to_string(),
+                speaker: None,
+                confidence: Some(0.99),
+                start_ms: Some(w.start),
+                end_ms: Some(w.end),
+            })
+            .collect(),
+        language: None,
+        channel: None,
+        interim: false,
+    };
+
+    Message::Text(serde_json::to_string(&chunk).unwrap().into())
+}
+
+fn server_message(msg: &ListenServerMessage) -> Message {
+    Message::Text(serde_json::to_string(msg).unwrap().into())
+}
@@ -0,0 +1,224 @@
+// https://docs.pyannote.ai/api-reference/diarize
+// https://docs.pyannote.ai/api-reference/get-job
+// https://docs.pyannote.ai/api-reference/create-media-upload-url
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde_json::json;
+
+use crate::MockServer;
+
+// Same shape as `diarization.json` in `hypr_data`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ScriptSegment {
+    pub start: u64,
+    pub end: u64,
+    pub speaker: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiarizationScript {
+    pub segments: Vec<ScriptSegment>,
+}
+
+impl DiarizationScript {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            segments: serde_json::from_str(json)?,
+        })
+    }
+
+    pub fn english() -> Self {
+        Self::from_json(hypr_data::english_2::DIARIZATION_JSON).unwrap()
+    }
+}
+
+#[derive(Clone)]
+struct PyannoteState {
+    script: DiarizationScript,
+    // Polls answered with `running` before a job succeeds.
+    pending_polls: usize,
+    // Polls so far, by job id.
+    jobs: Arc<Mutex<HashMap<String, usize>>>,
+    // Keys of `media://` urls whose audio was uploaded.
+    media: Arc<Mutex<HashSet<String>>>,
+    api_base: String,
+}
+
+// Speaks the pyannote job and media API. Jobs succeed with the script after `pending_polls` polls.
+// Webhooks in the request are accepted but never called.
+pub async fn serve_pyannote(script: DiarizationScript, pending_polls: usize) -> MockServer {
+    let listener = crate::bind().await;
+    let api_base = format!("http://{}", listener.local_addr().unwrap());
+
+    let router = Router::new()
+        .route("/v1/diarize", post(submit))
+        .route("/v1/jobs/{id}", get(get_job))
+        .route("/v1/media/input", post(create_media_upload))
+        .route("/upload/{*key}", put(upload_media))
+        .with_state(PyannoteState {
+            script,
+            pending_polls,
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            media: Arc::new(Mutex::new(HashSet::new())),
+            api_base,
+        });
+
+    crate::serve_axum(listener, router)
+}
+
+fn authorized(headers: &HeaderMap) -> bool {
+    headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("Bearer "))
+}
+
+async fn submit(
+    headers: HeaderMap,
+    State(state): State<PyannoteState>,
+    Json(body): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    if !authorized(&headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": "Unauthorized" })),
+        );
+    }
+    let Some(url) = body["url"].as_str().filter(|url| !url.is_empty()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": "url is required" })),
+        );
+    };
+    if let Some(key) = url.strip_prefix("media://") {
+        if !state.media.lock().unwrap().contains(key) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "message": "media not found" })),
+            );
+        }
+    }
+
+    let job_id = {
+        let mut jobs = state.jobs.lock().unwrap();
+        let job_id = format!("job-{}", jobs.len());
+        jobs.insert(job_id.clone(), 0);
+        job_id
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({ "jobId": job_id, "status": "created" })),
+    )
+}
+
+async fn create_media_upload(
+    headers: HeaderMap,
+    State(state): State<PyannoteState>,
+    Json(body): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    if !authorized(&headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": "Unauthorized" })),
+        );
+    }
+    let Some(key) = body["url"]
+        .as_str()
+        .and_then(|url| url.strip_prefix("media://"))
+    else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": "url must start with media://" })),
+        );
+    };
+
+    (
+        StatusCode::CREATED,
+        Json(json!({ "url": format!("{}/upload/{}", state.api_base, key) })),
+    )
+}
+
+// Stands in for the presigned storage url, so it needs no authorization.
+async fn upload_media(
+    Path(key): Path<String>,
+    State(state): State<PyannoteState>,
+) -> impl IntoResponse {
+    state.media.lock().unwrap().insert(key);
+    StatusCode::OK
+}
+
+async fn get_job(
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    State(state): State<PyannoteState>,
+) -> impl IntoResponse {
+    if !authorized(&headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": "Unauthorized" })),
+        );
+    }
+
+    let polls = {
+        let mut jobs = state.jobs.lock().unwrap();
+        match jobs.get_mut(&id) {
+            Some(polls) => {
+                *polls += 1;
+                *polls
+            }
+            None => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({ "message": "Job not found" })),
+                )
+            }
+        }
+    };
+
+    let now = "2025-01-01T00:00:00Z";
+    if polls <= state.pending_polls {
+        return (
+            StatusCode::OK,
+            Json(json!({
+                "jobId": id,
+                "status": "running",
+                "createdAt": now,
+                "updatedAt": now,
+            })),
+        );
+    }
+
+    let diarization: Vec<_> = state
+        .script
+        .segments
+        .iter()
+        .map(|s| {
+            json!({
+                "speaker": s.speaker,
+                "start": s.start as f32 / 1000.0,
+                "end": s.end as f32 / 1000.0,
+            })
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "jobId": id,
+            "status": "succeeded",
+            "createdAt": now,
+            "updatedAt": now,
+            "output": { "diarization": diarization },
+        })),
+    )
+}
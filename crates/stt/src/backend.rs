@@ -0,0 +1,125 @@
+use hypr_language::{Language, ISO639};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Clova,
+    Deepgram,
+    Rtzr,
+    Whisper,
+}
+
+impl Backend {
+    pub fn supports_realtime(&self, language: &Language) -> bool {
+        let language = language.clone();
+
+        match self {
+            Backend::Clova => language.for_clova_realtime().is_ok(),
+            Backend::Deepgram => language.for_deepgram().is_ok(),
+            Backend::Rtzr => language.for_rtzr().is_ok(),
+            Backend::Whisper => TryInto::<hypr_whisper::Language>::try_into(language).is_ok(),
+        }
+    }
+
+    pub fn supports_recorded(&self, language: &Language) -> bool {
+        let language = language.clone();
+
+        match self {
+            Backend::Clova => language.for_clova_recorded().is_ok(),
+            Backend::Deepgram => language.for_deepgram().is_ok(),
+            Backend::Rtzr => false,
+            Backend::Whisper => false,
+        }
+    }
+
+    pub(crate) fn preferred(language: &Language) -> &'static [Backend] {
+        match language.iso639() {
+            // Clova is noticeably better than Deepgram on Korean.
+            ISO639::Ko => &[
+                Backend::Clova,
+                Backend::Rtzr,
+                Backend::Deepgram,
+                Backend::Whisper,
+            ],
+            _ => &[
+                Backend::Deepgram,
+                Backend::Clova,
+                Backend::Rtzr,
+                Backend::Whisper,
+            ],
+        }
+    }
+
+    pub fn for_realtime(language: &Language) -> Result<Backend, crate::Error> {
+        Self::preferred(language)
+            .iter()
+            .find(|b| b.supports_realtime(language))
+            .copied()
+            .ok_or_else(|| crate::Error::UnsupportedLanguage(language.to_string()))
+    }
+
+    pub fn for_recorded(language: &Language) -> Result<Backend, crate::Error> {
+        Self::preferred(language)
+            .iter()
+            .find(|b| b.supports_recorded(language))
+            .copied()
+            .ok_or_else(|| crate::Error::UnsupportedLanguage(language.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routing() {
+        use Backend::*;
+
+        // (language, realtime, recorded)
+        let table = [
+            (ISO639::Ko, Some(Clova), Some(Clova)),
+            (ISO639::En, Some(Deepgram), Some(Deepgram)),
+            (ISO639::Ja, Some(Deepgram), Some(Deepgram)),
+            (ISO639::Zh, Some(Deepgram), Some(Deepgram)),
+            (ISO639::De, Some(Deepgram), Some(Deepgram)),
+            (ISO639::Pt, Some(Deepgram), Some(Deepgram)),
+            (ISO639::Af, Some(Whisper), None),
+            (ISO639::Cy, Some(Whisper), None),
+            (ISO639::Sw, Some(Whisper), None),
+            (ISO639::Ab, None, None),
+            (ISO639::Zu, None, None),
+        ];
+
+        for (code, realtime, recorded) in table {
+            let language: Language = code.into();
+
+            assert_eq!(
+                Backend::for_realtime(&language).ok(),
+                realtime,
+                "realtime: {}",
+                code
+            );
+            assert_eq!(
+                Backend::for_recorded(&language).ok(),
+                recorded,
+                "recorded: {}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_routing_examples() {
+        let realtime = |code: ISO639| Backend::for_realtime(&code.into()).ok();
+
+        assert_eq!(realtime(ISO639::Ko), Some(Backend::Clova));
+        assert_eq!(realtime(ISO639::En), Some(Backend::Deepgram));
+        assert_eq!(realtime(ISO639::De), Some(Backend::Deepgram));
+        assert_eq!(realtime(ISO639::Ja), Some(Backend::Deepgram));
+        assert_eq!(realtime(ISO639::Cy), Some(Backend::Whisper));
+        assert_eq!(realtime(ISO639::Sw), Some(Backend::Whisper));
+        assert_eq!(realtime(ISO639::Ab), None);
+
+        let err = Backend::for_realtime(&ISO639::Ab.into()).unwrap_err();
+        assert!(matches!(err, crate::Error::UnsupportedLanguage(_)));
+    }
+}
@@ -1,11 +1,20 @@
+use deepgram::common::options::Keyword;
+
 #[derive(Debug, Default)]
 pub struct DeepgramClientBuilder {
+    api_base: Option<String>,
     api_key: Option<String>,
     language: Option<hypr_language::Language>,
-    keywords: Option<Vec<String>>,
+    vocabulary: Option<hypr_listener_interface::Vocabulary>,
+    channels: Option<u16>,
 }
 
 impl DeepgramClientBuilder {
+    pub fn api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
     pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
         self.api_key = Some(api_key.into());
         self
@@ -16,8 +25,14 @@ impl DeepgramClientBuilder {
         self
     }
 
-    pub fn keywords(mut self, keywords: impl Into<Vec<String>>) -> Self {
-        self.keywords = Some(keywords.into());
+    pub fn vocabulary(mut self, vocabulary: hypr_listener_interface::Vocabulary) -> Self {
+        self.vocabulary = Some(vocabulary);
+        self
+    }
+
+    // Interleaved channels are transcribed separately, and each chunk says which channel it came from.
+    pub fn channels(mut self, channels: u16) -> Self {
+        self.channels = Some(channels);
         self
     }
 
@@ -25,7 +40,9 @@ impl DeepgramClientBuilder {
         let language = self.language.unwrap_or(hypr_language::ISO639::En.into());
 
         let client = deepgram::Deepgram::with_base_url_and_api_key(
-            "https://api.deepgram.com/v1",
+            self.api_base
+                .as_deref()
+                .unwrap_or("https://api.deepgram.com/v1"),
             self.api_key.unwrap(),
         )
         .unwrap();
@@ -33,7 +50,18 @@ impl DeepgramClientBuilder {
         Ok(DeepgramClient {
             client,
             language: language.for_deepgram()?,
-            keywords: self.keywords.unwrap_or_default(),
+            keywords: self
+                .vocabulary
+                .unwrap_or_default()
+                .terms
+                .into_iter()
+                // Deepgram has no notion of pronunciations, so only the term itself is boosted.
+                .map(|t| Keyword {
+                    keyword: t.text,
+                    intensifier: t.boost.map(f64::from),
+                })
+                .collect(),
+            channels: self.channels.unwrap_or(1),
         })
     }
 }
@@ -42,7 +70,8 @@ impl DeepgramClientBuilder {
 pub struct DeepgramClient {
     pub client: deepgram::Deepgram,
     pub language: deepgram::common::options::Language,
-    pub keywords: Vec<String>,
+    pub keywords: Vec<Keyword>,
+    pub channels: u16,
 }
 
 impl DeepgramClient {
@@ -6,6 +6,12 @@ pub enum Error {
     Deepgram(#[from] deepgram::DeepgramError),
     #[error(transparent)]
     Clova(#[from] hypr_clova::Error),
+    #[error(transparent)]
+    Rtzr(#[from] hypr_rtzr::Error),
     #[error("clova error {0}")]
     ClovaError(String),
+    #[error("no speech-to-text backend supports '{0}'")]
+    UnsupportedLanguage(String),
+    #[error("missing api key for {0:?}")]
+    MissingApiKey(crate::Backend),
 }
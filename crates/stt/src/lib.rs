@@ -1,6 +1,8 @@
+mod backend;
 mod deepgram;
 mod errors;
 
+pub use backend::*;
 pub use errors::*;
 
 #[cfg(feature = "realtime")]
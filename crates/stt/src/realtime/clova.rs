@@ -5,7 +5,25 @@ use std::error::Error;
 use super::RealtimeSpeechToText;
 
 pub use hypr_clova::realtime::interface as clova;
-pub use hypr_listener_interface::{ListenOutputChunk, Word};
+pub use hypr_listener_interface::{ListenOutputChunk, Vocabulary, Word};
+
+// Pronunciations share the weight of the term they spell out.
+pub fn keyword_boosting(vocabulary: &Vocabulary) -> clova::KeywordBoosting {
+    clova::KeywordBoosting {
+        boostings: vocabulary
+            .terms
+            .iter()
+            .map(|t| clova::KeywordBoostingItem {
+                words: std::iter::once(&t.text)
+                    .chain(t.pronunciations.iter())
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(","),
+                weight: t.boost.map(f64::from).unwrap_or(1.0),
+            })
+            .collect(),
+    }
+}
 
 impl<S, E> RealtimeSpeechToText<S, E> for hypr_clova::realtime::Client {
     async fn transcribe(
@@ -36,6 +54,9 @@ impl<S, E> RealtimeSpeechToText<S, E> for hypr_clova::realtime::Client {
                             end_ms: Some(r.transcription.end_timestamp * 1000),
                             confidence: Some(r.transcription.confidence as f32),
                         }],
+                        language: None,
+                        channel: None,
+                        interim: false,
                     })),
                     clova::StreamResponse::Config(_) => None,
                 },
@@ -47,3 +68,26 @@ impl<S, E> RealtimeSpeechToText<S, E> for hypr_clova::realtime::Client {
         Ok(Box::from(Box::pin(stream)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hypr_listener_interface::VocabularyTerm;
+
+    #[test]
+    fn test_keyword_boosting() {
+        let mut vocabulary: Vocabulary = ["Blitz"].into_iter().collect();
+        vocabulary.push(VocabularyTerm {
+            text: "하이퍼노트".to_string(),
+            boost: Some(2.0),
+            pronunciations: vec!["Hyprnote".to_string()],
+        });
+
+        let boosting = keyword_boosting(&vocabulary);
+        assert_eq!(boosting.boostings.len(), 2);
+        assert_eq!(boosting.boostings[0].words, "Blitz");
+        assert_eq!(boosting.boostings[0].weight, 1.0);
+        assert_eq!(boosting.boostings[1].words, "하이퍼노트,Hyprnote");
+        assert_eq!(boosting.boostings[1].weight, 2.0);
+    }
+}
@@ -24,14 +24,14 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
     {
         let options = Options::builder()
             .model(Model::Nova2Meeting)
-            .multichannel(false)
+            .multichannel(self.channels > 1)
             .smart_format(true)
             .punctuate(true)
             .numerals(true)
             .language(self.language.clone())
             .filler_words(false)
             .diarize(true)
-            .keywords(self.keywords.iter().map(String::as_str))
+            .keywords_with_intensifiers(self.keywords.clone())
             .build();
 
         let deepgram_stream = self
@@ -40,7 +40,7 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
             .stream_request_with_options(options)
             .keep_alive()
             .sample_rate(16 * 1000)
-            .channels(1)
+            .channels(self.channels)
             .encoding(Encoding::Linear16)
             .stream(stream)
             .await?;
@@ -53,11 +53,16 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
             future::ready(continue_stream)
         });
 
+        let multichannel = self.channels > 1;
         let transformed_stream = filtered_stream.filter_map(move |result| {
             let item = match result {
                 Err(e) => Some(Err(e.into())),
                 Ok(resp) => match resp {
-                    DeepgramStreamResponse::TranscriptResponse { channel, .. } => {
+                    DeepgramStreamResponse::TranscriptResponse {
+                        channel,
+                        channel_index,
+                        ..
+                    } => {
                         let data = channel.alternatives.first().unwrap();
 
                         if data.words.is_empty() {
@@ -82,7 +87,15 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
                                 })
                                 .collect();
 
-                            Some(Ok(ListenOutputChunk { words }))
+                            Some(Ok(ListenOutputChunk {
+                                words,
+                                language: None,
+                                channel: channel_index
+                                    .first()
+                                    .filter(|_| multichannel)
+                                    .map(|i| *i as u8),
+                                interim: false,
+                            }))
                         }
                     }
                     _ => None,
@@ -4,10 +4,11 @@ use std::error::Error;
 
 mod clova;
 mod deepgram;
+mod rtzr;
 mod whisper;
 
-use crate::deepgram::DeepgramClient;
-use hypr_listener_interface::ListenOutputChunk;
+use crate::{deepgram::DeepgramClient, Backend};
+use hypr_listener_interface::{ListenOutputChunk, Vocabulary};
 
 #[allow(dead_code)]
 pub trait RealtimeSpeechToText<S, E> {
@@ -27,25 +28,67 @@ pub trait RealtimeSpeechToText<S, E> {
 
 #[derive(Debug, Default)]
 pub struct ClientBuilder {
+    pub deepgram_api_base: Option<String>,
     pub deepgram_api_key: Option<String>,
+    pub clova_api_base: Option<String>,
     pub clova_api_key: Option<String>,
+    pub whisper_api_base: Option<String>,
+    pub whisper_api_key: Option<String>,
+    pub rtzr_client_id: Option<String>,
+    pub rtzr_client_secret: Option<String>,
 }
 
 impl ClientBuilder {
+    pub fn deepgram_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.deepgram_api_base = Some(api_base.into());
+        self
+    }
+
     pub fn deepgram_api_key(mut self, api_key: impl Into<String>) -> Self {
         self.deepgram_api_key = Some(api_key.into());
         self
     }
 
+    pub fn clova_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.clova_api_base = Some(api_base.into());
+        self
+    }
+
     pub fn clova_api_key(mut self, api_key: impl Into<String>) -> Self {
         self.clova_api_key = Some(api_key.into());
         self
     }
 
+    pub fn whisper_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.whisper_api_base = Some(api_base.into());
+        self
+    }
+
+    pub fn whisper_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.whisper_api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn rtzr_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.rtzr_client_id = Some(client_id.into());
+        self
+    }
+
+    pub fn rtzr_client_secret(mut self, client_secret: impl Into<String>) -> Self {
+        self.rtzr_client_secret = Some(client_secret.into());
+        self
+    }
+
     pub fn build(self) -> Client {
         Client {
+            deepgram_api_base: self.deepgram_api_base,
             deepgram_api_key: self.deepgram_api_key,
+            clova_api_base: self.clova_api_base,
             clova_api_key: self.clova_api_key,
+            whisper_api_base: self.whisper_api_base,
+            whisper_api_key: self.whisper_api_key,
+            rtzr_client_id: self.rtzr_client_id,
+            rtzr_client_secret: self.rtzr_client_secret,
         }
     }
 }
@@ -54,13 +97,20 @@ impl ClientBuilder {
 pub enum MultiClient {
     Clova(hypr_clova::realtime::Client),
     Deepgram(DeepgramClient),
+    Rtzr(hypr_rtzr::realtime::Client),
     Whisper(hypr_whisper::cloud::WhisperClient),
 }
 
 #[derive(Debug, Clone)]
 pub struct Client {
+    pub deepgram_api_base: Option<String>,
     pub deepgram_api_key: Option<String>,
+    pub clova_api_base: Option<String>,
     pub clova_api_key: Option<String>,
+    pub whisper_api_base: Option<String>,
+    pub whisper_api_key: Option<String>,
+    pub rtzr_client_id: Option<String>,
+    pub rtzr_client_secret: Option<String>,
 }
 
 impl Client {
@@ -68,37 +118,113 @@ impl Client {
         ClientBuilder::default()
     }
 
-    pub async fn for_language(&self, language: hypr_language::Language) -> MultiClient {
-        match language.iso639() {
-            hypr_language::ISO639::Ko => {
-                let clova = hypr_clova::realtime::Client::builder()
-                    .api_key(self.clova_api_key.as_ref().unwrap())
-                    .keywords(vec!["하이퍼노트".to_string()])
+    fn has_credentials(&self, backend: Backend) -> bool {
+        match backend {
+            Backend::Clova => self.clova_api_key.is_some(),
+            Backend::Deepgram => self.deepgram_api_key.is_some(),
+            Backend::Rtzr => self.rtzr_client_id.is_some() && self.rtzr_client_secret.is_some(),
+            Backend::Whisper => self.whisper_api_base.is_some() && self.whisper_api_key.is_some(),
+        }
+    }
+
+    // Like `Backend::for_realtime`, but skips backends this client has no credentials for.
+    pub fn backend_for(&self, language: &hypr_language::Language) -> Result<Backend, crate::Error> {
+        let preferred = Backend::for_realtime(language)?;
+
+        Backend::preferred(language)
+            .iter()
+            .find(|b| b.supports_realtime(language) && self.has_credentials(**b))
+            .copied()
+            .ok_or(crate::Error::MissingApiKey(preferred))
+    }
+
+    pub async fn for_language(
+        &self,
+        language: hypr_language::Language,
+        vocabulary: Vocabulary,
+    ) -> Result<MultiClient, crate::Error> {
+        match self.backend_for(&language)? {
+            Backend::Clova => {
+                let api_key = self
+                    .clova_api_key
+                    .as_ref()
+                    .ok_or(crate::Error::MissingApiKey(Backend::Clova))?;
+
+                let mut clova = hypr_clova::realtime::Client::builder().api_key(api_key);
+                if let Some(api_base) = &self.clova_api_base {
+                    clova = clova.api_base(api_base);
+                }
+
+                let clova = clova
+                    .language(language.for_clova_realtime()?)
+                    .keyword_boosting(clova::keyword_boosting(&vocabulary))
                     .build()
-                    .await
-                    .unwrap();
-                MultiClient::Clova(clova)
+                    .await?;
+
+                Ok(MultiClient::Clova(clova))
             }
-            hypr_language::ISO639::De => {
-                let whisper = hypr_whisper::cloud::WhisperClient::builder()
-                    .api_base(std::env::var("WHISPER_API_BASE").unwrap())
-                    .api_key(std::env::var("WHISPER_API_KEY").unwrap())
-                    .language(language.try_into().unwrap())
-                    .build();
+            Backend::Deepgram => {
+                let api_key = self
+                    .deepgram_api_key
+                    .as_ref()
+                    .ok_or(crate::Error::MissingApiKey(Backend::Deepgram))?;
+
+                let mut deepgram = DeepgramClient::builder().api_key(api_key);
+                if let Some(api_base) = &self.deepgram_api_base {
+                    deepgram = deepgram.api_base(api_base);
+                }
 
-                MultiClient::Whisper(whisper)
+                let deepgram = deepgram.vocabulary(vocabulary).language(language).build()?;
+
+                Ok(MultiClient::Deepgram(deepgram))
             }
-            hypr_language::ISO639::En => {
-                let deepgram = DeepgramClient::builder()
-                    .api_key(self.deepgram_api_key.as_ref().unwrap())
-                    .keywords(vec!["Hyprnote".to_string()])
-                    .language(language)
+            Backend::Rtzr => {
+                let (client_id, client_secret) = self
+                    .rtzr_client_id
+                    .as_ref()
+                    .zip(self.rtzr_client_secret.as_ref())
+                    .ok_or(crate::Error::MissingApiKey(Backend::Rtzr))?;
+
+                let rtzr = hypr_rtzr::realtime::Client::builder()
+                    .client_id(client_id)
+                    .client_secret(client_secret)
+                    .model_name(language.for_rtzr()?)
+                    .keywords(rtzr::keywords(&vocabulary))
                     .build()
-                    .unwrap();
+                    .await?;
+
+                Ok(MultiClient::Rtzr(rtzr))
+            }
+            Backend::Whisper => {
+                let (api_base, api_key) = self
+                    .whisper_api_base
+                    .as_ref()
+                    .zip(self.whisper_api_key.as_ref())
+                    .ok_or(crate::Error::MissingApiKey(Backend::Whisper))?;
+
+                let whisper = hypr_whisper::cloud::WhisperClient::builder()
+                    .api_base(api_base)
+                    .api_key(api_key)
+                    .language(language.try_into()?)
+                    // Whisper has no keyword boosting, so terms are primed through the prompt instead.
+                    .prompt(vocabulary.to_prompt())
+                    .build();
 
-                MultiClient::Deepgram(deepgram)
+                Ok(MultiClient::Whisper(whisper))
             }
-            _ => panic!("Unsupported language: {:?}", language),
+        }
+    }
+}
+
+impl MultiClient {
+    // Only Deepgram takes interleaved multichannel audio. Other backends need one stream per channel.
+    pub fn supports_multichannel(&self) -> bool {
+        matches!(self, MultiClient::Deepgram(_))
+    }
+
+    pub fn set_channels(&mut self, channels: u16) {
+        if let MultiClient::Deepgram(client) = self {
+            client.channels = channels;
         }
     }
 }
@@ -118,6 +244,7 @@ where
         match self {
             MultiClient::Clova(client) => Ok(Box::new(client.transcribe(stream).await?)),
             MultiClient::Deepgram(client) => Ok(Box::new(client.transcribe(stream).await?)),
+            MultiClient::Rtzr(client) => Ok(Box::new(client.transcribe(stream).await?)),
             MultiClient::Whisper(client) => Ok(Box::new(client.transcribe(stream).await?)),
         }
     }
@@ -187,6 +314,97 @@ mod tests {
         Box::pin(stream)
     }
 
+    fn stream_from_bytes_unpaced(
+        bytes: &[u8],
+    ) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send + Unpin + 'static {
+        let chunks: Vec<_> = bytes
+            .chunks(3200)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+
+        futures_util::stream::iter(chunks)
+    }
+
+    async fn collect_words(
+        mut client: MultiClient,
+        audio: &[u8],
+    ) -> Vec<hypr_listener_interface::Word> {
+        let mut transcript_stream = client
+            .transcribe(stream_from_bytes_unpaced(audio))
+            .await
+            .unwrap();
+
+        let mut acc = vec![];
+        while let Some(result) = transcript_stream.next().await {
+            acc.extend(result.unwrap().words);
+        }
+        acc
+    }
+
+    #[tokio::test]
+    async fn test_deepgram_mock() {
+        let script = hypr_stt_mock::Script::english();
+        let server = hypr_stt_mock::serve_deepgram(script.clone()).await;
+
+        let client = Client::builder()
+            .deepgram_api_base(server.api_base())
+            .deepgram_api_key("mock")
+            .build()
+            .for_language(hypr_language::ISO639::En.into(), Vocabulary::default())
+            .await
+            .unwrap();
+        assert!(matches!(client, MultiClient::Deepgram(_)));
+
+        let words = collect_words(client, hypr_data::english_2::AUDIO).await;
+
+        let expected: Vec<_> = script.words.iter().map(|w| w.text.trim()).collect();
+        let actual: Vec<_> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(actual, expected);
+        assert_eq!(words[0].start_ms, Some(script.words[0].start));
+    }
+
+    #[tokio::test]
+    async fn test_clova_mock() {
+        let script = hypr_stt_mock::Script::korean();
+        let server = hypr_stt_mock::serve_clova(script.clone()).await;
+
+        let client = Client::builder()
+            .clova_api_base(server.api_base())
+            .clova_api_key("mock")
+            .build()
+            .for_language(hypr_language::ISO639::Ko.into(), Vocabulary::default())
+            .await
+            .unwrap();
+        assert!(matches!(client, MultiClient::Clova(_)));
+
+        let words = collect_words(client, hypr_data::korean_2::AUDIO).await;
+        assert!(words.len() > 1);
+
+        let strip = |s: &str| s.split_whitespace().collect::<String>();
+        let actual: String = words.iter().map(|w| strip(&w.text)).collect();
+        assert_eq!(actual, strip(&script.text()));
+    }
+
+    #[test]
+    fn test_backend_for() {
+        let ko: hypr_language::Language = hypr_language::ISO639::Ko.into();
+
+        let client = Client::builder().clova_api_key("clova").build();
+        assert_eq!(client.backend_for(&ko).unwrap(), Backend::Clova);
+
+        let client = Client::builder()
+            .rtzr_client_id("id")
+            .rtzr_client_secret("secret")
+            .build();
+        assert_eq!(client.backend_for(&ko).unwrap(), Backend::Rtzr);
+
+        let client = Client::builder().build();
+        assert!(matches!(
+            client.backend_for(&ko).unwrap_err(),
+            crate::Error::MissingApiKey(Backend::Clova)
+        ));
+    }
+
     // RUST_TEST_TIMEOUT=0 cargo test test_deepgram -p stt --  --ignored --nocapture
     #[ignore]
     #[tokio::test]
@@ -198,8 +416,12 @@ mod tests {
         let mut client = Client::builder()
             .deepgram_api_key(std::env::var("DEEPGRAM_API_KEY").unwrap())
             .build()
-            .for_language(hypr_language::ISO639::En.into())
-            .await;
+            .for_language(
+                hypr_language::ISO639::En.into(),
+                ["Hyprnote"].into_iter().collect(),
+            )
+            .await
+            .unwrap();
 
         let mut transcript_stream = client.transcribe(audio_stream).await.unwrap();
 
@@ -227,8 +449,12 @@ mod tests {
         let mut client = Client::builder()
             .clova_api_key(std::env::var("CLOVA_API_KEY").unwrap())
             .build()
-            .for_language(hypr_language::ISO639::Ko.into())
-            .await;
+            .for_language(
+                hypr_language::ISO639::Ko.into(),
+                ["하이퍼노트"].into_iter().collect(),
+            )
+            .await
+            .unwrap();
 
         let mut transcript_stream = client.transcribe(audio_stream).await.unwrap();
 
@@ -0,0 +1,195 @@
+use bytes::Bytes;
+use futures_util::{future, Stream, StreamExt};
+use std::error::Error;
+
+use super::RealtimeSpeechToText;
+
+pub use hypr_listener_interface::{ListenOutputChunk, Vocabulary, Word};
+use hypr_rtzr::realtime::interface as rtzr;
+
+// https://developers.rtzr.ai/docs/en/stt-streaming/keyword-boosting
+pub fn keywords(vocabulary: &Vocabulary) -> Vec<String> {
+    vocabulary
+        .terms
+        .iter()
+        .map(|t| match t.boost {
+            Some(boost) => format!("{}:{}", t.text, boost),
+            None => t.text.clone(),
+        })
+        .collect()
+}
+
+// Settled results are forwarded as they are. Otherwise the latest hypothesis is sent as an interim chunk,
+// which the next chunk replaces.
+pub fn to_output_chunk(response: rtzr::DecoderResponse) -> Option<ListenOutputChunk> {
+    let (finals, interims): (Vec<_>, Vec<_>) =
+        response.results.into_iter().partition(|r| r.is_final);
+
+    let (results, interim) = if finals.is_empty() {
+        (interims, true)
+    } else {
+        (finals, false)
+    };
+
+    let words: Vec<Word> = results
+        .into_iter()
+        .filter_map(|r| {
+            let start_at = r.start_at.max(0) as u64;
+            let duration = r.duration.max(0) as u64;
+
+            r.alternatives.into_iter().next().map(|alt| {
+                if alt.words.is_empty() {
+                    vec![Word {
+                        text: alt.text.trim().to_string(),
+                        speaker: None,
+                        start_ms: Some(start_at),
+                        end_ms: Some(start_at + duration),
+                        confidence: Some(alt.confidence),
+                    }]
+                } else {
+                    alt.words
+                        .into_iter()
+                        .map(|w| Word {
+                            text: w.text.trim().to_string(),
+                            speaker: None,
+                            start_ms: Some(w.start_at.max(0) as u64),
+                            end_ms: Some((w.start_at + w.duration).max(0) as u64),
+                            confidence: Some(w.confidence),
+                        })
+                        .collect()
+                }
+            })
+        })
+        .flatten()
+        .filter(|w| !w.text.is_empty())
+        .collect();
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(ListenOutputChunk {
+            words,
+            language: None,
+            channel: None,
+            interim,
+        })
+    }
+}
+
+impl<S, E> RealtimeSpeechToText<S, E> for hypr_rtzr::realtime::Client {
+    async fn transcribe(
+        &mut self,
+        input_stream: S,
+    ) -> Result<
+        Box<dyn Stream<Item = Result<ListenOutputChunk, crate::Error>> + Send + Unpin>,
+        crate::Error,
+    >
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + Unpin + 'static,
+        E: Error + Send + Sync + 'static,
+    {
+        let output_stream = self.from_audio(input_stream).await?;
+
+        let stream = output_stream.filter_map(|item| {
+            let item = match item {
+                Err(e) => Some(Err(e.into())),
+                Ok(response) => to_output_chunk(response).map(Ok),
+            };
+
+            future::ready(item)
+        });
+
+        Ok(Box::from(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(results: Vec<rtzr::StreamingRecognitionResult>) -> rtzr::DecoderResponse {
+        rtzr::DecoderResponse {
+            error: false,
+            results,
+            speech_event_type: 0,
+        }
+    }
+
+    fn result(
+        text: &str,
+        is_final: bool,
+        words: Vec<rtzr::WordInfo>,
+    ) -> rtzr::StreamingRecognitionResult {
+        rtzr::StreamingRecognitionResult {
+            alternatives: vec![rtzr::SpeechRecognitionAlternative {
+                text: text.to_string(),
+                confidence: 0.8,
+                words,
+            }],
+            is_final,
+            stability: 0.0,
+            duration: 1500,
+            start_at: 2000,
+        }
+    }
+
+    #[test]
+    fn test_interim() {
+        let chunk = to_output_chunk(response(vec![result("안녕", false, vec![])])).unwrap();
+
+        assert!(chunk.interim);
+        assert_eq!(chunk.words[0].text, "안녕");
+    }
+
+    #[test]
+    fn test_final_without_words() {
+        let chunk = to_output_chunk(response(vec![
+            result("안녕하세요", true, vec![]),
+            result("반갑", false, vec![]),
+        ]))
+        .unwrap();
+
+        assert!(!chunk.interim);
+        assert_eq!(chunk.words.len(), 1);
+        assert_eq!(chunk.words[0].text, "안녕하세요");
+        assert_eq!(chunk.words[0].start_ms, Some(2000));
+        assert_eq!(chunk.words[0].end_ms, Some(3500));
+    }
+
+    #[test]
+    fn test_final_with_words() {
+        let words = vec![
+            rtzr::WordInfo {
+                start_at: 2000,
+                duration: 500,
+                text: "안녕".to_string(),
+                confidence: 0.9,
+                speaker_tag: 0,
+            },
+            rtzr::WordInfo {
+                start_at: 2500,
+                duration: 1000,
+                text: "하세요".to_string(),
+                confidence: 0.7,
+                speaker_tag: 0,
+            },
+        ];
+        let chunk = to_output_chunk(response(vec![result("안녕 하세요", true, words)])).unwrap();
+
+        let texts: Vec<_> = chunk.words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["안녕", "하세요"]);
+        assert_eq!(chunk.words[1].end_ms, Some(3500));
+    }
+
+    #[test]
+    fn test_keywords() {
+        let mut vocabulary: Vocabulary = ["하이퍼노트"].into_iter().collect();
+        vocabulary.push(hypr_listener_interface::VocabularyTerm {
+            text: "Blitz".to_string(),
+            boost: Some(2.5),
+            pronunciations: vec![],
+        });
+
+        assert_eq!(keywords(&vocabulary), vec!["하이퍼노트", "Blitz:2.5"]);
+    }
+}
@@ -30,6 +30,9 @@ impl<S, E> RealtimeSpeechToText<S, E> for WhisperClient {
                     start_ms: None,
                     confidence: None,
                 }],
+                language: None,
+                channel: None,
+                interim: false,
             })
         });
 
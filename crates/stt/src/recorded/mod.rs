@@ -4,7 +4,8 @@ use std::future::Future;
 mod clova;
 mod deepgram;
 
-use crate::deepgram::DeepgramClient;
+use crate::{deepgram::DeepgramClient, Backend};
+use hypr_listener_interface::Vocabulary;
 
 pub enum RecordedSpeech {
     File(std::path::PathBuf),
@@ -57,25 +58,42 @@ impl Client {
         ClientBuilder::default()
     }
 
-    pub async fn for_language(&self, language: hypr_language::Language) -> MultiClient {
-        match language.iso639() {
-            hypr_language::ISO639::Ko => {
+    pub async fn for_language(
+        &self,
+        language: hypr_language::Language,
+        vocabulary: Vocabulary,
+    ) -> Result<MultiClient, crate::Error> {
+        match Backend::for_recorded(&language)? {
+            Backend::Clova => {
                 let clova = hypr_clova::recorded::Client::builder()
                     .api_key(&self.clova_api_key)
+                    .language(language.for_clova_recorded()?)
+                    .boostings(
+                        vocabulary
+                            .terms
+                            .into_iter()
+                            .map(|t| hypr_clova::recorded::Boosting {
+                                words: std::iter::once(t.text)
+                                    .chain(t.pronunciations)
+                                    .collect::<Vec<_>>()
+                                    .join(","),
+                            })
+                            .collect::<Vec<_>>(),
+                    )
                     .build();
-                MultiClient::Clova(clova)
+
+                Ok(MultiClient::Clova(clova))
             }
-            hypr_language::ISO639::En => {
+            Backend::Deepgram => {
                 let deepgram = DeepgramClient::builder()
                     .api_key(&self.deepgram_api_key)
-                    .keywords(vec!["Hyprnote".to_string()])
+                    .vocabulary(vocabulary)
                     .language(language)
-                    .build()
-                    .unwrap();
+                    .build()?;
 
-                MultiClient::Deepgram(deepgram)
+                Ok(MultiClient::Deepgram(deepgram))
             }
-            _ => panic!("Unsupported language: {:?}", language),
+            Backend::Whisper => Err(crate::Error::UnsupportedLanguage(language.to_string())),
         }
     }
 }
@@ -0,0 +1,97 @@
+use hypr_db_user::{ChatMessageSource, SessionChunkHit};
+
+pub struct Answer {
+    pub content: String,
+    // Excerpt numbers cited in the content, in order of first appearance.
+    pub citations: Vec<u8>,
+}
+
+impl Answer {
+    pub(crate) fn new(content: String) -> Self {
+        let citations = citations(&content);
+        Self { content, citations }
+    }
+
+    // Sources for the cited excerpts, out of the `hits` the answer was given.
+    pub fn sources(&self, message_id: &str, hits: &[SessionChunkHit]) -> Vec<ChatMessageSource> {
+        self.citations
+            .iter()
+            .filter_map(|citation| {
+                let hit = hits.get((*citation as usize).checked_sub(1)?)?;
+
+                Some(ChatMessageSource {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    message_id: message_id.to_string(),
+                    session_id: hit.chunk.session_id.clone(),
+                    citation: *citation,
+                    text: hit.chunk.text.clone(),
+                    start_ms: hit.chunk.start_ms,
+                    end_ms: hit.chunk.end_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+// Excerpts as rendered in the chat template, numbered from 1.
+pub(crate) fn sources_context(hits: &[SessionChunkHit]) -> serde_json::Value {
+    hits.iter()
+        .enumerate()
+        .map(|(i, hit)| {
+            serde_json::json!({
+                "citation": i + 1,
+                "title": hit.title,
+                "date": hit.created_at.format("%Y-%m-%d").to_string(),
+                "timestamp": hit.chunk.start_ms.map(timestamp),
+                "kind": hit.chunk.kind.to_string(),
+                "text": hit.chunk.text,
+            })
+        })
+        .collect()
+}
+
+fn timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+
+    match secs / 3600 {
+        0 => format!("{:02}:{:02}", secs / 60, secs % 60),
+        hours => format!("{}:{:02}:{:02}", hours, secs / 60 % 60, secs % 60),
+    }
+}
+
+fn citations(content: &str) -> Vec<u8> {
+    let mut citations = Vec::new();
+
+    for part in content.split('[').skip(1) {
+        let Some((number, _)) = part.split_once(']') else {
+            continue;
+        };
+
+        if let Ok(citation) = number.trim().parse::<u8>() {
+            if citation > 0 && !citations.contains(&citation) {
+                citations.push(citation);
+            }
+        }
+    }
+    citations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_citations() {
+        assert_eq!(
+            citations("We ship on Friday [2]. Hiring is paused [1][2], see [link](url) and [ 3 ]."),
+            vec![2, 1, 3]
+        );
+        assert_eq!(citations("Nothing [0] relevant [x]."), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(65_000), "01:05");
+        assert_eq!(timestamp(3_725_000), "1:02:05");
+    }
+}
@@ -0,0 +1,136 @@
+const SEPARATOR: &str = "\n\n";
+
+// About four ASCII characters per token with most tokenizers. Other scripts, like CJK, are counted a token
+// per character, since a single one often takes a whole token.
+pub fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.bytes().filter(u8::is_ascii).count();
+    let other = text.chars().filter(|c| !c.is_ascii()).count();
+    ascii.div_ceil(4) + other
+}
+
+// Splits the output of the `timeline` filter into chunks of whole speaker turns.
+// A turn that doesn't fit in a chunk on its own is split between words, each part keeping the speaker.
+pub fn chunk_timeline(timeline: &str, max_tokens: usize) -> Vec<String> {
+    let turns = timeline
+        .split(SEPARATOR)
+        .filter(|turn| !turn.trim().is_empty())
+        .flat_map(|turn| split_turn(turn, max_tokens))
+        .collect::<Vec<_>>();
+
+    pack(&turns, max_tokens)
+}
+
+// Joins consecutive parts as long as they fit in `max_tokens`.
+pub fn pack(parts: &[String], max_tokens: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for part in parts {
+        if !current.is_empty()
+            && estimate_tokens(&current) + estimate_tokens(SEPARATOR) + estimate_tokens(part)
+                > max_tokens
+        {
+            chunks.push(std::mem::take(&mut current));
+        }
+
+        if !current.is_empty() {
+            current.push_str(SEPARATOR);
+        }
+        current.push_str(part);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn split_turn(turn: &str, max_tokens: usize) -> Vec<String> {
+    if estimate_tokens(turn) <= max_tokens {
+        return vec![turn.to_string()];
+    }
+
+    let (speaker, text) = turn.split_once('\n').unwrap_or(("", turn));
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty()
+            && estimate_tokens(speaker) + estimate_tokens(&current) + estimate_tokens(word) + 1
+                > max_tokens
+        {
+            parts.push(format!("{}\n{}", speaker, std::mem::take(&mut current)));
+        }
+
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+
+    if !current.is_empty() {
+        parts.push(format!("{}\n{}", speaker, current));
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_timeline() {
+        let timeline = hypr_template::timeline(hypr_data::english_3::WORDS_JSON.to_string());
+        let max_tokens = 200;
+
+        let chunks = chunk_timeline(&timeline, max_tokens);
+        assert!(chunks.len() > 1);
+
+        for chunk in &chunks {
+            assert!(estimate_tokens(chunk) <= max_tokens);
+            assert!(chunk.starts_with('['));
+        }
+
+        let words = |text: &str| {
+            text.lines()
+                .filter(|line| !line.starts_with('['))
+                .flat_map(str::split_whitespace)
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(words(&chunks.join(SEPARATOR)), words(&timeline));
+    }
+
+    #[test]
+    fn test_chunk_timeline_long_turn() {
+        let timeline = format!("[SPEAKER 0]\n{}", vec!["word"; 100].join(" "));
+
+        let chunks = chunk_timeline(&timeline, 20);
+        assert!(chunks.len() > 1);
+
+        for chunk in &chunks {
+            assert!(chunk.starts_with("[SPEAKER 0]\nword"));
+            assert!(estimate_tokens(chunk) <= 20);
+        }
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens("hello world!"), 3);
+        // Would be 7 by bytes.
+        assert_eq!(estimate_tokens("안녕하세요 여러분"), 9);
+        assert_eq!(estimate_tokens("会议 notes"), 4);
+    }
+
+    #[test]
+    fn test_pack() {
+        let parts = vec!["a".repeat(8), "b".repeat(8), "c".repeat(8)];
+
+        assert_eq!(pack(&parts, 100).len(), 1);
+        assert_eq!(pack(&parts, 5).len(), 2);
+        assert_eq!(pack(&parts, 2).len(), 3);
+    }
+}
@@ -0,0 +1,15 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    TemplateError(#[from] hypr_template::Error),
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    #[error(transparent)]
+    ReqwestMiddlewareError(#[from] reqwest_middleware::Error),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+    #[error("empty response")]
+    EmptyResponse,
+    #[error("summaries have {tokens} tokens, but only {limit} fit in a request")]
+    SummariesTooLong { tokens: usize, limit: usize },
+}
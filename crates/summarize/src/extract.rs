@@ -0,0 +1,96 @@
+use hypr_db_user::{ActionItem, Decision, Human};
+
+// What the model fills in. Owners are named, and resolved to participants afterwards.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct ExtractedItems {
+    action_items: Vec<ExtractedActionItem>,
+    decisions: Vec<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct ExtractedActionItem {
+    owner: Option<String>,
+    text: String,
+    due: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct Extraction {
+    pub action_items: Vec<ActionItem>,
+    pub decisions: Vec<Decision>,
+}
+
+impl Extraction {
+    pub(crate) fn extend(
+        &mut self,
+        session_id: &str,
+        participants: &[Human],
+        items: ExtractedItems,
+    ) {
+        for item in items.action_items {
+            self.action_items.push(ActionItem {
+                owner_id: item
+                    .owner
+                    .and_then(|owner| find_participant(participants, &owner))
+                    .map(|human| human.id.clone()),
+                due: item
+                    .due
+                    .and_then(|due| chrono::NaiveDate::parse_from_str(&due, "%Y-%m-%d").ok()),
+                ..ActionItem::new(session_id, item.text)
+            });
+        }
+
+        for text in items.decisions {
+            self.decisions.push(Decision::new(session_id, text));
+        }
+    }
+}
+
+// Schema of `ExtractedItems`, with owners limited to the participants' names.
+pub(crate) fn extraction_schema(participants: &[Human]) -> serde_json::Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(ExtractedItems)).unwrap();
+
+    let names = participants
+        .iter()
+        .filter_map(|human| human.full_name.clone())
+        .collect::<Vec<_>>();
+
+    if !names.is_empty() {
+        schema["definitions"]["ExtractedActionItem"]["properties"]["owner"] = serde_json::json!({
+            "anyOf": [{ "enum": names }, { "type": "null" }]
+        });
+    }
+
+    schema
+}
+
+fn find_participant<'a>(participants: &'a [Human], name: &str) -> Option<&'a Human> {
+    participants.iter().find(|human| {
+        human
+            .full_name
+            .as_deref()
+            .is_some_and(|full_name| full_name.trim().eq_ignore_ascii_case(name.trim()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extraction_schema() {
+        let participants = vec![Human {
+            full_name: Some("Jane Doe".to_string()),
+            ..Human::default()
+        }];
+
+        let schema = extraction_schema(&participants);
+        assert_eq!(
+            schema["definitions"]["ExtractedActionItem"]["properties"]["owner"]["anyOf"][0]["enum"],
+            serde_json::json!(["Jane Doe"])
+        );
+
+        let grammar = hypr_gbnf::json_schema_grammar(&schema);
+        assert!(grammar.contains(r#""\"Jane Doe\"""#));
+    }
+}
@@ -0,0 +1,533 @@
+use hypr_db_user::{ChatMessage, ChatMessageRole, Human, SessionChunkHit};
+use hypr_openai::{
+    ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequest, CreateChatCompletionResponse, ResponseFormat,
+    ResponseFormatJsonSchema,
+};
+use hypr_template::{minijinja::Environment, PredefinedTemplate};
+
+mod answer;
+mod chunk;
+mod error;
+mod extract;
+
+pub use answer::*;
+pub use chunk::*;
+pub use error::*;
+pub use extract::*;
+
+const DEFAULT_CHUNK_TOKENS: usize = 1024 * 4;
+
+type Context = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Default)]
+pub struct SummarizerBuilder {
+    api_base: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    chunk_tokens: Option<usize>,
+}
+
+impl SummarizerBuilder {
+    pub fn api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    // Transcript tokens sent in a single request. Should leave room for the prompt and the response.
+    pub fn chunk_tokens(mut self, chunk_tokens: usize) -> Self {
+        self.chunk_tokens = Some(chunk_tokens);
+        self
+    }
+
+    pub fn build(self) -> Summarizer {
+        let mut client = hypr_openai::OpenAIClient::builder().api_base(self.api_base.unwrap());
+        if let Some(api_key) = self.api_key {
+            client = client.api_key(api_key);
+        }
+
+        Summarizer {
+            client: client.build(),
+            model: self.model.unwrap(),
+            chunk_tokens: self.chunk_tokens.unwrap_or(DEFAULT_CHUNK_TOKENS),
+        }
+    }
+}
+
+// Map-reduce over transcripts that don't fit in the context window of the model.
+pub struct Summarizer {
+    client: hypr_openai::OpenAIClient,
+    model: String,
+    chunk_tokens: usize,
+}
+
+impl Summarizer {
+    pub fn builder() -> SummarizerBuilder {
+        SummarizerBuilder::default()
+    }
+
+    // Takes the context of the enhance templates, with `words` as JSON.
+    // Returns `None` if the transcript fits in a single request.
+    pub async fn summarize(
+        &self,
+        env: &Environment<'static>,
+        ctx: &Context,
+    ) -> Result<Option<Vec<String>>, crate::Error> {
+        let mut chunks = self.chunks(ctx);
+        if chunks.len() <= 1 {
+            return Ok(None);
+        }
+
+        loop {
+            let summaries = self.summarize_chunks(env, ctx, &chunks).await?;
+
+            // Summaries that still don't fit together are summarized again.
+            let packed = pack(&summaries, self.chunk_tokens);
+            if packed.len() == 1 && estimate_tokens(&packed[0]) <= self.chunk_tokens {
+                return Ok(Some(summaries));
+            }
+            // Another round wouldn't make them shorter.
+            if packed.len() >= chunks.len() {
+                return Err(crate::Error::SummariesTooLong {
+                    tokens: summaries.iter().map(|s| estimate_tokens(s)).sum(),
+                    limit: self.chunk_tokens,
+                });
+            }
+            chunks = packed;
+        }
+    }
+
+    // Runs the enhance templates, over partial summaries if the transcript is too long.
+    pub async fn enhance(
+        &self,
+        env: &Environment<'static>,
+        mut ctx: Context,
+    ) -> Result<String, crate::Error> {
+        if let Some(summaries) = self.summarize(env, &ctx).await? {
+            ctx.insert("summaries".to_string(), summaries.into());
+        }
+
+        let system = hypr_template::render(env, PredefinedTemplate::EnhanceSystem.into(), &ctx)?;
+        let user = hypr_template::render(env, PredefinedTemplate::EnhanceUser.into(), &ctx)?;
+
+        self.complete(system, user, None).await
+    }
+
+    // Takes the context of the enhance templates, with `session_id`, and optionally `date` to resolve due dates.
+    // Each chunk of the transcript is extracted from separately.
+    pub async fn extract(
+        &self,
+        env: &Environment<'static>,
+        ctx: &Context,
+    ) -> Result<Extraction, crate::Error> {
+        let session_id = ctx
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        let participants = ctx
+            .get("participants")
+            .and_then(|v| v.as_array())
+            .map(|participants| {
+                participants
+                    .iter()
+                    .filter_map(|p| serde_json::from_value::<Human>(p.clone()).ok())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let response_format = ResponseFormat::JsonSchema {
+            json_schema: ResponseFormatJsonSchema {
+                name: "extraction".to_string(),
+                description: None,
+                schema: Some(extract::extraction_schema(&participants)),
+                strict: None,
+            },
+        };
+
+        let system = hypr_template::render(env, PredefinedTemplate::ExtractSystem.into(), ctx)?;
+        let chunks = self.chunks(ctx);
+
+        let mut extraction = Extraction::default();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut ctx = ctx.clone();
+            ctx.insert("chunk".to_string(), chunk.as_str().into());
+            ctx.insert("index".to_string(), (i + 1).into());
+            ctx.insert("total".to_string(), chunks.len().into());
+
+            let user = hypr_template::render(env, PredefinedTemplate::ExtractUser.into(), &ctx)?;
+            let content = self
+                .complete(system.clone(), user, Some(response_format.clone()))
+                .await?;
+
+            extraction.extend(session_id, &participants, serde_json::from_str(&content)?);
+        }
+
+        Ok(extraction)
+    }
+
+    // Takes the context of the chat templates, with the `question`, and answers it from the `hits`.
+    // `history` is the conversation so far, without the question.
+    pub async fn answer(
+        &self,
+        env: &Environment<'static>,
+        mut ctx: Context,
+        history: &[ChatMessage],
+        hits: &[SessionChunkHit],
+    ) -> Result<Answer, crate::Error> {
+        ctx.insert("sources".to_string(), answer::sources_context(hits));
+
+        let system = hypr_template::render(env, PredefinedTemplate::AiChatSystem.into(), &ctx)?;
+        let user = hypr_template::render(env, PredefinedTemplate::AiChatUser.into(), &ctx)?;
+
+        let mut messages = vec![system_message(system)];
+        messages.extend(history.iter().map(|message| {
+            match message.role {
+                ChatMessageRole::User => user_message(message.content.clone()),
+                ChatMessageRole::Assistant => ChatCompletionRequestMessage::Assistant(
+                    ChatCompletionRequestAssistantMessageArgs::default()
+                        .content(message.content.clone())
+                        .build()
+                        .unwrap(),
+                ),
+            }
+        }));
+        messages.push(user_message(user));
+
+        let content = self.complete_messages(messages, None).await?;
+        Ok(Answer::new(content))
+    }
+
+    fn chunks(&self, ctx: &Context) -> Vec<String> {
+        let words = ctx
+            .get("words")
+            .and_then(|v| v.as_str())
+            .unwrap_or("[]")
+            .to_string();
+
+        chunk_timeline(&hypr_template::timeline(words), self.chunk_tokens)
+    }
+
+    async fn summarize_chunks(
+        &self,
+        env: &Environment<'static>,
+        ctx: &Context,
+        chunks: &[String],
+    ) -> Result<Vec<String>, crate::Error> {
+        let system =
+            hypr_template::render(env, PredefinedTemplate::SummarizeChunkSystem.into(), ctx)?;
+
+        let mut summaries = Vec::with_capacity(chunks.len());
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut ctx = ctx.clone();
+            ctx.insert("chunk".to_string(), chunk.as_str().into());
+            ctx.insert("index".to_string(), (i + 1).into());
+            ctx.insert("total".to_string(), chunks.len().into());
+
+            let user =
+                hypr_template::render(env, PredefinedTemplate::SummarizeChunkUser.into(), &ctx)?;
+            summaries.push(self.complete(system.clone(), user, None).await?);
+        }
+
+        Ok(summaries)
+    }
+
+    async fn complete(
+        &self,
+        system: String,
+        user: String,
+        response_format: Option<ResponseFormat>,
+    ) -> Result<String, crate::Error> {
+        self.complete_messages(
+            vec![system_message(system), user_message(user)],
+            response_format,
+        )
+        .await
+    }
+
+    async fn complete_messages(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        response_format: Option<ResponseFormat>,
+    ) -> Result<String, crate::Error> {
+        let request = CreateChatCompletionRequest {
+            model: self.model.clone(),
+            messages,
+            stream: Some(false),
+            response_format,
+            ..Default::default()
+        };
+
+        let response: CreateChatCompletionResponse = self
+            .client
+            .chat_completion(&request)
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or(crate::Error::EmptyResponse)
+    }
+}
+
+fn system_message(content: String) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::System(
+        ChatCompletionRequestSystemMessageArgs::default()
+            .content(content)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn user_message(content: String) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::User(
+        ChatCompletionRequestUserMessageArgs::default()
+            .content(content)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    fn env() -> Environment<'static> {
+        let mut env = Environment::new();
+        hypr_template::init(&mut env);
+        env
+    }
+
+    fn ctx() -> Context {
+        serde_json::json!({
+            "type": "HyprCloud",
+            "config": { "general": { "display_language": "en" } },
+            "words": hypr_data::english_3::WORDS_JSON,
+            "participants": [],
+            "editor": "",
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    fn user_message(request: &serde_json::Value) -> String {
+        request["messages"][1]["content"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_enhance_map_reduce() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let server = hypr_llm_mock::serve_openai({
+            let requests = requests.clone();
+
+            move |request| {
+                let user = user_message(request);
+                let mut requests = requests.lock().unwrap();
+                requests.push(user.clone());
+
+                if user.contains("<transcript_part>") {
+                    format!("- summary {}", requests.len())
+                } else {
+                    "# Note".to_string()
+                }
+            }
+        })
+        .await;
+
+        let summarizer = Summarizer::builder()
+            .api_base(server.api_base())
+            .model("mock")
+            .chunk_tokens(300)
+            .build();
+
+        let note = summarizer.enhance(&env(), ctx()).await.unwrap();
+        assert_eq!(note, "# Note");
+
+        let requests = requests.lock().unwrap();
+        let (last, parts) = requests.split_last().unwrap();
+        assert!(parts.len() > 1);
+
+        for (i, part) in parts.iter().enumerate() {
+            assert!(part.contains(&format!("part {} of {}", i + 1, parts.len())));
+            assert!(last.contains(&format!("- summary {}", i + 1)));
+        }
+        assert!(last.contains("<transcript_summaries>"));
+        assert!(!last.contains("<transcript>"));
+    }
+
+    #[tokio::test]
+    async fn test_enhance_summaries_too_long() {
+        let server = hypr_llm_mock::serve_openai(|_| "- summary ".repeat(200)).await;
+
+        let summarizer = Summarizer::builder()
+            .api_base(server.api_base())
+            .model("mock")
+            .chunk_tokens(300)
+            .build();
+
+        assert!(matches!(
+            summarizer.enhance(&env(), ctx()).await,
+            Err(crate::Error::SummariesTooLong { limit: 300, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_extract() {
+        let human = Human {
+            full_name: Some("Jane Doe".to_string()),
+            ..Human::default()
+        };
+
+        let server = hypr_llm_mock::serve_openai(|request| {
+            assert_eq!(request["response_format"]["type"], "json_schema");
+            assert!(user_message(request).contains("- Jane Doe"));
+
+            serde_json::json!({
+                "action_items": [
+                    { "owner": "jane doe", "text": "Send the deck", "due": "2025-01-31" },
+                    { "owner": null, "text": "Book a room", "due": "next week" }
+                ],
+                "decisions": ["Ship on Friday"]
+            })
+            .to_string()
+        })
+        .await;
+
+        let summarizer = Summarizer::builder()
+            .api_base(server.api_base())
+            .model("mock")
+            .chunk_tokens(300)
+            .build();
+
+        let mut ctx = ctx();
+        ctx.insert("session_id".to_string(), "session".into());
+        ctx.insert("participants".to_string(), serde_json::json!([human]));
+
+        let extraction = summarizer.extract(&env(), &ctx).await.unwrap();
+        let chunks = summarizer.chunks(&ctx).len();
+        assert!(chunks > 1);
+        assert_eq!(extraction.action_items.len(), 2 * chunks);
+        assert_eq!(extraction.decisions.len(), chunks);
+
+        let item = &extraction.action_items[0];
+        assert_eq!(item.session_id, "session");
+        assert_eq!(item.owner_id, Some(human.id.clone()));
+        assert_eq!(item.due, chrono::NaiveDate::from_ymd_opt(2025, 1, 31));
+
+        let item = &extraction.action_items[1];
+        assert_eq!(item.owner_id, None);
+        assert_eq!(item.due, None);
+    }
+
+    #[tokio::test]
+    async fn test_answer() {
+        let server = hypr_llm_mock::serve_openai(|request| {
+            let messages = request["messages"].as_array().unwrap();
+            assert_eq!(messages.len(), 4);
+            assert_eq!(messages[2]["role"], "assistant");
+
+            let user = messages[3]["content"].as_str().unwrap();
+            assert!(user.contains("[2] Weekly, 2025-01-20 at 01:05 (transcript):"));
+            assert!(user.ends_with("When do we ship?"));
+
+            "We ship on Friday [2].".to_string()
+        })
+        .await;
+
+        let summarizer = Summarizer::builder()
+            .api_base(server.api_base())
+            .model("mock")
+            .build();
+
+        let hit = |text: &str, start_ms: Option<u64>| SessionChunkHit {
+            chunk: hypr_db_user::SessionChunk {
+                id: "chunk".to_string(),
+                session_id: "session".to_string(),
+                kind: match start_ms {
+                    Some(_) => hypr_db_user::SessionChunkKind::Transcript,
+                    None => hypr_db_user::SessionChunkKind::Note,
+                },
+                text: text.to_string(),
+                embedding: vec![],
+                start_ms,
+                end_ms: start_ms.map(|ms| ms + 5000),
+            },
+            title: "Weekly".to_string(),
+            created_at: "2025-01-20T10:00:00Z".parse().unwrap(),
+            score: 1.0,
+        };
+        let hits = vec![
+            hit("Release is planned", None),
+            hit("we ship on friday", Some(65_000)),
+        ];
+
+        let history = ["Hi", "Hello!"]
+            .iter()
+            .zip([ChatMessageRole::User, ChatMessageRole::Assistant])
+            .map(|(content, role)| ChatMessage {
+                id: content.to_string(),
+                group_id: "group".to_string(),
+                created_at: chrono::Utc::now(),
+                role,
+                content: content.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut ctx = ctx();
+        ctx.insert("question".to_string(), "When do we ship?".into());
+
+        let answer = summarizer
+            .answer(&env(), ctx, &history, &hits)
+            .await
+            .unwrap();
+        assert_eq!(answer.content, "We ship on Friday [2].");
+
+        let sources = answer.sources("message", &hits);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].citation, 2);
+        assert_eq!(sources[0].text, "we ship on friday");
+        assert_eq!(sources[0].start_ms, Some(65_000));
+    }
+
+    #[tokio::test]
+    async fn test_enhance_short_transcript() {
+        let server = hypr_llm_mock::serve_openai(|request| {
+            assert!(user_message(request).contains("<transcript>"));
+            "# Note".to_string()
+        })
+        .await;
+
+        let summarizer = Summarizer::builder()
+            .api_base(server.api_base())
+            .model("mock")
+            .build();
+
+        let note = summarizer.enhance(&env(), ctx()).await.unwrap();
+        assert_eq!(note, "# Note");
+    }
+}
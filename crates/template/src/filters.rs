@@ -162,4 +162,33 @@ mod tests {
         a letter opener and stick it in your skull hey this doesn't matter and i don't even care michael you quit the other job or you're fired here
         "###);
     }
+
+    #[test]
+    fn test_timeline_assigned() {
+        let word = |text: &str, speaker: SpeakerIdentity| Word {
+            text: text.to_string(),
+            speaker: Some(speaker),
+            confidence: None,
+            start_ms: None,
+            end_ms: None,
+        };
+        let alice = SpeakerIdentity::Assigned {
+            id: "1".to_string(),
+            label: "Alice".to_string(),
+        };
+
+        let words = vec![
+            word("hello", alice.clone()),
+            word("there", alice),
+            word("hi", SpeakerIdentity::Unassigned { index: 1 }),
+        ];
+
+        insta::assert_snapshot!(timeline(serde_json::to_string(&words).unwrap()), @r###"
+        [Alice]
+        hello there
+
+        [SPEAKER 1]
+        hi
+        "###);
+    }
 }
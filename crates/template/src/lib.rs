@@ -3,6 +3,8 @@ use codes_iso_639::part_1::LanguageCode;
 mod filters;
 mod testers;
 
+pub use filters::timeline;
+
 mod error;
 pub use error::*;
 
@@ -38,6 +40,18 @@ pub enum PredefinedTemplate {
     CreateTitleSystem,
     #[strum(serialize = "create_title.user")]
     CreateTitleUser,
+    #[strum(serialize = "summarize_chunk.system")]
+    SummarizeChunkSystem,
+    #[strum(serialize = "summarize_chunk.user")]
+    SummarizeChunkUser,
+    #[strum(serialize = "extract.system")]
+    ExtractSystem,
+    #[strum(serialize = "extract.user")]
+    ExtractUser,
+    #[strum(serialize = "ai_chat.system")]
+    AiChatSystem,
+    #[strum(serialize = "ai_chat.user")]
+    AiChatUser,
 }
 
 impl From<PredefinedTemplate> for Template {
@@ -53,6 +67,18 @@ impl From<PredefinedTemplate> for Template {
             PredefinedTemplate::CreateTitleUser => {
                 Template::Static(PredefinedTemplate::CreateTitleUser)
             }
+            PredefinedTemplate::SummarizeChunkSystem => {
+                Template::Static(PredefinedTemplate::SummarizeChunkSystem)
+            }
+            PredefinedTemplate::SummarizeChunkUser => {
+                Template::Static(PredefinedTemplate::SummarizeChunkUser)
+            }
+            PredefinedTemplate::ExtractSystem => {
+                Template::Static(PredefinedTemplate::ExtractSystem)
+            }
+            PredefinedTemplate::ExtractUser => Template::Static(PredefinedTemplate::ExtractUser),
+            PredefinedTemplate::AiChatSystem => Template::Static(PredefinedTemplate::AiChatSystem),
+            PredefinedTemplate::AiChatUser => Template::Static(PredefinedTemplate::AiChatUser),
         }
     }
 }
@@ -61,6 +87,12 @@ pub const ENHANCE_SYSTEM_TPL: &str = include_str!("../assets/enhance.system.jinj
 pub const ENHANCE_USER_TPL: &str = include_str!("../assets/enhance.user.jinja");
 pub const CREATE_TITLE_SYSTEM_TPL: &str = include_str!("../assets/create_title.system.jinja");
 pub const CREATE_TITLE_USER_TPL: &str = include_str!("../assets/create_title.user.jinja");
+pub const SUMMARIZE_CHUNK_SYSTEM_TPL: &str = include_str!("../assets/summarize_chunk.system.jinja");
+pub const SUMMARIZE_CHUNK_USER_TPL: &str = include_str!("../assets/summarize_chunk.user.jinja");
+pub const EXTRACT_SYSTEM_TPL: &str = include_str!("../assets/extract.system.jinja");
+pub const EXTRACT_USER_TPL: &str = include_str!("../assets/extract.user.jinja");
+pub const AI_CHAT_SYSTEM_TPL: &str = include_str!("../assets/ai_chat.system.jinja");
+pub const AI_CHAT_USER_TPL: &str = include_str!("../assets/ai_chat.user.jinja");
 
 pub fn init(env: &mut minijinja::Environment) {
     env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
@@ -82,6 +114,30 @@ pub fn init(env: &mut minijinja::Environment) {
         CREATE_TITLE_USER_TPL,
     )
     .unwrap();
+    env.add_template(
+        PredefinedTemplate::SummarizeChunkSystem.as_ref(),
+        SUMMARIZE_CHUNK_SYSTEM_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::SummarizeChunkUser.as_ref(),
+        SUMMARIZE_CHUNK_USER_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::ExtractSystem.as_ref(),
+        EXTRACT_SYSTEM_TPL,
+    )
+    .unwrap();
+    env.add_template(PredefinedTemplate::ExtractUser.as_ref(), EXTRACT_USER_TPL)
+        .unwrap();
+    env.add_template(
+        PredefinedTemplate::AiChatSystem.as_ref(),
+        AI_CHAT_SYSTEM_TPL,
+    )
+    .unwrap();
+    env.add_template(PredefinedTemplate::AiChatUser.as_ref(), AI_CHAT_USER_TPL)
+        .unwrap();
 
     env.add_filter("timeline", filters::timeline);
     env.add_filter("language", filters::language);
@@ -0,0 +1,244 @@
+use std::io::Write;
+
+use super::Segment;
+
+// Phrases Whisper tends to produce on silence or music, mostly inherited from subtitled video in its training data.
+pub const DEFAULT_BLACKLIST: &[&str] = &[
+    "thank you for watching",
+    "thanks for watching",
+    "thank you so much for watching",
+    "thank you for watching and please subscribe",
+    "please subscribe",
+    "like and subscribe",
+    "dont forget to like and subscribe",
+    "see you in the next video",
+    "subtitles by the amaraorg community",
+    "transcribed by otterai",
+    "시청해주셔서 감사합니다",
+    "구독과 좋아요 부탁드립니다",
+    "ご視聴ありがとうございました",
+    "請不吝點贊 訂閱 轉發 打賞支持明鏡與點點欄目",
+    "untertitel der amaraorg community",
+];
+
+#[derive(Debug, Clone)]
+pub struct HallucinationFilter {
+    pub blacklist: Vec<String>,
+    pub min_confidence: f32,
+    // https://github.com/openai/whisper/blob/ba3f3cd/whisper/transcribe.py#L49-L50
+    pub no_speech_threshold: f32,
+    pub logprob_threshold: f32,
+    pub compression_ratio_threshold: f32,
+    pub max_ngram_size: usize,
+    pub max_ngram_repeats: usize,
+}
+
+impl Default for HallucinationFilter {
+    fn default() -> Self {
+        Self {
+            blacklist: DEFAULT_BLACKLIST.iter().map(|s| s.to_string()).collect(),
+            min_confidence: 0.4,
+            no_speech_threshold: 0.6,
+            logprob_threshold: -1.0,
+            compression_ratio_threshold: 2.4,
+            max_ngram_size: 4,
+            max_ngram_repeats: 2,
+        }
+    }
+}
+
+impl HallucinationFilter {
+    pub fn apply(&self, mut segment: Segment) -> Option<Segment> {
+        if segment.confidence < self.min_confidence {
+            tracing::warn!(
+                confidence = segment.confidence,
+                "low_confidence: {}",
+                segment.text
+            );
+            return None;
+        }
+
+        // Like Whisper, a likely silent chunk is still kept if it was decoded confidently.
+        if segment.no_speech_prob >= self.no_speech_threshold
+            && segment.avg_logprob < self.logprob_threshold
+        {
+            tracing::warn!(
+                no_speech_prob = segment.no_speech_prob,
+                avg_logprob = segment.avg_logprob,
+                "no_speech: {}",
+                segment.text
+            );
+            return None;
+        }
+
+        segment.text = self.collapse_repetitions(&segment.text);
+
+        let normalized = normalize(&segment.text);
+        if normalized.is_empty() {
+            return None;
+        }
+
+        if self.blacklist.iter().any(|b| normalize(b) == normalized) {
+            tracing::warn!("blacklisted_hallucination: {}", segment.text);
+            return None;
+        }
+
+        let ratio = compression_ratio(&segment.text);
+        if ratio > self.compression_ratio_threshold {
+            tracing::warn!(
+                compression_ratio = ratio,
+                "repetitive_hallucination: {}",
+                segment.text
+            );
+            return None;
+        }
+
+        Some(segment)
+    }
+
+    // Keeps a single copy of any n-gram repeated back-to-back more than `max_ngram_repeats` times.
+    pub fn collapse_repetitions(&self, text: &str) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let keys: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+
+        let mut out = Vec::with_capacity(words.len());
+        let mut i = 0;
+
+        while i < words.len() {
+            let mut skip = None;
+
+            for n in 1..=self.max_ngram_size {
+                if i + n > words.len() {
+                    break;
+                }
+
+                let mut repeats = 1;
+                while i + (repeats + 1) * n <= words.len()
+                    && keys[i + repeats * n..i + (repeats + 1) * n] == keys[i..i + n]
+                {
+                    repeats += 1;
+                }
+
+                if repeats > self.max_ngram_repeats {
+                    skip = Some((n, repeats));
+                    break;
+                }
+            }
+
+            match skip {
+                Some((n, repeats)) => {
+                    out.extend_from_slice(&words[i..i + n]);
+                    i += n * repeats;
+                }
+                None => {
+                    out.push(words[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        out.join(" ")
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Same measure as Whisper's `compression_ratio`, which uses zlib.
+pub fn compression_ratio(text: &str) -> f32 {
+    let bytes = text.as_bytes();
+    if bytes.is_empty() {
+        return 0.0;
+    }
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    let compressed = encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .map(|c| c.len())
+        .unwrap_or(bytes.len());
+
+    bytes.len() as f32 / compressed as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct Fixture {
+        text: String,
+        confidence: f32,
+        avg_logprob: f32,
+        no_speech_prob: f32,
+        expected: Option<String>,
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let fixtures: Vec<Fixture> =
+            serde_json::from_str(hypr_data::whisper_hallucination::SEGMENTS_JSON).unwrap();
+        let filter = HallucinationFilter::default();
+
+        for fixture in fixtures {
+            let segment = Segment {
+                text: fixture.text.clone(),
+                confidence: fixture.confidence,
+                avg_logprob: fixture.avg_logprob,
+                no_speech_prob: fixture.no_speech_prob,
+                ..Default::default()
+            };
+
+            let actual = filter.apply(segment).map(|s| s.text);
+            assert_eq!(actual, fixture.expected, "{}", fixture.text);
+        }
+    }
+
+    #[test]
+    fn test_collapse_repetitions() {
+        let filter = HallucinationFilter::default();
+
+        assert_eq!(filter.collapse_repetitions("no no no no"), "no");
+        assert_eq!(filter.collapse_repetitions("no no"), "no no");
+        assert_eq!(
+            filter.collapse_repetitions("a b c a b c a b c d"),
+            "a b c d"
+        );
+        assert_eq!(filter.collapse_repetitions(""), "");
+    }
+
+    #[test]
+    fn test_custom_blacklist() {
+        let filter = HallucinationFilter {
+            blacklist: vec!["Hyprnote".to_string()],
+            ..Default::default()
+        };
+
+        let segment = Segment {
+            text: "Thank you for watching.".to_string(),
+            confidence: 1.0,
+            ..Default::default()
+        };
+        assert!(filter.apply(segment).is_some());
+
+        let segment = Segment {
+            text: "hyprnote!".to_string(),
+            confidence: 1.0,
+            ..Default::default()
+        };
+        assert!(filter.apply(segment).is_none());
+    }
+
+    #[test]
+    fn test_compression_ratio() {
+        assert!(compression_ratio("Let's ship it on Friday.") < 1.0);
+        assert!(compression_ratio(&"the same words again ".repeat(20)) > 2.4);
+    }
+}
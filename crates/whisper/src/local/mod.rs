@@ -8,3 +8,6 @@ pub use model::*;
 
 mod error;
 pub use error::*;
+
+mod filter;
+pub use filter::*;
@@ -1,23 +1,67 @@
 // https://github.com/tazz4843/whisper-rs/blob/master/examples/audio_transcription.rs
 
+use std::sync::Arc;
+
 use lazy_static::lazy_static;
 use regex::Regex;
 
 use whisper_rs::{
-    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
-    WhisperToken,
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperToken,
 };
 
 lazy_static! {
     static ref TRAILING_DOTS: Regex = Regex::new(r"\.{2,}$").unwrap();
 }
 
+pub use whisper_rs::WhisperState;
+
+// Cheap to clone. All states created from it share the loaded weights.
+#[derive(Clone)]
+pub struct WhisperModel {
+    ctx: Arc<WhisperContext>,
+}
+
+impl WhisperModel {
+    pub fn load(model_path: impl AsRef<str>) -> Result<Self, super::Error> {
+        unsafe { suppress_log() };
+
+        let context_param = {
+            let mut p = WhisperContextParameters::default();
+            p.dtw_parameters.mode = whisper_rs::DtwMode::None;
+            p
+        };
+
+        let ctx = WhisperContext::new_with_params(model_path.as_ref(), context_param)?;
+        Ok(Self { ctx: Arc::new(ctx) })
+    }
+
+    pub fn create_state(&self) -> Result<WhisperState, super::Error> {
+        Ok(self.ctx.create_state()?)
+    }
+
+    fn token_eot(&self) -> WhisperToken {
+        self.ctx.token_eot()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectLanguage {
+    // Detect on the first chunk that produces speech, then keep that language.
+    Once,
+    // Detect on every chunk, to follow speakers switching languages.
+    PerChunk,
+}
+
 #[derive(Default)]
 pub struct WhisperBuilder {
     model_path: Option<String>,
+    model: Option<WhisperModel>,
+    state: Option<WhisperState>,
     language: Option<crate::Language>,
+    detect_language: Option<DetectLanguage>,
     static_prompt: Option<String>,
     dynamic_prompt: Option<String>,
+    filter: Option<super::HallucinationFilter>,
 }
 
 impl WhisperBuilder {
@@ -26,11 +70,28 @@ impl WhisperBuilder {
         self
     }
 
+    pub fn model(mut self, model: WhisperModel) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    // Must be created from the same `WhisperModel` passed to `model`.
+    pub fn state(mut self, state: WhisperState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
     pub fn language(mut self, language: crate::Language) -> Self {
         self.language = Some(language);
         self
     }
 
+    // Takes precedence over `language`.
+    pub fn detect_language(mut self, detect_language: DetectLanguage) -> Self {
+        self.detect_language = Some(detect_language);
+        self
+    }
+
     pub fn static_prompt(mut self, static_prompt: impl Into<String>) -> Self {
         self.static_prompt = Some(static_prompt.into());
         self
@@ -41,47 +102,73 @@ impl WhisperBuilder {
         self
     }
 
-    pub fn build(self) -> Whisper {
-        unsafe { Self::suppress_log() };
+    pub fn filter(mut self, filter: super::HallucinationFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
 
-        let context_param = {
-            let mut p = WhisperContextParameters::default();
-            p.dtw_parameters.mode = whisper_rs::DtwMode::None;
-            p
+    pub fn build(self) -> Whisper {
+        let model = match self.model {
+            Some(model) => model,
+            None => WhisperModel::load(self.model_path.unwrap()).unwrap(),
         };
 
-        let model_path = self.model_path.unwrap();
-
-        let ctx = WhisperContext::new_with_params(&model_path, context_param).unwrap();
-        let state = ctx.create_state().unwrap();
-        let eot = ctx.token_eot();
-
-        let language = self.language.unwrap_or(crate::Language::En);
+        let state = match self.state {
+            Some(state) => state,
+            None => model.create_state().unwrap(),
+        };
+        let eot = model.token_eot();
 
         Whisper {
-            language,
+            language: resolve_language(self.language, self.detect_language),
+            detect_language: self.detect_language,
             static_prompt: self.static_prompt.unwrap_or_default(),
             dynamic_prompt: self.dynamic_prompt.unwrap_or_default(),
+            filter: self.filter.unwrap_or_default(),
             state,
             eot,
         }
     }
+}
 
-    unsafe fn suppress_log() {
-        unsafe extern "C" fn noop_callback(
-            _level: whisper_rs::whisper_rs_sys::ggml_log_level,
-            _text: *const ::std::os::raw::c_char,
-            _user_data: *mut ::std::os::raw::c_void,
-        ) {
-        }
-        unsafe { whisper_rs::set_log_callback(Some(noop_callback), std::ptr::null_mut()) };
+// `None` lets Whisper detect the language of each chunk.
+fn resolve_language(
+    language: Option<crate::Language>,
+    detect_language: Option<DetectLanguage>,
+) -> Option<crate::Language> {
+    match detect_language {
+        Some(_) => None,
+        None => Some(language.unwrap_or(crate::Language::En)),
+    }
+}
+
+fn language_param(language: &Option<crate::Language>) -> &str {
+    language.as_ref().map_or("auto", |l| l.as_ref())
+}
+
+fn initial_prompt(static_prompt: &str, dynamic_prompt: &str) -> String {
+    [static_prompt.trim(), dynamic_prompt.trim()]
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+unsafe fn suppress_log() {
+    unsafe extern "C" fn noop_callback(
+        _level: whisper_rs::whisper_rs_sys::ggml_log_level,
+        _text: *const ::std::os::raw::c_char,
+        _user_data: *mut ::std::os::raw::c_void,
+    ) {
     }
+    unsafe { whisper_rs::set_log_callback(Some(noop_callback), std::ptr::null_mut()) };
 }
 
 pub struct Whisper {
-    language: crate::Language,
+    language: Option<crate::Language>,
+    detect_language: Option<DetectLanguage>,
     static_prompt: String,
     dynamic_prompt: String,
+    filter: super::HallucinationFilter,
     state: WhisperState,
     eot: WhisperToken,
 }
@@ -91,18 +178,21 @@ impl Whisper {
         WhisperBuilder::default()
     }
 
+    // Hands the state back so it can be reused by another session without reallocating.
+    pub fn into_state(self) -> WhisperState {
+        self.state
+    }
+
     pub fn transcribe(&mut self, audio: &[f32]) -> Result<Vec<Segment>, super::Error> {
         let params = {
             let mut p = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
 
-            let parts = [self.static_prompt.trim(), self.dynamic_prompt.trim()];
-            let joined = parts.join("\n");
-            let initial_prompt = joined.trim();
+            let initial_prompt = initial_prompt(&self.static_prompt, &self.dynamic_prompt);
 
             tracing::info!(initial_prompt = ?initial_prompt, "transcribe");
 
             p.set_translate(false);
-            p.set_language(Some(self.language.as_ref()));
+            p.set_language(Some(language_param(&self.language)));
             p.set_initial_prompt(&initial_prompt);
 
             p.set_no_timestamps(true);
@@ -126,6 +216,7 @@ impl Whisper {
 
         self.state.full(params, &audio[..])?;
         let num_segments = self.state.full_n_segments()?;
+        let language = self.current_language()?;
 
         let mut segments = Vec::new();
         for i in 0..num_segments {
@@ -135,15 +226,29 @@ impl Whisper {
                 self.state.full_get_segment_t1(i)?,
             );
             let confidence = self.calculate_segment_confidence(i);
+            let avg_logprob = self.calculate_segment_avg_logprob(i);
+            let no_speech_prob = self.state.full_get_segment_no_speech_prob(i)?;
 
             let mut segment = Segment {
                 text,
                 start: start as f32 / 1000.0,
                 end: end as f32 / 1000.0,
                 confidence,
+                avg_logprob,
+                no_speech_prob,
+                language: Some(language),
             };
             segment.trim();
-            segments.push(segment);
+
+            // Filtered before updating `dynamic_prompt`, so hallucinations don't prime the next chunk.
+            if let Some(segment) = self.filter.apply(segment) {
+                segments.push(segment);
+            }
+        }
+
+        if !segments.is_empty() && self.detect_language == Some(DetectLanguage::Once) {
+            tracing::info!(language = ?language, "language_detected");
+            self.language = Some(language);
         }
 
         self.dynamic_prompt = segments
@@ -155,6 +260,27 @@ impl Whisper {
         Ok(segments)
     }
 
+    pub fn language(&self) -> Option<crate::Language> {
+        self.language
+    }
+
+    // Stops detecting, as if `DetectLanguage::Once` had detected `language`.
+    pub fn set_language(&mut self, language: crate::Language) {
+        self.language = Some(language);
+        self.detect_language = None;
+    }
+
+    fn current_language(&self) -> Result<crate::Language, super::Error> {
+        if let Some(language) = self.language {
+            return Ok(language);
+        }
+
+        let id = self.state.full_lang_id_from_state()?;
+        Ok(whisper_rs::get_lang_str(id)
+            .and_then(|code| code.parse().ok())
+            .unwrap_or(crate::Language::En))
+    }
+
     // https://github.com/ggml-org/whisper.cpp/pull/971/files#diff-2d3599a9fad195f2c3c60bd06691bc1815325b3560b5feda41a91fa71194e805R310-R327
     fn calculate_segment_confidence(&self, segment_idx: i32) -> f32 {
         let n_tokens = self.state.full_n_tokens(segment_idx).unwrap_or(0);
@@ -192,6 +318,32 @@ impl Whisper {
 
         total_confidence / valid_tokens as f32
     }
+
+    // https://github.com/openai/whisper/blob/ba3f3cd/whisper/decoding.py#L690
+    fn calculate_segment_avg_logprob(&self, segment_idx: i32) -> f32 {
+        let n_tokens = self.state.full_n_tokens(segment_idx).unwrap_or(0);
+
+        let logprobs = (0..n_tokens)
+            .filter(|&j| {
+                self.state
+                    .full_get_token_id(segment_idx, j)
+                    .is_ok_and(|id| id < self.eot)
+            })
+            .map(|j| {
+                let p = self
+                    .state
+                    .full_get_token_prob(segment_idx, j)
+                    .unwrap_or(0.0);
+                p.max(f32::MIN_POSITIVE).ln()
+            })
+            .collect::<Vec<_>>();
+
+        if logprobs.is_empty() {
+            return f32::NEG_INFINITY;
+        }
+
+        logprobs.iter().sum::<f32>() / logprobs.len() as f32
+    }
 }
 
 // https://github.com/floneum/floneum/blob/52967ae/models/rwhisper/src/lib.rs#L116
@@ -201,6 +353,9 @@ pub struct Segment {
     pub start: f32,
     pub end: f32,
     pub confidence: f32,
+    pub avg_logprob: f32,
+    pub no_speech_prob: f32,
+    pub language: Option<crate::Language>,
 }
 
 impl Segment {
@@ -224,6 +379,24 @@ impl Segment {
         self.confidence
     }
 
+    pub fn avg_logprob(&self) -> f32 {
+        self.avg_logprob
+    }
+
+    pub fn no_speech_prob(&self) -> f32 {
+        self.no_speech_prob
+    }
+
+    pub fn language(&self) -> Option<crate::Language> {
+        self.language
+    }
+
+    // Stops detecting, as if `DetectLanguage::Once` had detected `language`.
+    pub fn set_language(&mut self, language: crate::Language) {
+        self.language = Some(language);
+        self.detect_language = None;
+    }
+
     pub fn trim(&mut self) {
         self.text = TRAILING_DOTS.replace(&self.text, "").to_string();
     }
@@ -15,6 +15,17 @@ pub struct ChunkedTranscriptionTask<S> {
     current_segment_task: Option<Pin<Box<dyn Stream<Item = Segment> + Send>>>,
 }
 
+impl<S> ChunkedTranscriptionTask<S> {
+    pub fn into_whisper(self) -> Whisper {
+        self.whisper
+    }
+
+    // Applies to the chunks after the one being transcribed.
+    pub fn whisper_mut(&mut self) -> &mut Whisper {
+        &mut self.whisper
+    }
+}
+
 pub trait TranscribeChunkedAudioStreamExt<S>: Sized {
     fn transcribe(self, whisper: Whisper) -> ChunkedTranscriptionTask<S>;
 }
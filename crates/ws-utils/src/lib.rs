@@ -1,56 +1,189 @@
-use axum::extract::ws::{Message, WebSocket};
+mod session;
+pub use session::*;
+
+use std::sync::{Arc, Mutex};
+
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
 
 use futures_util::{stream::SplitStream, Stream, StreamExt};
-use hypr_listener_interface::ListenInputChunk;
+use hypr_listener_interface::{AudioEncoding, AudioFormat, ListenFrame};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("malformed frame: {0}")]
+    MalformedFrame(String),
+    #[error("unsupported protocol version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("unsupported audio format: {0:?}")]
+    UnsupportedFormat(AudioFormat),
+    #[error("unknown or expired session: {0}")]
+    UnknownSession(String),
+}
 
+impl ProtocolError {
+    pub fn close_frame(&self) -> CloseFrame {
+        let code = match self {
+            ProtocolError::MalformedFrame(_) => close_code::INVALID,
+            ProtocolError::UnsupportedVersion(_) => close_code::PROTOCOL,
+            ProtocolError::UnsupportedFormat(_) => close_code::UNSUPPORTED,
+            ProtocolError::UnknownSession(_) => close_code::POLICY,
+        };
+
+        CloseFrame {
+            code,
+            reason: self.to_string().into(),
+        }
+    }
+}
+
+// The audio stream can only end, so a protocol error that ends it is left here for the handler to report.
+#[derive(Debug, Clone, Default)]
+struct ProtocolErrorSlot(Arc<Mutex<Option<ProtocolError>>>);
+
+impl ProtocolErrorSlot {
+    fn take(&self) -> Option<ProtocolError> {
+        self.0.lock().unwrap().take()
+    }
+
+    fn set(&self, error: ProtocolError) {
+        tracing::warn!("listen_protocol_error: {}", error);
+        *self.0.lock().unwrap() = Some(error);
+    }
+}
+
+// Decoded mono audio of a listen session. It outlives any single connection, so a resumed client keeps feeding the same stream.
 pub struct WebSocketAudioSource {
-    receiver: Option<SplitStream<WebSocket>>,
+    receiver: mpsc::Receiver<Vec<f32>>,
     sample_rate: u32,
 }
 
 impl WebSocketAudioSource {
-    pub fn new(receiver: SplitStream<WebSocket>, sample_rate: u32) -> Self {
+    fn new(receiver: mpsc::Receiver<Vec<f32>>, sample_rate: u32) -> Self {
         Self {
-            receiver: Some(receiver),
+            receiver,
             sample_rate,
         }
     }
 }
 
-impl kalosm_sound::AsyncSource for WebSocketAudioSource {
-    fn as_stream(&mut self) -> impl Stream<Item = f32> + '_ {
-        let receiver = self.receiver.as_mut().unwrap();
-
-        futures_util::stream::unfold(receiver, |receiver| async move {
-            let item = receiver.next().await;
-
-            match item {
-                Some(Ok(Message::Text(data))) => {
-                    let input: ListenInputChunk = serde_json::from_str(&data).unwrap();
-
-                    match input {
-                        ListenInputChunk::Audio { data } => {
-                            if data.is_empty() {
-                                None
-                            } else {
-                                let samples: Vec<f32> = data
-                                    .chunks_exact(2)
-                                    .map(|chunk| {
-                                        let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
-                                        sample as f32 / 32767.0
-                                    })
-                                    .collect();
-
-                                Some((samples, receiver))
-                            }
-                        }
-                        ListenInputChunk::End => None,
-                    }
+async fn next_frame(
+    receiver: &mut SplitStream<WebSocket>,
+) -> Result<Option<ListenFrame>, ProtocolError> {
+    loop {
+        let msg = match receiver.next().await {
+            Some(Ok(msg)) => msg,
+            Some(Err(e)) => {
+                tracing::warn!("websocket_receive_error: {}", e);
+                return Ok(None);
+            }
+            None => return Ok(None),
+        };
+
+        match msg {
+            Message::Text(text) => {
+                return ListenFrame::from_text(text.as_str())
+                    .map(Some)
+                    .map_err(|e| ProtocolError::MalformedFrame(e.to_string()));
+            }
+            Message::Binary(data) => return Ok(Some(ListenFrame::from_binary(data.to_vec()))),
+            Message::Close(_) => return Ok(None),
+            Message::Ping(_) | Message::Pong(_) => {}
+        }
+    }
+}
+
+// The longest Opus packet is 120ms, here at the highest sample rate.
+const MAX_OPUS_PACKET_SAMPLES: usize = 48 * 120;
+
+// Turns the binary frames of a session into samples, either downmixed to mono or split into one buffer per channel.
+enum AudioDecoder {
+    Linear16,
+    // Carries state from one packet to the next, so packets must be decoded in order.
+    Opus(audiopus::coder::Decoder),
+}
+
+impl AudioDecoder {
+    fn new(format: AudioFormat) -> Result<Self, ProtocolError> {
+        match format.encoding {
+            AudioEncoding::Linear16 => Ok(Self::Linear16),
+            AudioEncoding::Opus => {
+                let sample_rate = audiopus::SampleRate::try_from(format.sample_rate as i32);
+                let channels = match format.channels {
+                    1 => Ok(audiopus::Channels::Mono),
+                    2 => Ok(audiopus::Channels::Stereo),
+                    _ => Err(ProtocolError::UnsupportedFormat(format)),
+                }?;
+
+                sample_rate
+                    .and_then(|sample_rate| audiopus::coder::Decoder::new(sample_rate, channels))
+                    .map(Self::Opus)
+                    .map_err(|_| ProtocolError::UnsupportedFormat(format))
+            }
+        }
+    }
+
+    fn decode(
+        &mut self,
+        data: &[u8],
+        channels: usize,
+        split: bool,
+    ) -> Result<Vec<Vec<f32>>, ProtocolError> {
+        let interleaved = match self {
+            AudioDecoder::Linear16 => {
+                let frame_size = 2 * channels;
+                if data.len() % frame_size != 0 {
+                    return Err(ProtocolError::MalformedFrame(format!(
+                        "{} bytes is not a multiple of the {} byte frame size",
+                        data.len(),
+                        frame_size
+                    )));
                 }
-                Some(Ok(Message::Close(_))) => None,
-                Some(Err(_)) => None,
-                _ => Some((Vec::new(), receiver)),
+
+                data.chunks_exact(2)
+                    .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32767.0)
+                    .collect()
+            }
+            AudioDecoder::Opus(decoder) => {
+                let malformed = |e: audiopus::Error| ProtocolError::MalformedFrame(e.to_string());
+
+                let packet = audiopus::packet::Packet::try_from(data).map_err(malformed)?;
+                let mut output = vec![0.0; MAX_OPUS_PACKET_SAMPLES * channels];
+                let signals = audiopus::MutSignals::try_from(&mut output).map_err(malformed)?;
+                let samples = decoder
+                    .decode_float(Some(packet), signals, false)
+                    .map_err(malformed)?;
+
+                output.truncate(samples * channels);
+                output
             }
+        };
+
+        Ok(deinterleave(&interleaved, channels, split))
+    }
+}
+
+fn deinterleave(samples: &[f32], channels: usize, split: bool) -> Vec<Vec<f32>> {
+    let frames = samples.chunks_exact(channels);
+
+    if !split {
+        let mono = frames.map(|frame| frame.iter().sum::<f32>() / channels as f32);
+        return vec![mono.collect()];
+    }
+
+    let mut buffers = vec![Vec::with_capacity(samples.len() / channels); channels];
+    for frame in frames {
+        for (buffer, sample) in buffers.iter_mut().zip(frame) {
+            buffer.push(*sample);
+        }
+    }
+    buffers
+}
+
+impl kalosm_sound::AsyncSource for WebSocketAudioSource {
+    fn as_stream(&mut self) -> impl Stream<Item = f32> + '_ {
+        futures_util::stream::unfold(&mut self.receiver, |receiver| async move {
+            receiver.recv().await.map(|samples| (samples, receiver))
         })
         .flat_map(futures_util::stream::iter)
     }
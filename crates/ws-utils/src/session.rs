@@ -0,0 +1,750 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use tokio::sync::mpsc;
+
+use hypr_listener_interface::{
+    AudioFormat, ListenFrame, ListenOutputChunk, ListenParams, ListenResume, ListenServerMessage,
+    LISTEN_PROTOCOL_VERSION,
+};
+
+use crate::{next_frame, AudioDecoder, ProtocolError, ProtocolErrorSlot, WebSocketAudioSource};
+
+pub const DEFAULT_RESUME_TIMEOUT: Duration = Duration::from_secs(30);
+
+// v2 clients get an `ack` after every this many audio frames.
+const ACK_INTERVAL: u64 = 10;
+
+// Final transcripts kept for replay on resume. A client that misses more than this loses the oldest ones.
+const MAX_BUFFERED_OUTPUTS: usize = 1000;
+
+// Messages waiting to be written to a client. Fits a full replay, so `attach` never waits.
+// A client that reads slower than that holds back the transcription.
+const CLIENT_QUEUE: usize = MAX_BUFFERED_OUTPUTS + 2;
+
+// Decoded audio frames waiting for the transcriber. When full, the socket is not read
+// until it catches up, which holds back the client instead of growing memory.
+const AUDIO_QUEUE: usize = 64;
+
+// Listen sessions that outlive their websocket connection. A v2 client that drops can reconnect
+// within `resume_timeout`, resend the audio the server never acknowledged, and get the transcripts it missed.
+#[derive(Clone)]
+pub struct ListenSessions {
+    sessions: Arc<Mutex<HashMap<String, Arc<Session>>>>,
+    resume_timeout: Duration,
+}
+
+impl Default for ListenSessions {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESUME_TIMEOUT)
+    }
+}
+
+pub struct ListenSession {
+    // One per channel if `ListenParams::multichannel` is set, otherwise a single mono source.
+    pub sources: Vec<WebSocketAudioSource>,
+    pub sink: ListenSink,
+    // `None` for v1 clients, which pass them in the query string instead.
+    pub params: Option<ListenParams>,
+}
+
+impl ListenSessions {
+    pub fn new(resume_timeout: Duration) -> Self {
+        Self {
+            sessions: Default::default(),
+            resume_timeout,
+        }
+    }
+
+    // Returns a session to transcribe, or `None` if the connection was rejected or resumed an existing one.
+    pub async fn accept(&self, socket: WebSocket) -> Option<ListenSession> {
+        let (sender, mut receiver) = socket.split();
+        let client = spawn_writer(sender);
+
+        let (format, params, resume, pending, ended) = loop {
+            let frame = match next_frame(&mut receiver).await {
+                Ok(frame) => frame,
+                Err(e) => {
+                    let _ = client.try_send(Message::Close(Some(e.close_frame())));
+                    return None;
+                }
+            };
+
+            match frame {
+                Some(ListenFrame::Start {
+                    version,
+                    params,
+                    format,
+                    resume,
+                }) => {
+                    if let Err(e) = validate(version, format) {
+                        let _ = client.try_send(Message::Close(Some(e.close_frame())));
+                        return None;
+                    }
+
+                    break (format, Some(params), resume, None, false);
+                }
+                Some(ListenFrame::Audio(data)) => {
+                    break (AudioFormat::default(), None, None, Some(data), false)
+                }
+                Some(ListenFrame::KeepAlive) => continue,
+                Some(ListenFrame::End) | None => {
+                    break (AudioFormat::default(), None, None, None, true)
+                }
+            }
+        };
+
+        if let Some(resume) = resume {
+            self.resume(resume, client, receiver);
+            return None;
+        }
+
+        let decoder = match AudioDecoder::new(format) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                let _ = client.try_send(Message::Close(Some(e.close_frame())));
+                return None;
+            }
+        };
+
+        let resumable = params.is_some();
+        let multichannel = params.as_ref().is_some_and(|p| p.multichannel);
+        let outputs = if multichannel { format.channels } else { 1 };
+        let (audio_tx, audio_rx): (Vec<_>, Vec<_>) =
+            (0..outputs).map(|_| mpsc::channel(AUDIO_QUEUE)).unzip();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        if resumable {
+            let _ = client.try_send(server_message(&ListenServerMessage::Session {
+                session_id: id.clone(),
+                next_seq: 0,
+            }));
+        }
+
+        let session = Arc::new(Session {
+            id,
+            resumable,
+            channels: format.channels as usize,
+            multichannel,
+            error: ProtocolErrorSlot::default(),
+            state: Mutex::new(SessionState {
+                generation: 0,
+                decoder,
+                audio: Some(audio_tx),
+                received: 0,
+                outputs: VecDeque::new(),
+                dropped: 0,
+                client: Some(client),
+                close: None,
+            }),
+        });
+
+        if resumable {
+            self.sessions
+                .lock()
+                .unwrap()
+                .insert(session.id.clone(), session.clone());
+        }
+
+        if let Some(data) = pending {
+            session.push_audio(0, &data).await;
+        }
+        if ended {
+            session.finish(0);
+        } else {
+            tokio::spawn(self.clone().read(session.clone(), receiver, 0));
+        }
+
+        Some(ListenSession {
+            sources: audio_rx
+                .into_iter()
+                .map(|rx| WebSocketAudioSource::new(rx, format.sample_rate))
+                .collect(),
+            sink: ListenSink {
+                session,
+                sessions: self.clone(),
+            },
+            params,
+        })
+    }
+
+    fn resume(
+        &self,
+        resume: ListenResume,
+        client: mpsc::Sender<Message>,
+        receiver: SplitStream<WebSocket>,
+    ) {
+        let session = self
+            .sessions
+            .lock()
+            .unwrap()
+            .get(&resume.session_id)
+            .cloned();
+        let Some(session) = session else {
+            let e = ProtocolError::UnknownSession(resume.session_id);
+            let _ = client.try_send(Message::Close(Some(e.close_frame())));
+            return;
+        };
+
+        tracing::info!("listen_session_resumed: {}", session.id);
+        let generation = session.attach(client, resume.received);
+        tokio::spawn(self.clone().read(session, receiver, generation));
+    }
+
+    async fn read(
+        self,
+        session: Arc<Session>,
+        mut receiver: SplitStream<WebSocket>,
+        generation: u64,
+    ) {
+        loop {
+            let frame = match next_frame(&mut receiver).await {
+                Ok(Some(frame)) => frame,
+                Ok(None) => break,
+                Err(e) => return session.fail(generation, e),
+            };
+
+            match frame {
+                ListenFrame::Audio(data) => {
+                    if !session.push_audio(generation, &data).await {
+                        return;
+                    }
+                }
+                ListenFrame::KeepAlive => {}
+                // Keep reading, to notice if the client drops before it got everything.
+                ListenFrame::End => session.finish(generation),
+                ListenFrame::Start { .. } => {
+                    let e = ProtocolError::MalformedFrame("unexpected start message".to_string());
+                    return session.fail(generation, e);
+                }
+            }
+        }
+
+        // The connection dropped. Keep the session around in case the client comes back.
+        if session.detach(generation) {
+            tokio::time::sleep(self.resume_timeout).await;
+            session.expire(generation);
+        }
+    }
+
+    fn remove(&self, id: &str) {
+        self.sessions.lock().unwrap().remove(id);
+    }
+}
+
+// Where the transcription of a session goes, whichever connection the client is currently on.
+pub struct ListenSink {
+    session: Arc<Session>,
+    sessions: ListenSessions,
+}
+
+impl ListenSink {
+    pub async fn send(&self, chunk: &ListenOutputChunk) {
+        let msg = Message::Text(serde_json::to_string(chunk).unwrap().into());
+
+        // Buffered and addressed together, so a resume in between can't deliver it twice.
+        let client = {
+            let mut state = self.session.state.lock().unwrap();
+            // Interim chunks are superseded by the final one, so they are not worth replaying.
+            if self.session.resumable && !chunk.interim {
+                state.buffer(msg.clone());
+            }
+            state.client.clone()
+        };
+        self.session.deliver(client, msg).await;
+    }
+
+    // Closes with `frame`, or with the protocol error that ended the audio if there is one.
+    // A detached session is kept for a while so the client can still resume and fetch the rest.
+    pub async fn close(self, frame: Option<CloseFrame>) {
+        let frame = frame.or_else(|| self.session.error.take().map(|e| e.close_frame()));
+        let msg = Message::Close(frame);
+
+        let client = {
+            let mut state = self.session.state.lock().unwrap();
+            state.close = Some(msg.clone());
+            state.client.clone()
+        };
+        self.session.deliver(client, msg).await;
+        let attached = self.session.state.lock().unwrap().client.is_some();
+
+        if attached || !self.session.resumable {
+            self.sessions.remove(&self.session.id);
+            return;
+        }
+
+        tokio::spawn(async move {
+            tokio::time::sleep(self.sessions.resume_timeout).await;
+            self.sessions.remove(&self.session.id);
+        });
+    }
+}
+
+struct Session {
+    id: String,
+    resumable: bool,
+    channels: usize,
+    multichannel: bool,
+    error: ProtocolErrorSlot,
+    state: Mutex<SessionState>,
+}
+
+struct SessionState {
+    // Bumped on every resume, so a stale connection can't touch the session.
+    generation: u64,
+    decoder: AudioDecoder,
+    // `None` once the audio ended.
+    audio: Option<Vec<mpsc::Sender<Vec<f32>>>>,
+    // Audio frames received so far, which is also the next expected sequence number.
+    received: u64,
+    // The latest outputs, for replay. `dropped` older ones were evicted from the front.
+    outputs: VecDeque<Message>,
+    dropped: u64,
+    client: Option<mpsc::Sender<Message>>,
+    close: Option<Message>,
+}
+
+impl SessionState {
+    fn buffer(&mut self, msg: Message) {
+        if self.outputs.len() == MAX_BUFFERED_OUTPUTS {
+            self.outputs.pop_front();
+            self.dropped += 1;
+        }
+        self.outputs.push_back(msg);
+    }
+}
+
+impl Session {
+    // Returns `false` if the connection is stale.
+    async fn push_audio(&self, generation: u64, data: &[u8]) -> bool {
+        let (audio, samples, ack) = {
+            let mut state = self.state.lock().unwrap();
+            if state.generation != generation {
+                return false;
+            }
+
+            // Decoded under the lock, so a stale connection can't feed the Opus decoder out of order.
+            let samples = match state.decoder.decode(data, self.channels, self.multichannel) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    drop(state);
+                    self.fail(generation, e);
+                    return false;
+                }
+            };
+
+            state.received += 1;
+            let ack = (self.resumable && state.received % ACK_INTERVAL == 0).then(|| {
+                let msg = server_message(&ListenServerMessage::Ack {
+                    seq: state.received,
+                });
+                (state.client.clone(), msg)
+            });
+
+            (state.audio.clone(), samples, ack)
+        };
+
+        if let Some(audio) = audio {
+            for (tx, samples) in audio.iter().zip(samples) {
+                let _ = tx.send(samples).await;
+            }
+        }
+        // Only after the audio is queued, so an acknowledged frame is never lost.
+        if let Some((client, msg)) = ack {
+            self.deliver(client, msg).await;
+        }
+
+        true
+    }
+
+    // Waits for room in the client's queue. A client that is gone is detached, if it still is the current one.
+    async fn deliver(&self, client: Option<mpsc::Sender<Message>>, msg: Message) {
+        let Some(client) = client else { return };
+        if client.send(msg).await.is_ok() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state
+            .client
+            .as_ref()
+            .is_some_and(|current| current.same_channel(&client))
+        {
+            state.client = None;
+        }
+    }
+
+    fn finish(&self, generation: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            state.audio = None;
+        }
+    }
+
+    fn fail(&self, generation: u64, error: ProtocolError) {
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            self.error.set(error);
+            state.audio = None;
+        }
+    }
+
+    // Returns `true` if the session now waits for the client to resume.
+    fn detach(&self, generation: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation {
+            return false;
+        }
+
+        state.client = None;
+        if !self.resumable {
+            state.audio = None;
+        }
+
+        self.resumable && state.close.is_none()
+    }
+
+    fn expire(&self, generation: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation && state.client.is_none() {
+            tracing::info!("listen_session_expired: {}", self.id);
+            state.audio = None;
+        }
+    }
+
+    // Replays whatever the client missed, and returns the generation of the new connection.
+    fn attach(&self, client: mpsc::Sender<Message>, received: u64) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+
+        let session = server_message(&ListenServerMessage::Session {
+            session_id: self.id.clone(),
+            next_seq: state.received,
+        });
+        if received < state.dropped {
+            tracing::warn!(
+                lost = state.dropped - received,
+                "listen_session_outputs_lost: {}",
+                self.id
+            );
+        }
+        let missed = state
+            .outputs
+            .iter()
+            .skip(received.saturating_sub(state.dropped) as usize)
+            .cloned();
+
+        for msg in std::iter::once(session)
+            .chain(missed)
+            .chain(state.close.clone())
+        {
+            let _ = client.try_send(msg);
+        }
+
+        state.client = Some(client);
+        state.generation
+    }
+}
+
+fn validate(version: u8, format: AudioFormat) -> Result<(), ProtocolError> {
+    if !(2..=LISTEN_PROTOCOL_VERSION).contains(&version) {
+        return Err(ProtocolError::UnsupportedVersion(version));
+    }
+    if format.channels == 0 || format.sample_rate == 0 {
+        return Err(ProtocolError::UnsupportedFormat(format));
+    }
+
+    Ok(())
+}
+
+fn server_message(msg: &ListenServerMessage) -> Message {
+    Message::Text(serde_json::to_string(msg).unwrap().into())
+}
+
+// Owns the sending half, so sessions can queue messages without awaiting.
+fn spawn_writer(mut sender: SplitSink<WebSocket, Message>) -> mpsc::Sender<Message> {
+    let (tx, mut rx) = mpsc::channel::<Message>(CLIENT_QUEUE);
+
+    tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let close = matches!(msg, Message::Close(_));
+
+            if let Err(e) = sender.send(msg).await {
+                tracing::warn!("websocket_send_error: {}", e);
+                break;
+            }
+            if close {
+                break;
+            }
+        }
+    });
+
+    tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+    use hypr_listener_interface::AudioEncoding;
+
+    fn session() -> Session {
+        Session {
+            id: "test".to_string(),
+            resumable: true,
+            channels: 1,
+            multichannel: false,
+            error: ProtocolErrorSlot::default(),
+            state: Mutex::new(SessionState {
+                generation: 0,
+                decoder: AudioDecoder::Linear16,
+                audio: None,
+                received: 0,
+                outputs: VecDeque::new(),
+                dropped: 0,
+                client: None,
+                close: None,
+            }),
+        }
+    }
+
+    // 20ms Opus packets of a 440Hz tone on the first channel, and silence on the second if there is one.
+    fn opus_packets(sample_rate: u32, channels: u16, count: usize) -> Vec<Vec<u8>> {
+        let encoder = audiopus::coder::Encoder::new(
+            audiopus::SampleRate::try_from(sample_rate as i32).unwrap(),
+            audiopus::Channels::try_from(channels as i32).unwrap(),
+            audiopus::Application::Audio,
+        )
+        .unwrap();
+
+        let frame = sample_rate as usize / 50;
+        (0..count)
+            .map(|i| {
+                let input: Vec<f32> = (0..frame)
+                    .flat_map(|n| {
+                        let t = (i * frame + n) as f32 / sample_rate as f32;
+                        let tone = 0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+                        std::iter::once(tone).chain(std::iter::repeat_n(0.0, channels as usize - 1))
+                    })
+                    .collect();
+
+                let mut output = vec![0; 4000];
+                let len = encoder.encode_float(&input, &mut output).unwrap();
+                output.truncate(len);
+                output
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn opus_session(
+        sample_rate: u32,
+        channels: u16,
+        multichannel: bool,
+    ) -> (Session, Vec<mpsc::Receiver<Vec<f32>>>) {
+        let format = AudioFormat {
+            sample_rate,
+            encoding: AudioEncoding::Opus,
+            channels,
+        };
+        let outputs = if multichannel { channels } else { 1 };
+        let (audio_tx, audio_rx) = (0..outputs).map(|_| mpsc::channel(AUDIO_QUEUE)).unzip();
+
+        let session = session();
+        let session = Session {
+            channels: channels as usize,
+            multichannel,
+            ..session
+        };
+        {
+            let mut state = session.state.lock().unwrap();
+            state.decoder = AudioDecoder::new(format).unwrap();
+            state.audio = Some(audio_tx);
+        }
+
+        (session, audio_rx)
+    }
+
+    fn replay(session: &Session, received: u64) -> Vec<String> {
+        let (tx, mut rx) = mpsc::channel(CLIENT_QUEUE);
+        session.attach(tx, received);
+
+        // Skips the `session` message.
+        rx.try_recv().unwrap();
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|msg| msg.into_text().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_outputs_are_bounded() {
+        let session = session();
+        let total = MAX_BUFFERED_OUTPUTS + 5;
+        for i in 0..total {
+            session
+                .state
+                .lock()
+                .unwrap()
+                .buffer(Message::Text(i.to_string().into()));
+        }
+
+        assert_eq!(
+            session.state.lock().unwrap().outputs.len(),
+            MAX_BUFFERED_OUTPUTS
+        );
+        assert_eq!(replay(&session, (total - 2) as u64), vec!["1003", "1004"]);
+
+        let replayed = replay(&session, 0);
+        assert_eq!(replayed.len(), MAX_BUFFERED_OUTPUTS);
+        assert_eq!(replayed[0], "5");
+    }
+
+    #[tokio::test]
+    async fn test_opus_frames() {
+        let (session, mut rx) = opus_session(16000, 1, false);
+        for packet in opus_packets(16000, 1, 10) {
+            assert!(session.push_audio(0, &packet).await);
+        }
+
+        let decoded: Vec<Vec<f32>> = std::iter::from_fn(|| rx[0].try_recv().ok()).collect();
+        assert_eq!(decoded.len(), 10);
+        assert!(decoded.iter().all(|samples| samples.len() == 320));
+        // Past the decoder's warm-up, the tone comes back at about its RMS of 0.35.
+        assert!(
+            (rms(&decoded[9]) - 0.35).abs() < 0.1,
+            "{}",
+            rms(&decoded[9])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_opus_frames_multichannel() {
+        let (session, mut rx) = opus_session(48000, 2, true);
+        for packet in opus_packets(48000, 2, 10) {
+            assert!(session.push_audio(0, &packet).await);
+        }
+
+        let last = |rx: &mut mpsc::Receiver<Vec<f32>>| {
+            std::iter::from_fn(|| rx.try_recv().ok()).last().unwrap()
+        };
+        let (tone, silence) = (last(&mut rx[0]), last(&mut rx[1]));
+        assert_eq!(tone.len(), 960);
+        assert_eq!(silence.len(), 960);
+        assert!(rms(&tone) > 0.2, "{}", rms(&tone));
+        assert!(rms(&silence) < 0.05, "{}", rms(&silence));
+    }
+
+    #[tokio::test]
+    async fn test_opus_malformed_frame() {
+        let (session, _rx) = opus_session(16000, 1, false);
+
+        assert!(!session.push_audio(0, &[]).await);
+        assert!(matches!(
+            session.error.take(),
+            Some(ProtocolError::MalformedFrame(_))
+        ));
+        assert!(session.state.lock().unwrap().audio.is_none());
+    }
+
+    #[test]
+    fn test_opus_unsupported_format() {
+        for (sample_rate, channels) in [(44100, 1), (16000, 3)] {
+            let format = AudioFormat {
+                sample_rate,
+                encoding: AudioEncoding::Opus,
+                channels,
+            };
+            assert!(matches!(
+                AudioDecoder::new(format),
+                Err(ProtocolError::UnsupportedFormat(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audio_backpressure() {
+        let session = session();
+        let (tx, mut rx) = mpsc::channel(AUDIO_QUEUE);
+        session.state.lock().unwrap().audio = Some(vec![tx]);
+
+        let frame = [0; 320];
+        for _ in 0..AUDIO_QUEUE {
+            assert!(session.push_audio(0, &frame).await);
+        }
+
+        // The transcriber is behind, so the next frame waits for it.
+        let mut next = Box::pin(session.push_audio(0, &frame));
+        assert!((&mut next).now_or_never().is_none());
+
+        rx.recv().await.unwrap();
+        assert!(next.await);
+    }
+
+    #[tokio::test]
+    async fn test_client_backpressure() {
+        let (tx, mut rx) = mpsc::channel(CLIENT_QUEUE);
+        let session = Arc::new(session());
+        session.state.lock().unwrap().client = Some(tx);
+        let sink = ListenSink {
+            session,
+            sessions: ListenSessions::default(),
+        };
+
+        let chunk = ListenOutputChunk {
+            words: vec![],
+            language: None,
+            channel: None,
+            interim: false,
+        };
+        for _ in 0..CLIENT_QUEUE {
+            sink.send(&chunk).await;
+        }
+
+        // The client is behind, so the next transcript waits for it.
+        let mut next = Box::pin(sink.send(&chunk));
+        assert!((&mut next).now_or_never().is_none());
+
+        rx.recv().await.unwrap();
+        next.await;
+    }
+
+    #[tokio::test]
+    async fn test_only_final_outputs_are_replayed() {
+        let sink = ListenSink {
+            session: Arc::new(session()),
+            sessions: ListenSessions::default(),
+        };
+
+        for (text, interim) in [("hel", true), ("hello", false), ("wor", true)] {
+            let word = hypr_listener_interface::Word {
+                text: text.to_string(),
+                speaker: None,
+                confidence: None,
+                start_ms: None,
+                end_ms: None,
+            };
+            let chunk = ListenOutputChunk {
+                words: vec![word],
+                language: None,
+                channel: None,
+                interim,
+            };
+            sink.send(&chunk).await;
+        }
+
+        let replayed = replay(&sink.session, 0);
+        assert_eq!(replayed.len(), 1);
+        assert!(replayed[0].contains("hello"));
+    }
+}
@@ -13,40 +13,71 @@ pub trait WebSocketIO: Send + 'static {
     fn to_input(data: bytes::Bytes) -> Self::Input;
     fn to_message(input: Self::Input) -> Message;
     fn from_message(msg: Message) -> Option<Self::Output>;
+
+    // Sent whenever no audio went out for `KEEP_ALIVE_INTERVAL`.
+    fn keep_alive_message() -> Option<Message> {
+        None
+    }
 }
 
+pub const KEEP_ALIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
 pub struct WebSocketClient {
     request: ClientRequestBuilder,
+    initial_message: Option<Message>,
 }
 
 impl WebSocketClient {
     pub fn new(request: ClientRequestBuilder) -> Self {
-        Self { request }
+        Self {
+            request,
+            initial_message: None,
+        }
+    }
+
+    // Sent right after connecting, before any audio.
+    pub fn with_initial_message(mut self, message: Message) -> Self {
+        self.initial_message = Some(message);
+        self
     }
 
     pub async fn from_audio<T: WebSocketIO>(
         &self,
         mut audio_stream: impl Stream<Item = bytes::Bytes> + Send + Unpin + 'static,
     ) -> Result<impl Stream<Item = T::Output>, crate::Error> {
-        let ws_stream = (|| self.try_connect(self.request.clone()))
-            .retry(
-                ConstantBuilder::default()
-                    .with_max_times(20)
-                    .with_delay(std::time::Duration::from_millis(500)),
-            )
-            .when(|e| {
-                tracing::error!("ws_connect_failed: {:?}", e);
-                true
-            })
-            .sleep(tokio::time::sleep)
-            .await?;
+        let ws_stream = connect(self.request.clone()).await?;
 
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
+        let initial_message = self.initial_message.clone();
+
         let _send_task = tokio::spawn(async move {
-            while let Some(data) = audio_stream.next().await {
-                let input = T::to_input(data);
-                let msg = T::to_message(input);
+            if let Some(msg) = initial_message {
+                if let Err(e) = ws_sender.send(msg).await {
+                    tracing::error!("ws_send_failed: {:?}", e);
+                    return;
+                }
+            }
+
+            let mut keep_alive = tokio::time::interval_at(
+                tokio::time::Instant::now() + KEEP_ALIVE_INTERVAL,
+                KEEP_ALIVE_INTERVAL,
+            );
+
+            loop {
+                let msg = tokio::select! {
+                    data = audio_stream.next() => match data {
+                        Some(data) => {
+                            keep_alive.reset();
+                            T::to_message(T::to_input(data))
+                        }
+                        None => break,
+                    },
+                    _ = keep_alive.tick() => match T::keep_alive_message() {
+                        Some(msg) => msg,
+                        None => continue,
+                    },
+                };
 
                 if let Err(e) = ws_sender.send(msg).await {
                     tracing::error!("ws_send_failed: {:?}", e);
@@ -86,23 +117,34 @@ impl WebSocketClient {
 
         Ok(output_stream)
     }
+}
 
-    async fn try_connect(
-        &self,
-        req: ClientRequestBuilder,
-    ) -> Result<
-        tokio_tungstenite::WebSocketStream<
-            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-        >,
-        crate::Error,
-    > {
-        let req = req.into_client_request().unwrap();
+pub type WebSocketStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+// Retries for about 10 seconds before giving up.
+pub async fn connect(request: ClientRequestBuilder) -> Result<WebSocketStream, crate::Error> {
+    (|| try_connect(request.clone()))
+        .retry(
+            ConstantBuilder::default()
+                .with_max_times(20)
+                .with_delay(std::time::Duration::from_millis(500)),
+        )
+        .when(|e| {
+            tracing::error!("ws_connect_failed: {:?}", e);
+            true
+        })
+        .sleep(tokio::time::sleep)
+        .await
+}
 
-        tracing::info!("connect_async: {:?}", req.uri());
+async fn try_connect(req: ClientRequestBuilder) -> Result<WebSocketStream, crate::Error> {
+    let req = req.into_client_request().unwrap();
 
-        let (ws_stream, _) =
-            tokio::time::timeout(std::time::Duration::from_secs(8), connect_async(req)).await??;
+    tracing::info!("connect_async: {:?}", req.uri());
 
-        Ok(ws_stream)
-    }
+    let (ws_stream, _) =
+        tokio::time::timeout(std::time::Duration::from_secs(8), connect_async(req)).await??;
+
+    Ok(ws_stream)
 }
@@ -153,6 +153,12 @@ async fn _sync_events(
                 start_date: e.start_date,
                 end_date: e.end_date,
                 google_event_url: None,
+                participants: e
+                    .participants
+                    .iter()
+                    .cloned()
+                    .map(hypr_db_user::EventParticipant::from)
+                    .collect(),
             })
             .collect::<Vec<hypr_db_user::Event>>();
 
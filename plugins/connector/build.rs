@@ -9,6 +9,9 @@ const COMMANDS: &[&str] = &[
     "set_custom_llm_connection",
     "get_llm_connection",
     "get_stt_connection",
+    "summarize_transcript",
+    "extract_session",
+    "answer_chat",
 ];
 
 fn main() {
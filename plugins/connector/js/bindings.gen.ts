@@ -36,6 +36,15 @@ async getLlmConnection() : Promise<ConnectionLLM> {
 },
 async getSttConnection() : Promise<ConnectionSTT> {
     return await TAURI_INVOKE("plugin:connector|get_stt_connection");
+},
+async summarizeTranscript(ctx: Partial<{ [key in string]: JsonValue }>) : Promise<string[] | null> {
+    return await TAURI_INVOKE("plugin:connector|summarize_transcript", { ctx });
+},
+async extractSession(ctx: Partial<{ [key in string]: JsonValue }>) : Promise<Extraction> {
+    return await TAURI_INVOKE("plugin:connector|extract_session", { ctx });
+},
+async answerChat(groupId: string, filter: SessionChunkFilter, ctx: Partial<{ [key in string]: JsonValue }>) : Promise<ChatAnswer> {
+    return await TAURI_INVOKE("plugin:connector|answer_chat", { groupId, filter, ctx });
 }
 }
 
@@ -49,9 +58,18 @@ async getSttConnection() : Promise<ConnectionSTT> {
 
 /** user-defined types **/
 
+export type ActionItem = { id: string; session_id: string; owner_id: string | null; text: string; due: string | null; done: boolean; created_at: string }
+export type ChatAnswer = { message: ChatMessage; sources: ChatMessageSource[] }
+export type ChatMessage = { id: string; group_id: string; created_at: string; role: ChatMessageRole; content: string }
+export type ChatMessageRole = "User" | "Assistant"
+export type ChatMessageSource = { id: string; message_id: string; session_id: string; citation: number; text: string; start_ms: number | null; end_ms: number | null }
 export type Connection = { api_base: string; api_key: string | null }
 export type ConnectionLLM = { type: "HyprCloud"; connection: Connection } | { type: "HyprLocal"; connection: Connection } | { type: "Custom"; connection: Connection }
 export type ConnectionSTT = { type: "HyprCloud"; connection: Connection } | { type: "HyprLocal"; connection: Connection }
+export type Decision = { id: string; session_id: string; text: string; created_at: string }
+export type Extraction = { action_items: ActionItem[]; decisions: Decision[] }
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+export type SessionChunkFilter = { user_id: string; participant_id: string | null; tag_id: string | null; start: string | null; end: string | null }
 
 /** tauri-specta globals **/
 
@@ -88,3 +88,36 @@ pub async fn get_stt_connection<R: tauri::Runtime>(
 ) -> Result<ConnectionSTT, String> {
     app.get_stt_connection().await.map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn summarize_transcript<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    ctx: serde_json::Map<String, serde_json::Value>,
+) -> Result<Option<Vec<String>>, String> {
+    app.summarize_transcript(ctx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_session<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    ctx: serde_json::Map<String, serde_json::Value>,
+) -> Result<hypr_summarize::Extraction, String> {
+    app.extract_session(ctx).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn answer_chat<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    group_id: String,
+    filter: hypr_db_user::SessionChunkFilter,
+    ctx: serde_json::Map<String, serde_json::Value>,
+) -> Result<crate::ChatAnswer, String> {
+    app.answer_chat(group_id, filter, ctx)
+        .await
+        .map_err(|e| e.to_string())
+}
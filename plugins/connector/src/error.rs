@@ -5,17 +5,23 @@ pub enum Error {
     #[error(transparent)]
     AuthError(#[from] tauri_plugin_auth::Error),
     #[error(transparent)]
+    DatabaseError(#[from] tauri_plugin_db::Error),
+    #[error(transparent)]
     LocalLlmError(#[from] tauri_plugin_local_llm::Error),
     #[error(transparent)]
     LocalSttError(#[from] tauri_plugin_local_stt::Error),
     #[error(transparent)]
     StoreError(#[from] tauri_plugin_store2::Error),
     #[error(transparent)]
+    SummarizeError(#[from] hypr_summarize::Error),
+    #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
     #[error(transparent)]
     UrlParseError(#[from] url::ParseError),
     #[error("no models found")]
     NoModelsFound,
+    #[error("embedding model not downloaded")]
+    EmbeddingModelNotDownloaded,
     #[error("custom error: {0}")]
     UnknownError(String),
 }
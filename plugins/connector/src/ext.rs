@@ -1,8 +1,16 @@
 use std::future::Future;
 
-use crate::{Connection, ConnectionLLM, ConnectionSTT, StoreKey};
+use crate::{ChatAnswer, Connection, ConnectionLLM, ConnectionSTT, StoreKey};
 use tauri_plugin_store2::StorePluginExt;
 
+// Leaves room for the prompt and the response in the 9k context of the local model.
+const LOCAL_CHUNK_TOKENS: usize = 1024 * 6;
+const CLOUD_CHUNK_TOKENS: usize = 1024 * 32;
+// Excerpts retrieved for each chat question. Small enough for the local model.
+const CHAT_SOURCES: usize = 8;
+// Most recent chat messages kept with each question, alongside the excerpts.
+const CHAT_HISTORY_TOKENS: usize = 1024 * 2;
+
 pub trait ConnectorPluginExt<R: tauri::Runtime> {
     fn connector_store(&self) -> tauri_plugin_store2::ScopedStore<R, crate::StoreKey>;
 
@@ -22,6 +30,23 @@ pub trait ConnectorPluginExt<R: tauri::Runtime> {
 
     fn get_llm_connection(&self) -> impl Future<Output = Result<ConnectionLLM, crate::Error>>;
     fn get_stt_connection(&self) -> impl Future<Output = Result<ConnectionSTT, crate::Error>>;
+
+    fn summarize_transcript(
+        &self,
+        ctx: serde_json::Map<String, serde_json::Value>,
+    ) -> impl Future<Output = Result<Option<Vec<String>>, crate::Error>>;
+
+    fn extract_session(
+        &self,
+        ctx: serde_json::Map<String, serde_json::Value>,
+    ) -> impl Future<Output = Result<hypr_summarize::Extraction, crate::Error>>;
+
+    fn answer_chat(
+        &self,
+        group_id: String,
+        filter: hypr_db_user::SessionChunkFilter,
+        ctx: serde_json::Map<String, serde_json::Value>,
+    ) -> impl Future<Output = Result<ChatAnswer, crate::Error>>;
 }
 
 impl<R: tauri::Runtime, T: tauri::Manager<R>> ConnectorPluginExt<R> for T {
@@ -226,6 +251,150 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> ConnectorPluginExt<R> for T {
             Ok(conn)
         }
     }
+
+    async fn summarize_transcript(
+        &self,
+        ctx: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Option<Vec<String>>, crate::Error> {
+        let summaries = summarizer(self)
+            .await?
+            .summarize(&template_env(), &ctx)
+            .await?;
+        Ok(summaries)
+    }
+
+    async fn extract_session(
+        &self,
+        ctx: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<hypr_summarize::Extraction, crate::Error> {
+        let extraction = summarizer(self)
+            .await?
+            .extract(&template_env(), &ctx)
+            .await?;
+        Ok(extraction)
+    }
+
+    // Takes the context of the chat templates, with the `question`.
+    // Stores the question, the answer, and the sources the answer cites.
+    async fn answer_chat(
+        &self,
+        group_id: String,
+        filter: hypr_db_user::SessionChunkFilter,
+        ctx: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<ChatAnswer, crate::Error> {
+        use tauri_plugin_db::DatabasePluginExt;
+        use tauri_plugin_local_llm::LocalLlmPluginExt;
+
+        let question = ctx
+            .get("question")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        // Excerpts are what the answer is grounded on, so there is nothing to answer from without them.
+        if !self.is_embedding_model_downloaded().await {
+            return Err(crate::Error::EmbeddingModelNotDownloaded);
+        }
+
+        let history = self.db_list_chat_messages(&group_id).await?;
+        let history = recent_history(&history, CHAT_HISTORY_TOKENS);
+
+        let embedding = self.embed_query(question.clone()).await?;
+        let hits = self
+            .db_search_session_chunks(filter, embedding, CHAT_SOURCES)
+            .await?;
+
+        let answer = summarizer(self)
+            .await?
+            .answer(&template_env(), ctx, history, &hits)
+            .await?;
+
+        self.db_upsert_chat_message(chat_message(
+            &group_id,
+            hypr_db_user::ChatMessageRole::User,
+            question,
+        ))
+        .await?;
+
+        let message = self
+            .db_upsert_chat_message(chat_message(
+                &group_id,
+                hypr_db_user::ChatMessageRole::Assistant,
+                answer.content.clone(),
+            ))
+            .await?;
+
+        let sources = answer.sources(&message.id, &hits);
+        self.db_insert_chat_message_sources(sources.clone()).await?;
+
+        Ok(ChatAnswer { message, sources })
+    }
+}
+
+fn chat_message(
+    group_id: &str,
+    role: hypr_db_user::ChatMessageRole,
+    content: String,
+) -> hypr_db_user::ChatMessage {
+    hypr_db_user::ChatMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        group_id: group_id.to_string(),
+        created_at: chrono::Utc::now(),
+        role,
+        content,
+    }
+}
+
+// The latest messages that fit in `max_tokens`, oldest first.
+fn recent_history(
+    history: &[hypr_db_user::ChatMessage],
+    max_tokens: usize,
+) -> &[hypr_db_user::ChatMessage] {
+    let mut tokens = 0;
+    let start = history
+        .iter()
+        .rposition(|message| {
+            tokens += hypr_summarize::estimate_tokens(&message.content);
+            tokens > max_tokens
+        })
+        .map_or(0, |i| i + 1);
+
+    &history[start..]
+}
+
+async fn summarizer<R: tauri::Runtime>(
+    app: &impl ConnectorPluginExt<R>,
+) -> Result<hypr_summarize::Summarizer, crate::Error> {
+    let conn = app.get_llm_connection().await?;
+
+    let (chunk_tokens, model) = match &conn {
+        ConnectionLLM::HyprCloud(_) => (CLOUD_CHUNK_TOKENS, "gpt-4".to_string()),
+        ConnectionLLM::HyprLocal(_) => (LOCAL_CHUNK_TOKENS, "gpt-4".to_string()),
+        // Context size of custom endpoints is unknown, so assume a small one.
+        ConnectionLLM::Custom(_) => (
+            LOCAL_CHUNK_TOKENS,
+            app.get_custom_llm_model()?
+                .unwrap_or_else(|| "gpt-4".to_string()),
+        ),
+    };
+
+    let Connection { api_base, api_key } = conn.into();
+
+    let mut builder = hypr_summarize::Summarizer::builder()
+        .api_base(api_base)
+        .model(model)
+        .chunk_tokens(chunk_tokens);
+    if let Some(api_key) = api_key {
+        builder = builder.api_key(api_key);
+    }
+
+    Ok(builder.build())
+}
+
+fn template_env() -> hypr_template::minijinja::Environment<'static> {
+    let mut env = hypr_template::minijinja::Environment::new();
+    hypr_template::init(&mut env);
+    env
 }
 
 trait OpenaiCompatible {
@@ -276,3 +445,29 @@ impl OpenaiCompatible for ConnectionLLM {
         Ok(models)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_history() {
+        let history = ["a".repeat(40), "b".repeat(40), "c".repeat(40)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, content)| {
+                let role = if i % 2 == 0 {
+                    hypr_db_user::ChatMessageRole::User
+                } else {
+                    hypr_db_user::ChatMessageRole::Assistant
+                };
+                chat_message("group", role, content)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(recent_history(&history, 1000).len(), 3);
+        assert_eq!(recent_history(&history, 25).len(), 2);
+        assert_eq!(recent_history(&history, 25)[0].content, "b".repeat(40));
+        assert!(recent_history(&history, 5).is_empty());
+    }
+}
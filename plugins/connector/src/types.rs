@@ -4,6 +4,13 @@ pub struct Connection {
     pub api_key: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize, serde::Serialize, specta::Type)]
+pub struct ChatAnswer {
+    pub message: hypr_db_user::ChatMessage,
+    // Only the sources cited in the message.
+    pub sources: Vec<hypr_db_user::ChatMessageSource>,
+}
+
 #[derive(Debug, serde::Deserialize, serde::Serialize, specta::Type)]
 #[serde(tag = "type", content = "connection")]
 pub enum ConnectionLLM {
@@ -16,7 +16,14 @@ const COMMANDS: &[&str] = &[
     "session_remove_participant",
     "session_list_participants",
     "session_get_event",
+    "session_rename_speaker",
+    "session_merge_speakers",
+    "session_split_speaker",
+    "session_reassign_words",
     "get_words_onboarding",
+    "split_session_chunks",
+    "replace_session_chunks",
+    "search_sessions",
     "get_words",
     // template
     "list_templates",
@@ -44,11 +51,24 @@ const COMMANDS: &[&str] = &[
     "list_chat_messages",
     "create_chat_group",
     "upsert_chat_message",
+    "list_chat_message_sources",
     // tag
     "list_all_tags",
     "list_session_tags",
     "assign_tag_to_session",
     "unassign_tag_from_session",
+    // voice profile
+    "list_voice_profiles",
+    "delete_voice_profile",
+    "list_speaker_suggestions",
+    "accept_speaker_suggestion",
+    "reject_speaker_suggestion",
+    // action item
+    "list_action_items",
+    "upsert_action_item",
+    "delete_action_item",
+    "list_decisions",
+    "replace_session_extraction",
 ];
 
 fn main() {
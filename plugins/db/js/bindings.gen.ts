@@ -67,12 +67,33 @@ async sessionListParticipants(sessionId: string) : Promise<Human[]> {
 async sessionGetEvent(sessionId: string) : Promise<Event | null> {
     return await TAURI_INVOKE("plugin:db|session_get_event", { sessionId });
 },
+async sessionRenameSpeaker(sessionId: string, index: number, humanId: string) : Promise<Session | null> {
+    return await TAURI_INVOKE("plugin:db|session_rename_speaker", { sessionId, index, humanId });
+},
+async sessionMergeSpeakers(sessionId: string, from: number, into: number) : Promise<Session | null> {
+    return await TAURI_INVOKE("plugin:db|session_merge_speakers", { sessionId, from, into });
+},
+async sessionSplitSpeaker(sessionId: string, index: number, fromMs: number) : Promise<Session | null> {
+    return await TAURI_INVOKE("plugin:db|session_split_speaker", { sessionId, index, fromMs });
+},
+async sessionReassignWords(sessionId: string, start: number, end: number, humanId: string) : Promise<Session | null> {
+    return await TAURI_INVOKE("plugin:db|session_reassign_words", { sessionId, start, end, humanId });
+},
 async getWords(sessionId: string) : Promise<Word[]> {
     return await TAURI_INVOKE("plugin:db|get_words", { sessionId });
 },
 async getWordsOnboarding() : Promise<Word[]> {
     return await TAURI_INVOKE("plugin:db|get_words_onboarding");
 },
+async splitSessionChunks(sessionId: string) : Promise<SessionChunk[]> {
+    return await TAURI_INVOKE("plugin:db|split_session_chunks", { sessionId });
+},
+async replaceSessionChunks(sessionId: string, chunks: SessionChunk[]) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|replace_session_chunks", { sessionId, chunks });
+},
+async searchSessions(userId: string, embedding: number[], limit: number) : Promise<SessionSearchHit[]> {
+    return await TAURI_INVOKE("plugin:db|search_sessions", { userId, embedding, limit });
+},
 async getConfig() : Promise<Config> {
     return await TAURI_INVOKE("plugin:db|get_config");
 },
@@ -115,6 +136,9 @@ async createChatGroup(group: ChatGroup) : Promise<ChatGroup> {
 async upsertChatMessage(message: ChatMessage) : Promise<ChatMessage> {
     return await TAURI_INVOKE("plugin:db|upsert_chat_message", { message });
 },
+async listChatMessageSources(messageId: string) : Promise<ChatMessageSource[]> {
+    return await TAURI_INVOKE("plugin:db|list_chat_message_sources", { messageId });
+},
 async listAllTags() : Promise<Tag[]> {
     return await TAURI_INVOKE("plugin:db|list_all_tags");
 },
@@ -126,6 +150,36 @@ async assignTagToSession(tagId: string, sessionId: string) : Promise<null> {
 },
 async unassignTagFromSession(tagId: string, sessionId: string) : Promise<null> {
     return await TAURI_INVOKE("plugin:db|unassign_tag_from_session", { tagId, sessionId });
+},
+async listVoiceProfiles(humanId: string | null) : Promise<VoiceProfile[]> {
+    return await TAURI_INVOKE("plugin:db|list_voice_profiles", { humanId });
+},
+async deleteVoiceProfile(id: string) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|delete_voice_profile", { id });
+},
+async listSpeakerSuggestions(sessionId: string) : Promise<SpeakerSuggestion[]> {
+    return await TAURI_INVOKE("plugin:db|list_speaker_suggestions", { sessionId });
+},
+async acceptSpeakerSuggestion(id: string) : Promise<Session | null> {
+    return await TAURI_INVOKE("plugin:db|accept_speaker_suggestion", { id });
+},
+async rejectSpeakerSuggestion(id: string) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|reject_speaker_suggestion", { id });
+},
+async listActionItems(filter: ListActionItemFilter) : Promise<ActionItem[]> {
+    return await TAURI_INVOKE("plugin:db|list_action_items", { filter });
+},
+async upsertActionItem(item: ActionItem) : Promise<ActionItem> {
+    return await TAURI_INVOKE("plugin:db|upsert_action_item", { item });
+},
+async deleteActionItem(id: string) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|delete_action_item", { id });
+},
+async listDecisions(sessionId: string) : Promise<Decision[]> {
+    return await TAURI_INVOKE("plugin:db|list_decisions", { sessionId });
+},
+async replaceSessionExtraction(sessionId: string, actionItems: ActionItem[], decisions: Decision[]) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|replace_session_extraction", { sessionId, actionItems, decisions });
 }
 }
 
@@ -139,28 +193,39 @@ async unassignTagFromSession(tagId: string, sessionId: string) : Promise<null> {
 
 /** user-defined types **/
 
+export type ActionItem = { id: string; session_id: string; owner_id: string | null; text: string; due: string | null; done: boolean; created_at: string }
 export type Calendar = { id: string; tracking_id: string; user_id: string; platform: Platform; name: string; selected: boolean; source: string | null }
 export type ChatGroup = { id: string; user_id: string; name: string | null; created_at: string }
 export type ChatMessage = { id: string; group_id: string; created_at: string; role: ChatMessageRole; content: string }
 export type ChatMessageRole = "User" | "Assistant"
+export type ChatMessageSource = { id: string; message_id: string; session_id: string; citation: number; text: string; start_ms: number | null; end_ms: number | null }
 export type Config = { id: string; user_id: string; general: ConfigGeneral; notification: ConfigNotification; ai: ConfigAI }
 export type ConfigAI = { api_base: string | null; api_key: string | null }
-export type ConfigGeneral = { autostart: boolean; display_language: string; jargons: string[]; telemetry_consent: boolean; save_recordings: boolean | null }
+export type ConfigGeneral = { autostart: boolean; display_language: string; jargons: string[]; telemetry_consent: boolean; save_recordings: boolean | null; language_detection: LanguageDetection | null; multichannel: boolean | null }
 export type ConfigNotification = { before: boolean; auto: boolean; ignoredPlatforms: string[] | null }
-export type Event = { id: string; user_id: string; tracking_id: string; calendar_id: string | null; name: string; note: string; start_date: string; end_date: string; google_event_url: string | null }
+export type Event = { id: string; user_id: string; tracking_id: string; calendar_id: string | null; name: string; note: string; start_date: string; end_date: string; google_event_url: string | null; participants: EventParticipant[] }
+export type Decision = { id: string; session_id: string; text: string; created_at: string }
+export type EventParticipant = { name: string; email: string | null }
 export type GetSessionFilter = { id: string } | { calendarEventId: string } | { tagId: string }
 export type Human = { id: string; organization_id: string | null; is_user: boolean; full_name: string | null; email: string | null; job_title: string | null; linkedin_username: string | null }
+export type LanguageDetection = "off" | "first_chunk" | "per_chunk"
+export type ListActionItemFilter = { session: { session_id: string } } | { open: { user_id: string; owner_id: string | null } }
 export type ListEventFilter = ({ user_id: string; limit: number | null }) & ({ type: "simple" } | { type: "search"; query: string } | { type: "dateRange"; start: string; end: string } | { type: "not-assigned-past" })
 export type ListHumanFilter = { search: [number, string] }
 export type ListOrganizationFilter = { search: [number, string] }
-export type ListSessionFilter = ({ user_id: string; limit: number | null }) & ({ type: "search"; query: string } | { type: "recentlyVisited" } | { type: "dateRange"; start: string; end: string })
+export type ListSessionFilter = ({ user_id: string; limit: number | null }) & ({ type: "search"; query: string } | { type: "recentlyVisited" } | { type: "dateRange"; start: string; end: string } | { type: "semantic"; query: string })
 export type Organization = { id: string; name: string; description: string | null }
 export type Platform = "Apple" | "Google" | "Outlook"
-export type Session = { id: string; created_at: string; visited_at: string; user_id: string; calendar_event_id: string | null; title: string; raw_memo_html: string; enhanced_memo_html: string | null; words: Word[]; record_start: string | null; record_end: string | null }
+export type Session = { id: string; created_at: string; visited_at: string; user_id: string; calendar_event_id: string | null; title: string; raw_memo_html: string; enhanced_memo_html: string | null; words: Word[]; record_start: string | null; record_end: string | null; language: string | null }
+export type SessionChunk = { id: string; session_id: string; kind: SessionChunkKind; text: string; embedding: number[]; start_ms: number | null; end_ms: number | null }
+export type SessionChunkKind = "transcript" | "note"
+export type SessionSearchHit = { session: Session; snippet: string; score: number }
 export type SpeakerIdentity = { type: "unassigned"; value: { index: number } } | { type: "assigned"; value: { id: string; label: string } }
+export type SpeakerSuggestion = { id: string; session_id: string; speaker_index: number; human_id: string; similarity: number; embedding: number[] }
 export type Tag = { id: string; name: string }
 export type Template = { id: string; user_id: string; title: string; description: string; sections: TemplateSection[]; tags: string[] }
 export type TemplateSection = { title: string; description: string }
+export type VoiceProfile = { id: string; human_id: string; created_at: string; embedding: number[] }
 export type Word = { text: string; speaker: SpeakerIdentity | null; confidence: number | null; start_ms: number | null; end_ms: number | null }
 
 /** tauri-specta globals **/
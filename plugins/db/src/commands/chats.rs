@@ -75,3 +75,23 @@ pub async fn upsert_chat_message(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn list_chat_message_sources(
+    state: tauri::State<'_, crate::ManagedState>,
+    message_id: String,
+) -> Result<Vec<hypr_db_user::ChatMessageSource>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.list_chat_message_sources(message_id)
+        .await
+        .map_err(|e| e.to_string())
+}
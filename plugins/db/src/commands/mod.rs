@@ -1,3 +1,4 @@
+pub mod action_items;
 pub mod calendars;
 pub mod chats;
 pub mod configs;
@@ -7,3 +8,4 @@ pub mod organizations;
 pub mod sessions;
 pub mod tags;
 pub mod templates;
+pub mod voice_profiles;
@@ -1,3 +1,5 @@
+use tauri_plugin_local_llm::LocalLlmPluginExt;
+
 #[tauri::command]
 #[specta::specta]
 #[tracing::instrument(skip(state))]
@@ -73,11 +75,36 @@ pub async fn upsert_session(
 
 #[tauri::command]
 #[specta::specta]
-#[tracing::instrument(skip(state))]
-pub async fn list_sessions(
+#[tracing::instrument(skip(app, state))]
+pub async fn list_sessions<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
     state: tauri::State<'_, crate::ManagedState>,
     filter: Option<hypr_db_user::ListSessionFilter>,
 ) -> Result<Vec<hypr_db_user::Session>, String> {
+    if let Some(hypr_db_user::ListSessionFilter {
+        common: hypr_db_user::ListSessionFilterCommon { user_id, limit },
+        specific: hypr_db_user::ListSessionFilterSpecific::Semantic { query },
+    }) = &filter
+    {
+        let embedding = app
+            .embed_query(query.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let guard = state.lock().await;
+        let db = guard
+            .db
+            .as_ref()
+            .ok_or(crate::Error::NoneDatabase)
+            .map_err(|e| e.to_string())?;
+
+        let hits = db
+            .search_sessions(user_id, &embedding, limit.unwrap_or(100) as usize)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(hits.into_iter().map(|hit| hit.session).collect());
+    }
+
     let guard = state.lock().await;
 
     let db = guard
@@ -245,3 +272,160 @@ pub async fn session_get_event(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_rename_speaker(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    index: u8,
+    human_id: String,
+) -> Result<Option<hypr_db_user::Session>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_rename_speaker(session_id, index, human_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_merge_speakers(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    from: u8,
+    into: u8,
+) -> Result<Option<hypr_db_user::Session>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_merge_speakers(session_id, from, into)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_split_speaker(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    index: u8,
+    from_ms: u64,
+) -> Result<Option<hypr_db_user::Session>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_split_speaker(session_id, index, from_ms)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_reassign_words(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    start: usize,
+    end: usize,
+    human_id: String,
+) -> Result<Option<hypr_db_user::Session>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_reassign_words(session_id, start, end, human_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn split_session_chunks(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+) -> Result<Vec<hypr_db_user::SessionChunk>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    let session = db
+        .get_session(hypr_db_user::GetSessionFilter::Id(session_id))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(session
+        .map(|session| hypr_db_user::SessionChunk::split(&session))
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state, chunks))]
+pub async fn replace_session_chunks(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    chunks: Vec<hypr_db_user::SessionChunk>,
+) -> Result<(), String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.replace_session_chunks(session_id, chunks)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state, embedding))]
+pub async fn search_sessions(
+    state: tauri::State<'_, crate::ManagedState>,
+    user_id: String,
+    embedding: Vec<f32>,
+    limit: u8,
+) -> Result<Vec<hypr_db_user::SessionSearchHit>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.search_sessions(user_id, &embedding, limit as usize)
+        .await
+        .map_err(|e| e.to_string())
+}
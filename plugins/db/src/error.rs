@@ -13,6 +13,8 @@ pub enum Error {
     #[error(transparent)]
     DatabaseCoreError(#[from] hypr_db_core::Error),
     #[error(transparent)]
+    DatabaseUserError(#[from] hypr_db_user::Error),
+    #[error(transparent)]
     IoError(#[from] std::io::Error),
 }
 
@@ -25,6 +25,54 @@ pub trait DatabasePluginExt<R: tauri::Runtime> {
         &self,
         session: hypr_db_user::Session,
     ) -> impl Future<Output = Result<(), crate::Error>>;
+    fn db_update_session_transcript(
+        &self,
+        session_id: impl Into<String>,
+        language: Option<hypr_language::Language>,
+        f: impl FnOnce(&mut Vec<hypr_listener_interface::Word>) + Send,
+    ) -> impl Future<Output = Result<Option<hypr_db_user::Session>, crate::Error>>;
+    fn db_get_event(
+        &self,
+        event_id: impl Into<String>,
+    ) -> impl Future<Output = Result<Option<hypr_db_user::Event>, crate::Error>>;
+    fn db_get_human(
+        &self,
+        human_id: impl Into<String>,
+    ) -> impl Future<Output = Result<Option<hypr_db_user::Human>, crate::Error>>;
+    fn db_list_voice_profiles(
+        &self,
+        human_id: Option<String>,
+    ) -> impl Future<Output = Result<Vec<hypr_db_user::VoiceProfile>, crate::Error>>;
+    fn db_upsert_voice_profile(
+        &self,
+        profile: hypr_db_user::VoiceProfile,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
+    fn db_upsert_speaker_suggestion(
+        &self,
+        suggestion: hypr_db_user::SpeakerSuggestion,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
+    fn db_clear_speaker_suggestions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
+    fn db_search_session_chunks(
+        &self,
+        filter: hypr_db_user::SessionChunkFilter,
+        embedding: Vec<f32>,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<hypr_db_user::SessionChunkHit>, crate::Error>>;
+    fn db_list_chat_messages(
+        &self,
+        group_id: impl Into<String>,
+    ) -> impl Future<Output = Result<Vec<hypr_db_user::ChatMessage>, crate::Error>>;
+    fn db_upsert_chat_message(
+        &self,
+        message: hypr_db_user::ChatMessage,
+    ) -> impl Future<Output = Result<hypr_db_user::ChatMessage, crate::Error>>;
+    fn db_insert_chat_message_sources(
+        &self,
+        sources: Vec<hypr_db_user::ChatMessageSource>,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
 }
 
 impl<R: tauri::Runtime, T: tauri::Manager<R>> DatabasePluginExt<R> for T {
@@ -120,6 +168,46 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> DatabasePluginExt<R> for T {
         Ok(())
     }
 
+    async fn db_update_session_transcript(
+        &self,
+        session_id: impl Into<String>,
+        language: Option<hypr_language::Language>,
+        f: impl FnOnce(&mut Vec<hypr_listener_interface::Word>) + Send,
+    ) -> Result<Option<hypr_db_user::Session>, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let session = db
+            .session_update_transcript(session_id, language, f)
+            .await?;
+        Ok(session)
+    }
+
+    async fn db_get_event(
+        &self,
+        event_id: impl Into<String>,
+    ) -> Result<Option<hypr_db_user::Event>, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let event = db.get_event(event_id).await?;
+        Ok(event)
+    }
+
+    async fn db_get_human(
+        &self,
+        human_id: impl Into<String>,
+    ) -> Result<Option<hypr_db_user::Human>, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let human = db.get_human(human_id).await?;
+        Ok(human)
+    }
+
     async fn db_get_config(
         &self,
         user_id: impl Into<String>,
@@ -131,4 +219,101 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> DatabasePluginExt<R> for T {
         let config = db.get_config(user_id.into()).await?;
         Ok(config)
     }
+
+    async fn db_list_voice_profiles(
+        &self,
+        human_id: Option<String>,
+    ) -> Result<Vec<hypr_db_user::VoiceProfile>, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let profiles = db.list_voice_profiles(human_id).await?;
+        Ok(profiles)
+    }
+
+    async fn db_upsert_voice_profile(
+        &self,
+        profile: hypr_db_user::VoiceProfile,
+    ) -> Result<(), crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        db.upsert_voice_profile(profile).await?;
+        Ok(())
+    }
+
+    async fn db_upsert_speaker_suggestion(
+        &self,
+        suggestion: hypr_db_user::SpeakerSuggestion,
+    ) -> Result<(), crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        db.upsert_speaker_suggestion(suggestion).await?;
+        Ok(())
+    }
+
+    async fn db_clear_speaker_suggestions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<(), crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        db.clear_speaker_suggestions(session_id).await?;
+        Ok(())
+    }
+    async fn db_search_session_chunks(
+        &self,
+        filter: hypr_db_user::SessionChunkFilter,
+        embedding: Vec<f32>,
+        limit: usize,
+    ) -> Result<Vec<hypr_db_user::SessionChunkHit>, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let hits = db.search_session_chunks(filter, &embedding, limit).await?;
+        Ok(hits)
+    }
+
+    async fn db_list_chat_messages(
+        &self,
+        group_id: impl Into<String>,
+    ) -> Result<Vec<hypr_db_user::ChatMessage>, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let messages = db.list_chat_messages(group_id).await?;
+        Ok(messages)
+    }
+
+    async fn db_upsert_chat_message(
+        &self,
+        message: hypr_db_user::ChatMessage,
+    ) -> Result<hypr_db_user::ChatMessage, crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        let message = db.upsert_chat_message(message).await?;
+        Ok(message)
+    }
+
+    async fn db_insert_chat_message_sources(
+        &self,
+        sources: Vec<hypr_db_user::ChatMessageSource>,
+    ) -> Result<(), crate::Error> {
+        let state = self.state::<crate::ManagedState>();
+        let guard = state.lock().await;
+
+        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
+        db.insert_chat_message_sources(sources).await?;
+        Ok(())
+    }
 }
@@ -34,7 +34,7 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::templates::upsert_template,
             commands::templates::delete_template,
             commands::sessions::onboarding_session_id,
-            commands::sessions::list_sessions,
+            commands::sessions::list_sessions::<tauri::Wry>,
             commands::sessions::delete_session,
             commands::sessions::get_session,
             commands::sessions::set_session_event,
@@ -42,8 +42,15 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::sessions::session_remove_participant,
             commands::sessions::session_list_participants,
             commands::sessions::session_get_event,
+            commands::sessions::session_rename_speaker,
+            commands::sessions::session_merge_speakers,
+            commands::sessions::session_split_speaker,
+            commands::sessions::session_reassign_words,
             commands::sessions::get_words,
             commands::sessions::get_words_onboarding,
+            commands::sessions::split_session_chunks,
+            commands::sessions::replace_session_chunks,
+            commands::sessions::search_sessions,
             commands::configs::get_config,
             commands::configs::set_config,
             commands::humans::get_human,
@@ -58,10 +65,21 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::chats::list_chat_messages,
             commands::chats::create_chat_group,
             commands::chats::upsert_chat_message,
+            commands::chats::list_chat_message_sources,
             commands::tags::list_all_tags,
             commands::tags::list_session_tags,
             commands::tags::assign_tag_to_session,
             commands::tags::unassign_tag_from_session,
+            commands::voice_profiles::list_voice_profiles,
+            commands::voice_profiles::delete_voice_profile,
+            commands::voice_profiles::list_speaker_suggestions,
+            commands::voice_profiles::accept_speaker_suggestion,
+            commands::voice_profiles::reject_speaker_suggestion,
+            commands::action_items::list_action_items,
+            commands::action_items::upsert_action_item,
+            commands::action_items::delete_action_item,
+            commands::action_items::list_decisions,
+            commands::action_items::replace_session_extraction,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
 }
@@ -38,6 +38,38 @@ common_derives! {
 common_derives! {
     pub struct ListenOutputChunk {
         pub words: Vec<Word>,
+        #[specta(type = Option<String>)]
+        #[schemars(with = "Option<String>")]
+        #[serde(
+            default,
+            serialize_with = "serialize_language_opt",
+            deserialize_with = "deserialize_language_opt"
+        )]
+        pub language: Option<hypr_language::Language>,
+        // Which channel the words came from, when `ListenParams::multichannel` is set.
+        #[serde(default)]
+        pub channel: Option<u8>,
+        // A hypothesis the backend may still revise. The next chunk replaces it rather than adding to it.
+        #[serde(default)]
+        pub interim: bool,
+    }
+}
+
+// Channel layout of multichannel listen audio.
+pub const MIC_CHANNEL: u8 = 0;
+pub const SPEAKER_CHANNEL: u8 = 1;
+
+// Inserts words in time order, e.g. ones from another channel. Words without a timestamp go last.
+pub fn merge_words(words: &mut Vec<Word>, incoming: impl IntoIterator<Item = Word>) {
+    for word in incoming {
+        let at = match word.start_ms {
+            Some(start) => words
+                .iter()
+                .rposition(|w| w.start_ms.is_none_or(|s| s <= start))
+                .map_or(0, |i| i + 1),
+            None => words.len(),
+        };
+        words.insert(at, word);
     }
 }
 
@@ -60,6 +92,231 @@ impl Default for ListenInputChunk {
     }
 }
 
+pub const LISTEN_PROTOCOL_VERSION: u8 = 2;
+
+common_derives! {
+    #[derive(Default, Copy, Eq)]
+    pub enum AudioEncoding {
+        #[default]
+        #[serde(rename = "linear16")]
+        Linear16,
+        // One packet per binary frame, without an Ogg container.
+        #[serde(rename = "opus")]
+        Opus,
+    }
+}
+
+common_derives! {
+    #[derive(Copy)]
+    pub struct AudioFormat {
+        pub sample_rate: u32,
+        pub encoding: AudioEncoding,
+        pub channels: u16,
+    }
+}
+
+// What v1 clients always sent.
+impl Default for AudioFormat {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16 * 1000,
+            encoding: AudioEncoding::Linear16,
+            channels: 1,
+        }
+    }
+}
+
+// Text frames of protocol v2. Audio itself is sent as binary frames in between `Start` and `End`.
+common_derives! {
+    #[serde(tag = "type")]
+    pub enum ListenControlMessage {
+        #[serde(rename = "start")]
+        Start {
+            version: u8,
+            params: ListenParams,
+            #[serde(default)]
+            format: AudioFormat,
+            #[serde(default)]
+            resume: Option<ListenResume>,
+        },
+        #[serde(rename = "keepalive")]
+        KeepAlive,
+        #[serde(rename = "end")]
+        End,
+    }
+}
+
+common_derives! {
+    pub struct ListenResume {
+        pub session_id: String,
+        // Number of final `ListenOutputChunk`s the client already got, so the server only replays the rest.
+        // Interim ones are never replayed.
+        pub received: u64,
+    }
+}
+
+// Text frames the server sends to v2 clients, next to `ListenOutputChunk`s.
+// Audio frames are numbered implicitly from 0, in the order they were sent.
+common_derives! {
+    #[serde(tag = "type")]
+    pub enum ListenServerMessage {
+        // Reply to `Start`. The client resends unacknowledged audio from `next_seq`.
+        #[serde(rename = "session")]
+        Session { session_id: String, next_seq: u64 },
+        // Every audio frame before `seq` was received.
+        #[serde(rename = "ack")]
+        Ack { seq: u64 },
+    }
+}
+
+// A client frame of either protocol version, as seen by the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListenFrame {
+    Start {
+        version: u8,
+        params: ListenParams,
+        format: AudioFormat,
+        resume: Option<ListenResume>,
+    },
+    Audio(Vec<u8>),
+    KeepAlive,
+    End,
+}
+
+impl ListenFrame {
+    pub fn from_text(text: &str) -> Result<Self, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(text)?;
+
+        // Only v1 sends audio as text.
+        if value.get("type").and_then(|t| t.as_str()) == Some("audio") {
+            return match serde_json::from_value(value)? {
+                ListenInputChunk::Audio { data } => Ok(ListenFrame::Audio(data)),
+                ListenInputChunk::End => Ok(ListenFrame::End),
+            };
+        }
+
+        Ok(match serde_json::from_value(value)? {
+            ListenControlMessage::Start {
+                version,
+                params,
+                format,
+                resume,
+            } => ListenFrame::Start {
+                version,
+                params,
+                format,
+                resume,
+            },
+            ListenControlMessage::KeepAlive => ListenFrame::KeepAlive,
+            ListenControlMessage::End => ListenFrame::End,
+        })
+    }
+
+    pub fn from_binary(data: impl Into<Vec<u8>>) -> Self {
+        ListenFrame::Audio(data.into())
+    }
+}
+
+common_derives! {
+    #[derive(Default, Copy, Eq)]
+    pub enum LanguageDetection {
+        #[default]
+        #[serde(rename = "off")]
+        Off,
+        #[serde(rename = "first_chunk")]
+        FirstChunk,
+        #[serde(rename = "per_chunk")]
+        PerChunk,
+    }
+}
+
+impl LanguageDetection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LanguageDetection::Off => "off",
+            LanguageDetection::FirstChunk => "first_chunk",
+            LanguageDetection::PerChunk => "per_chunk",
+        }
+    }
+}
+
+common_derives! {
+    pub struct VocabularyTerm {
+        pub text: String,
+        // Relative weight. `None` leaves it to the backend's default.
+        #[serde(default)]
+        pub boost: Option<f32>,
+        // Alternative spellings of how the term sounds, e.g. "hyper note".
+        #[serde(default)]
+        pub pronunciations: Vec<String>,
+    }
+}
+
+impl VocabularyTerm {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            boost: None,
+            pronunciations: vec![],
+        }
+    }
+}
+
+common_derives! {
+    #[derive(Default)]
+    pub struct Vocabulary {
+        pub terms: Vec<VocabularyTerm>,
+    }
+}
+
+impl Vocabulary {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    // Terms are matched case-insensitively, and the first occurrence wins.
+    pub fn push(&mut self, term: VocabularyTerm) {
+        let text = term.text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        let exists = self
+            .terms
+            .iter()
+            .any(|t| t.text.to_lowercase() == text.to_lowercase());
+        if !exists {
+            self.terms.push(VocabularyTerm {
+                text: text.to_string(),
+                ..term
+            });
+        }
+    }
+
+    pub fn extend(&mut self, terms: impl IntoIterator<Item = VocabularyTerm>) {
+        for term in terms {
+            self.push(term);
+        }
+    }
+
+    // For prompt-conditioned models like Whisper.
+    pub fn to_prompt(&self) -> String {
+        self.terms
+            .iter()
+            .map(|t| t.text.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Vocabulary {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut vocabulary = Vocabulary::default();
+        vocabulary.extend(iter.into_iter().map(VocabularyTerm::new));
+        vocabulary
+    }
+}
+
 common_derives! {
     #[derive(Default)]
     pub struct ListenParams {
@@ -67,8 +324,15 @@ common_derives! {
         #[schemars(with = "String")]
         #[serde(serialize_with = "serialize_language", deserialize_with = "deserialize_language")]
         pub language: hypr_language::Language,
+        #[serde(default)]
+        pub language_detection: LanguageDetection,
+        #[serde(default, deserialize_with = "deserialize_vocabulary")]
+        pub vocabulary: Vocabulary,
         pub static_prompt: String,
         pub dynamic_prompt: String,
+        // Transcribe each channel of a v2 stream on its own, instead of downmixing to mono.
+        #[serde(default)]
+        pub multichannel: bool,
     }
 }
 
@@ -117,3 +381,174 @@ fn deserialize_language<'de, D: serde::Deserializer<'de>>(
     let iso639 = hypr_language::ISO639::from_str(&str).map_err(serde::de::Error::custom)?;
     Ok(iso639.into())
 }
+
+fn serialize_language_opt<S: serde::Serializer>(
+    lang: &Option<hypr_language::Language>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match lang {
+        Some(lang) => serialize_language(lang, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_language_opt<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<hypr_language::Language>, D::Error> {
+    let str = Option::<String>::deserialize(deserializer)?;
+    str.map(|s| {
+        let iso639 = hypr_language::ISO639::from_str(&s).map_err(serde::de::Error::custom)?;
+        Ok(iso639.into())
+    })
+    .transpose()
+}
+
+// Query strings can only carry it as JSON text.
+fn deserialize_vocabulary<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vocabulary, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Json(String),
+        Value(Vocabulary),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Json(s) if s.is_empty() => Ok(Vocabulary::default()),
+        Raw::Json(s) => serde_json::from_str(&s).map_err(serde::de::Error::custom),
+        Raw::Value(v) => Ok(v),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_words() {
+        let word = |text: &str, start_ms: Option<u64>| Word {
+            text: text.to_string(),
+            speaker: None,
+            confidence: None,
+            start_ms,
+            end_ms: None,
+        };
+
+        let mut words = vec![word("a", Some(0)), word("c", Some(200))];
+        merge_words(
+            &mut words,
+            [word("b", Some(100)), word("d", Some(200)), word("e", None)],
+        );
+        merge_words(&mut words, [word("f", Some(300))]);
+
+        let texts: Vec<_> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["a", "b", "c", "d", "e", "f"]);
+    }
+
+    #[test]
+    fn test_vocabulary_dedup() {
+        let mut vocabulary: Vocabulary = ["Hyprnote", " hyprnote ", "", "Blitz"]
+            .into_iter()
+            .collect();
+        vocabulary.push(VocabularyTerm::new("BLITZ"));
+
+        assert_eq!(vocabulary.to_prompt(), "Hyprnote, Blitz");
+    }
+
+    #[test]
+    fn test_listen_frame() {
+        let v1 = serde_json::to_string(&ListenInputChunk::Audio {
+            data: vec![1, 2, 3, 4],
+        })
+        .unwrap();
+        assert_eq!(
+            ListenFrame::from_text(&v1).unwrap(),
+            ListenFrame::Audio(vec![1, 2, 3, 4])
+        );
+
+        let v1_end = serde_json::to_string(&ListenInputChunk::End).unwrap();
+        assert_eq!(ListenFrame::from_text(&v1_end).unwrap(), ListenFrame::End);
+
+        let start = serde_json::to_string(&ListenControlMessage::Start {
+            version: LISTEN_PROTOCOL_VERSION,
+            params: ListenParams {
+                language: hypr_language::ISO639::Ko.into(),
+                ..Default::default()
+            },
+            format: AudioFormat {
+                sample_rate: 48000,
+                encoding: AudioEncoding::Linear16,
+                channels: 2,
+            },
+            resume: Some(ListenResume {
+                session_id: "abc".to_string(),
+                received: 3,
+            }),
+        })
+        .unwrap();
+        match ListenFrame::from_text(&start).unwrap() {
+            ListenFrame::Start {
+                version,
+                params,
+                format,
+                resume,
+            } => {
+                assert_eq!(version, 2);
+                assert_eq!(params.language.iso639(), hypr_language::ISO639::Ko);
+                assert_eq!(format.sample_rate, 48000);
+                assert_eq!(format.channels, 2);
+                assert_eq!(resume.unwrap().received, 3);
+            }
+            frame => panic!("unexpected frame: {:?}", frame),
+        }
+
+        let start_without_format = r#"{"type":"start","version":2,"params":{"language":"en","static_prompt":"","dynamic_prompt":""}}"#;
+        assert!(matches!(
+            ListenFrame::from_text(start_without_format).unwrap(),
+            ListenFrame::Start { format, resume: None, .. } if format == AudioFormat::default()
+        ));
+
+        assert_eq!(
+            ListenFrame::from_text(r#"{"type":"keepalive"}"#).unwrap(),
+            ListenFrame::KeepAlive
+        );
+        assert_eq!(
+            ListenFrame::from_binary(vec![0, 0]),
+            ListenFrame::Audio(vec![0, 0])
+        );
+
+        let ack = serde_json::to_string(&ListenServerMessage::Ack { seq: 7 }).unwrap();
+        assert_eq!(ack, r#"{"type":"ack","seq":7}"#);
+        assert!(serde_json::from_str::<ListenOutputChunk>(&ack).is_err());
+
+        assert!(ListenFrame::from_text("not json").is_err());
+        assert!(ListenFrame::from_text(r#"{"type":"audio","value":{}}"#).is_err());
+        assert!(ListenFrame::from_text(r#"{"type":"start"}"#).is_err());
+    }
+
+    #[test]
+    fn test_vocabulary_from_query() {
+        let vocabulary = Vocabulary {
+            terms: vec![VocabularyTerm {
+                text: "Hyprnote".to_string(),
+                boost: Some(2.0),
+                pronunciations: vec!["hyper note".to_string()],
+            }],
+        };
+
+        let query = format!(
+            "language=en&static_prompt=&dynamic_prompt=&vocabulary={}",
+            url::form_urlencoded::byte_serialize(
+                serde_json::to_string(&vocabulary).unwrap().as_bytes()
+            )
+            .collect::<String>()
+        );
+        let params: ListenParams = serde_urlencoded::from_str(&query).unwrap();
+        assert_eq!(params.vocabulary, vocabulary);
+
+        let params: ListenParams =
+            serde_urlencoded::from_str("language=en&static_prompt=&dynamic_prompt=").unwrap();
+        assert!(params.vocabulary.is_empty());
+    }
+}
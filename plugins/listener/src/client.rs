@@ -1,10 +1,18 @@
-use futures_util::Stream;
+use std::collections::VecDeque;
+
+use futures_util::{SinkExt, Stream, StreamExt};
 
 use hypr_audio::AsyncSource;
 use hypr_audio_utils::AudioFormatExt;
-use hypr_ws::client::{ClientRequestBuilder, Message, WebSocketClient, WebSocketIO};
+use hypr_ws::client::{ClientRequestBuilder, Message, KEEP_ALIVE_INTERVAL};
+
+use crate::{
+    AudioFormat, ListenControlMessage, ListenOutputChunk, ListenParams, ListenResume,
+    ListenServerMessage, LISTEN_PROTOCOL_VERSION,
+};
 
-use crate::{ListenInputChunk, ListenOutputChunk};
+// Reconnects in a row without any progress before giving up on the session.
+const MAX_RECONNECTS: usize = 5;
 
 #[derive(Default)]
 pub struct ListenClientBuilder {
@@ -33,12 +41,17 @@ impl ListenClientBuilder {
         let uri = {
             let mut url: url::Url = self.api_base.unwrap().parse().unwrap();
 
-            let params = self.params.unwrap_or_default();
+            let params = self.params.clone().unwrap_or_default();
             let language = params.language.code();
 
             url.set_path("/api/desktop/listen/realtime");
             url.query_pairs_mut()
                 .append_pair("language", language)
+                .append_pair("language_detection", params.language_detection.as_str())
+                .append_pair(
+                    "vocabulary",
+                    &serde_json::to_string(&params.vocabulary).unwrap(),
+                )
                 .append_pair("static_prompt", &params.static_prompt)
                 .append_pair("dynamic_prompt", &params.dynamic_prompt);
 
@@ -59,49 +72,263 @@ impl ListenClientBuilder {
             None => ClientRequestBuilder::new(uri),
         };
 
-        ListenClient { request }
+        ListenClient {
+            request,
+            params: self.params.unwrap_or_default(),
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct ListenClient {
     request: ClientRequestBuilder,
+    params: ListenParams,
+}
+
+fn control_message(msg: &ListenControlMessage) -> Message {
+    Message::Text(serde_json::to_string(msg).unwrap().into())
+}
+
+// About a minute of 16kHz stereo linear16. Past that, the oldest unacknowledged audio is dropped.
+const MAX_UNACKED_BYTES: usize = 16 * 1000 * 2 * 2 * 60;
+
+// Audio frames sent but not yet acknowledged, kept to resend after a reconnect.
+#[derive(Default)]
+struct Outbox {
+    frames: VecDeque<(u64, bytes::Bytes)>,
+    bytes: usize,
+    next_seq: u64,
+    // Frames dropped since the last reconnect, which leaves a gap in the audio.
+    dropped: u64,
 }
 
-impl WebSocketIO for ListenClient {
-    type Input = ListenInputChunk;
-    type Output = ListenOutputChunk;
+impl Outbox {
+    fn push(&mut self, data: bytes::Bytes) -> Message {
+        self.frames.push_back((self.next_seq, data.clone()));
+        self.bytes += data.len();
+        self.next_seq += 1;
 
-    fn to_input(data: bytes::Bytes) -> Self::Input {
-        ListenInputChunk::Audio {
-            data: data.to_vec(),
+        while self.bytes > MAX_UNACKED_BYTES {
+            let Some((_, dropped)) = self.frames.pop_front() else {
+                break;
+            };
+            if self.dropped == 0 {
+                tracing::warn!("listen_outbox_full: dropping the oldest unacked audio");
+            }
+            self.bytes -= dropped.len();
+            self.dropped += 1;
         }
+
+        Message::Binary(data)
     }
 
-    fn to_message(input: Self::Input) -> Message {
-        Message::Text(serde_json::to_string(&input).unwrap().into())
+    fn ack(&mut self, seq: u64) {
+        while self.frames.front().is_some_and(|(s, _)| *s < seq) {
+            let (_, data) = self.frames.pop_front().unwrap();
+            self.bytes -= data.len();
+        }
     }
 
-    fn from_message(msg: Message) -> Option<Self::Output> {
-        match msg {
-            Message::Text(text) => serde_json::from_str::<Self::Output>(&text).ok(),
-            _ => None,
+    // The server numbers audio frames by how many it got, so whatever follows a gap is renumbered from `next_seq`.
+    fn resume(&mut self, next_seq: u64) -> Vec<Message> {
+        self.ack(next_seq);
+
+        let resent = self.frames.front().map_or(self.next_seq, |(seq, _)| *seq);
+        if resent > next_seq {
+            tracing::warn!(
+                frames = resent - next_seq,
+                dropped = self.dropped,
+                "listen_audio_gap"
+            );
+        }
+        self.dropped = 0;
+
+        for (i, (seq, _)) in self.frames.iter_mut().enumerate() {
+            *seq = next_seq + i as u64;
         }
+        self.next_seq = next_seq + self.frames.len() as u64;
+
+        self.frames
+            .iter()
+            .map(|(_, data)| Message::Binary(data.clone()))
+            .collect()
     }
 }
 
+enum Event {
+    Server(Option<Message>),
+    Audio(Option<bytes::Bytes>),
+    KeepAlive,
+}
+
 impl ListenClient {
     pub fn builder() -> ListenClientBuilder {
         ListenClientBuilder::default()
     }
 
+    fn start_message(&self, channels: u16, resume: Option<ListenResume>) -> Message {
+        control_message(&ListenControlMessage::Start {
+            version: LISTEN_PROTOCOL_VERSION,
+            params: ListenParams {
+                multichannel: channels > 1,
+                ..self.params.clone()
+            },
+            format: AudioFormat {
+                channels,
+                ..Default::default()
+            },
+            resume,
+        })
+    }
+
     pub async fn from_audio(
         &self,
         audio_stream: impl AsyncSource + Send + Unpin + 'static,
     ) -> Result<impl Stream<Item = ListenOutputChunk>, hypr_ws::Error> {
-        let input_stream = audio_stream.to_i16_le_chunks(16 * 1000, 1024);
-        let ws = WebSocketClient::new(self.request.clone());
-        ws.from_audio::<Self>(input_stream).await
+        let input_stream = audio_stream.to_i16_le_chunks(AudioFormat::default().sample_rate, 1024);
+        self.from_stream(input_stream, 1).await
+    }
+
+    // Mic and speaker are transcribed separately, and each chunk is tagged with `MIC_CHANNEL` or `SPEAKER_CHANNEL`.
+    pub async fn from_multichannel_audio(
+        &self,
+        mic_stream: impl AsyncSource + Send + Unpin + 'static,
+        speaker_stream: impl AsyncSource + Send + Unpin + 'static,
+    ) -> Result<impl Stream<Item = ListenOutputChunk>, hypr_ws::Error> {
+        let sample_rate = AudioFormat::default().sample_rate;
+        let input_stream = hypr_audio_utils::interleave_i16_le(vec![
+            mic_stream.to_i16_le_chunks(sample_rate, 1024).boxed(),
+            speaker_stream.to_i16_le_chunks(sample_rate, 1024).boxed(),
+        ]);
+        self.from_stream(input_stream, 2).await
+    }
+
+    // Survives dropped connections by resuming the session, so no transcript is lost or repeated.
+    async fn from_stream(
+        &self,
+        mut input_stream: impl Stream<Item = bytes::Bytes> + Send + Unpin + 'static,
+        channels: u16,
+    ) -> Result<impl Stream<Item = ListenOutputChunk>, hypr_ws::Error> {
+        let mut socket = Some(hypr_ws::client::connect(self.request.clone()).await?);
+        let client = self.clone();
+
+        let output_stream = async_stream::stream! {
+            let mut outbox = Outbox::default();
+            let mut session_id: Option<String> = None;
+            let mut received = 0;
+            let mut audio_ended = false;
+            let mut reconnects = 0;
+
+            'connection: loop {
+                let ws = match socket.take() {
+                    Some(ws) => ws,
+                    None => {
+                        reconnects += 1;
+                        if reconnects > MAX_RECONNECTS {
+                            tracing::error!("listen_reconnect_gave_up");
+                            break;
+                        }
+
+                        match hypr_ws::client::connect(client.request.clone()).await {
+                            Ok(ws) => ws,
+                            Err(e) => {
+                                tracing::error!("listen_reconnect_failed: {:?}", e);
+                                break;
+                            }
+                        }
+                    }
+                };
+                let (mut ws_sender, mut ws_receiver) = ws.split();
+
+                let resume = session_id.clone().map(|session_id| ListenResume {
+                    session_id,
+                    received,
+                });
+                if let Err(e) = ws_sender.send(client.start_message(channels, resume)).await {
+                    tracing::warn!("ws_send_failed: {:?}", e);
+                    continue;
+                }
+
+                let mut keep_alive = tokio::time::interval_at(
+                    tokio::time::Instant::now() + KEEP_ALIVE_INTERVAL,
+                    KEEP_ALIVE_INTERVAL,
+                );
+                // Audio waits until the server says where the session stands.
+                let mut ready = false;
+
+                loop {
+                    let event = tokio::select! {
+                        msg = ws_receiver.next() => Event::Server(match msg {
+                            Some(Ok(msg)) => Some(msg),
+                            Some(Err(e)) => {
+                                tracing::warn!("ws_receiver_failed: {:?}", e);
+                                None
+                            }
+                            None => None,
+                        }),
+                        data = input_stream.next(), if ready && !audio_ended => Event::Audio(data),
+                        _ = keep_alive.tick() => Event::KeepAlive,
+                    };
+
+                    let messages = match event {
+                        Event::Server(Some(Message::Text(text))) => {
+                            match serde_json::from_str::<ListenServerMessage>(&text) {
+                                Ok(ListenServerMessage::Session { session_id: id, next_seq }) => {
+                                    session_id = Some(id);
+                                    ready = true;
+
+                                    let mut messages = outbox.resume(next_seq);
+                                    if audio_ended {
+                                        messages.push(control_message(&ListenControlMessage::End));
+                                    }
+                                    messages
+                                }
+                                Ok(ListenServerMessage::Ack { seq }) => {
+                                    outbox.ack(seq);
+                                    reconnects = 0;
+                                    vec![]
+                                }
+                                Err(_) => {
+                                    if let Ok(output) = serde_json::from_str::<ListenOutputChunk>(&text) {
+                                        if !output.interim {
+                                            received += 1;
+                                        }
+                                        reconnects = 0;
+                                        yield output;
+                                    }
+                                    vec![]
+                                }
+                            }
+                        }
+                        Event::Server(Some(Message::Close(_))) => break 'connection,
+                        Event::Server(Some(_)) => vec![],
+                        Event::Server(None) => {
+                            tracing::warn!("listen_disconnected");
+                            continue 'connection;
+                        }
+                        Event::Audio(Some(data)) => {
+                            keep_alive.reset();
+                            vec![outbox.push(data)]
+                        }
+                        // We shouldn't send a 'Close' message, as it would prevent receiving remaining transcripts from the server.
+                        Event::Audio(None) => {
+                            audio_ended = true;
+                            vec![control_message(&ListenControlMessage::End)]
+                        }
+                        Event::KeepAlive => vec![control_message(&ListenControlMessage::KeepAlive)],
+                    };
+
+                    for msg in messages {
+                        if let Err(e) = ws_sender.send(msg).await {
+                            tracing::warn!("ws_send_failed: {:?}", e);
+                            continue 'connection;
+                        }
+                    }
+                }
+            }
+        };
+
+        Ok(output_stream)
     }
 }
 
@@ -111,15 +338,17 @@ mod tests {
     use futures_util::StreamExt;
 
     #[tokio::test]
-    #[ignore]
     async fn test_listen_client() {
+        let script = hypr_stt_mock::Script::english();
+        let server = hypr_stt_mock::serve_listen(script.clone()).await;
+
         let audio = rodio::Decoder::new_wav(std::io::BufReader::new(
-            std::fs::File::open(hypr_data::english_1::AUDIO_PATH).unwrap(),
+            std::fs::File::open(hypr_data::english_2::AUDIO_PATH).unwrap(),
         ))
         .unwrap();
 
         let client = ListenClient::builder()
-            .api_base("http://127.0.0.1:1234")
+            .api_base(server.api_base())
             .api_key("".to_string())
             .params(hypr_listener_interface::ListenParams {
                 language: hypr_language::ISO639::En.into(),
@@ -130,8 +359,34 @@ mod tests {
         let stream = client.from_audio(audio).await.unwrap();
         futures_util::pin_mut!(stream);
 
+        let mut words = vec![];
         while let Some(result) = stream.next().await {
-            println!("{:?}", result);
+            words.extend(result.words.into_iter().map(|w| w.text));
         }
+
+        let expected: Vec<_> = script.words.iter().map(|w| w.text.trim()).collect();
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn test_outbox_is_bounded() {
+        let mut outbox = Outbox::default();
+        let frame = bytes::Bytes::from(vec![0; 1024]);
+        let kept = MAX_UNACKED_BYTES / frame.len();
+        for _ in 0..kept + 10 {
+            outbox.push(frame.clone());
+        }
+        assert_eq!(outbox.frames.len(), kept);
+        assert_eq!(outbox.bytes, MAX_UNACKED_BYTES);
+
+        // The server got 5 frames before the connection dropped, so the 5 after them are lost,
+        // and the rest is renumbered to follow on from there.
+        assert_eq!(outbox.resume(5).len(), kept);
+        assert_eq!(outbox.frames.front().unwrap().0, 5);
+        assert_eq!(outbox.next_seq, 5 + kept as u64);
+
+        outbox.ack(7);
+        assert_eq!(outbox.frames.len(), kept - 2);
+        assert_eq!(outbox.bytes, MAX_UNACKED_BYTES - 2 * frame.len());
     }
 }
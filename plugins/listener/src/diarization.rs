@@ -0,0 +1,489 @@
+use std::collections::{HashMap, HashSet};
+
+use tokio::sync::mpsc;
+
+use hypr_db_user::{SpeakerSuggestion, VoiceProfile};
+use hypr_listener_interface::{SpeakerIdentity, Word, MIC_CHANNEL, SPEAKER_CHANNEL};
+use hypr_pyannote::local::{
+    clustering::cosine_similarity,
+    diarization::{Diarizer, SpeakerEmbedding, SpeakerTurn},
+    enrollment::Enrollment,
+};
+
+// Words this close to a turn, but outside it, still get its speaker. Segmentation tends to trim word edges.
+const MAX_GAP_MS: u64 = 500;
+
+// A speaker this similar to a voice profile is labeled as its human right away.
+const MATCH_THRESHOLD: f32 = 0.7;
+// Below `MATCH_THRESHOLD` but above this, the user is asked to confirm instead.
+const SUGGEST_THRESHOLD: f32 = 0.5;
+// A speaker's centroid is too noisy to match until it was averaged from this many embeddings.
+const MIN_EMBEDDINGS_TO_MATCH: usize = 3;
+
+// Diarization numbers speakers from 0 and never gets this far. Indices from here on are placeholders,
+// which diarization replaces once it has a turn for the word.
+const PLACEHOLDER_SPEAKER_INDEX: u8 = 240;
+// Someone on the system audio, before diarization tells the remote speakers apart.
+pub const REMOTE_SPEAKER: SpeakerIdentity = SpeakerIdentity::Unassigned {
+    index: PLACEHOLDER_SPEAKER_INDEX,
+};
+
+// Speech needed from the mic to enroll the user's voice.
+const ENROLLMENT_SPEECH_MS: u64 = 30 * 1000;
+// One profile per session until there are this many, to cover different mics and rooms.
+pub const MAX_USER_VOICE_PROFILES: usize = 3;
+
+pub struct DiarizationUpdate {
+    pub turns: Vec<SpeakerTurn>,
+    pub speakers: Vec<SpeakerEmbedding>,
+}
+
+// Runs on its own thread, since segmentation and embedding are too heavy for the async runtime.
+// Turns are sent as soon as a window of audio is diarized, and the rest when `audio_rx` closes.
+pub fn spawn_diarizer(
+    sample_rate: u32,
+    mut audio_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+) -> mpsc::UnboundedReceiver<DiarizationUpdate> {
+    let (update_tx, update_rx) = mpsc::unbounded_channel();
+
+    std::thread::spawn(move || {
+        let mut diarizer = match Diarizer::new(sample_rate) {
+            Ok(diarizer) => diarizer,
+            Err(e) => {
+                tracing::error!("diarizer_init_error: {:?}", e);
+                return;
+            }
+        };
+
+        while let Some(samples) = audio_rx.blocking_recv() {
+            match diarizer.push(&samples) {
+                Ok(turns) if turns.is_empty() => {}
+                Ok(turns) => {
+                    let update = DiarizationUpdate {
+                        turns,
+                        speakers: diarizer.speakers(),
+                    };
+                    if update_tx.send(update).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => tracing::error!("diarization_error: {:?}", e),
+            }
+        }
+
+        match diarizer.finish() {
+            Ok(turns) => {
+                let _ = update_tx.send(DiarizationUpdate {
+                    turns,
+                    speakers: diarizer.speakers(),
+                });
+            }
+            Err(e) => tracing::error!("diarization_error: {:?}", e),
+        }
+    });
+
+    update_rx
+}
+
+// Sends a voice embedding once there is enough speech in `audio_rx`, which must only hold the user's voice.
+pub fn spawn_enrollment(
+    sample_rate: u32,
+    mut audio_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+) -> mpsc::UnboundedReceiver<Vec<f32>> {
+    let (embedding_tx, embedding_rx) = mpsc::unbounded_channel();
+
+    std::thread::spawn(move || {
+        let mut enrollment = match Enrollment::new(sample_rate, ENROLLMENT_SPEECH_MS) {
+            Ok(enrollment) => enrollment,
+            Err(e) => {
+                tracing::error!("enrollment_init_error: {:?}", e);
+                return;
+            }
+        };
+
+        while let Some(samples) = audio_rx.blocking_recv() {
+            match enrollment.push(&samples) {
+                Ok(None) => {}
+                Ok(Some(embedding)) => {
+                    let _ = embedding_tx.send(embedding);
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!("enrollment_error: {:?}", e);
+                    return;
+                }
+            }
+        }
+    });
+
+    embedding_rx
+}
+
+// Matches diarized speakers of a session against enrolled voice profiles.
+pub struct VoiceMatcher {
+    session_id: String,
+    profiles: Vec<VoiceProfile>,
+    labels: HashMap<String, String>,
+    known: HashMap<usize, SpeakerIdentity>,
+    suggested: HashSet<usize>,
+}
+
+impl VoiceMatcher {
+    // `labels` maps the human of each profile to the name their words are labeled with.
+    pub fn new(
+        session_id: impl Into<String>,
+        profiles: Vec<VoiceProfile>,
+        labels: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            session_id: session_id.into(),
+            profiles,
+            labels,
+            known: HashMap::new(),
+            suggested: HashSet::new(),
+        }
+    }
+
+    pub fn known(&self) -> &HashMap<usize, SpeakerIdentity> {
+        &self.known
+    }
+
+    // Returns suggestions for speakers that are only somewhat similar to a profile, at most once per speaker.
+    pub fn update(&mut self, speakers: &[SpeakerEmbedding]) -> Vec<SpeakerSuggestion> {
+        let mut suggestions = Vec::new();
+
+        for speaker in speakers {
+            if speaker.count < MIN_EMBEDDINGS_TO_MATCH || self.known.contains_key(&speaker.speaker)
+            {
+                continue;
+            }
+
+            let closest = self
+                .profiles
+                .iter()
+                .map(|p| (p, cosine_similarity(&p.embedding, &speaker.embedding)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            match closest {
+                Some((profile, similarity)) if similarity >= MATCH_THRESHOLD => {
+                    self.known.insert(
+                        speaker.speaker,
+                        SpeakerIdentity::Assigned {
+                            id: profile.human_id.clone(),
+                            label: self
+                                .labels
+                                .get(&profile.human_id)
+                                .cloned()
+                                .unwrap_or_default(),
+                        },
+                    );
+                }
+                Some((profile, similarity)) if similarity >= SUGGEST_THRESHOLD => {
+                    if self.suggested.insert(speaker.speaker) {
+                        suggestions.push(SpeakerSuggestion {
+                            id: uuid::Uuid::new_v4().to_string(),
+                            session_id: self.session_id.clone(),
+                            speaker_index: speaker.speaker as u8,
+                            human_id: profile.human_id.clone(),
+                            similarity,
+                            embedding: speaker.embedding.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        suggestions
+    }
+}
+
+// Mic words are the user's. System audio words are someone else's, until diarization says whose.
+pub fn tag_speakers(
+    mut words: Vec<Word>,
+    channel: Option<u8>,
+    user_speaker: &SpeakerIdentity,
+) -> Vec<Word> {
+    let speaker = match channel {
+        Some(MIC_CHANNEL) => user_speaker.clone(),
+        Some(SPEAKER_CHANNEL) => REMOTE_SPEAKER,
+        _ => return words,
+    };
+
+    for word in &mut words {
+        word.speaker = Some(speaker.clone());
+    }
+    words
+}
+
+fn is_placeholder(speaker: &Option<SpeakerIdentity>) -> bool {
+    matches!(speaker, Some(SpeakerIdentity::Unassigned { index }) if *index >= PLACEHOLDER_SPEAKER_INDEX)
+}
+
+// Gives words without a speaker, or with a placeholder, the one of the turn they overlap the most, and labels speakers matched to a voice profile.
+// Returns whether any word changed.
+pub fn assign_speakers(
+    words: &mut [Word],
+    turns: &[SpeakerTurn],
+    known: &HashMap<usize, SpeakerIdentity>,
+) -> bool {
+    let mut changed = false;
+
+    for word in words.iter_mut() {
+        let index = match &word.speaker {
+            speaker if speaker.is_none() || is_placeholder(speaker) => {
+                match closest_speaker(word, turns) {
+                    Some(speaker) => speaker,
+                    None => continue,
+                }
+            }
+            // Matched to a profile after the words were diarized.
+            Some(SpeakerIdentity::Unassigned { index })
+                if known.contains_key(&(*index as usize)) =>
+            {
+                *index as usize
+            }
+            _ => continue,
+        };
+
+        word.speaker = Some(
+            known
+                .get(&index)
+                .cloned()
+                .unwrap_or(SpeakerIdentity::Unassigned { index: index as u8 }),
+        );
+        changed = true;
+    }
+
+    changed
+}
+
+// Like `assign_speakers`, but for turns of the whole recording, whose indices don't match the ones words already have.
+// Only speakers assigned to a human are kept, whether by hand, by voice profile, or as the user on the mic channel.
+// Placeholders are kept too, if no turn replaces them.
+pub fn relabel_speakers(
+    words: &mut [Word],
+    turns: &[SpeakerTurn],
+    known: &HashMap<usize, SpeakerIdentity>,
+) -> bool {
+    let mut changed = false;
+
+    for word in words.iter_mut() {
+        if matches!(word.speaker, Some(SpeakerIdentity::Assigned { .. })) {
+            continue;
+        }
+
+        let speaker = match closest_speaker(word, turns) {
+            Some(index) => Some(
+                known
+                    .get(&index)
+                    .cloned()
+                    .unwrap_or(SpeakerIdentity::Unassigned { index: index as u8 }),
+            ),
+            // Still tells the remote side apart from the user.
+            None if is_placeholder(&word.speaker) => word.speaker.clone(),
+            None => None,
+        };
+
+        if word.speaker != speaker {
+            word.speaker = speaker;
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+// Speaker of the turn the word overlaps the most.
+fn closest_speaker(word: &Word, turns: &[SpeakerTurn]) -> Option<usize> {
+    let (start_ms, end_ms) = (word.start_ms?, word.end_ms?);
+
+    turns
+        .iter()
+        .filter(|t| t.start_ms <= end_ms + MAX_GAP_MS && start_ms <= t.end_ms + MAX_GAP_MS)
+        .max_by_key(|t| end_ms.min(t.end_ms) as i64 - start_ms.max(t.start_ms) as i64)
+        .map(|t| t.speaker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> Word {
+        Word {
+            text: text.to_string(),
+            speaker: None,
+            confidence: None,
+            start_ms: Some(start_ms),
+            end_ms: Some(end_ms),
+        }
+    }
+
+    fn turns() -> Vec<SpeakerTurn> {
+        vec![
+            SpeakerTurn {
+                start_ms: 0,
+                end_ms: 1000,
+                speaker: 0,
+            },
+            SpeakerTurn {
+                start_ms: 1000,
+                end_ms: 2000,
+                speaker: 1,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_assign_speakers() {
+        let mut words = vec![
+            word("a", 100, 300),
+            word("b", 900, 1300),
+            word("c", 2200, 2400),
+            word("d", 5000, 5200),
+        ];
+        assert!(assign_speakers(&mut words, &turns(), &HashMap::new()));
+
+        let speakers: Vec<_> = words.iter().map(|w| w.speaker.clone()).collect();
+        assert_eq!(
+            speakers,
+            vec![
+                Some(SpeakerIdentity::Unassigned { index: 0 }),
+                Some(SpeakerIdentity::Unassigned { index: 1 }),
+                Some(SpeakerIdentity::Unassigned { index: 1 }),
+                None,
+            ]
+        );
+
+        // Words that already have a speaker are left alone.
+        assert!(!assign_speakers(&mut words[..3], &turns(), &HashMap::new()));
+
+        // Unless their speaker was matched to a voice profile since.
+        let jane = SpeakerIdentity::Assigned {
+            id: "jane".to_string(),
+            label: "Jane".to_string(),
+        };
+        let known = HashMap::from([(1, jane.clone())]);
+        assert!(assign_speakers(&mut words, &turns(), &known));
+        assert_eq!(words[1].speaker, Some(jane.clone()));
+        assert_eq!(words[2].speaker, Some(jane));
+        assert_eq!(
+            words[0].speaker,
+            Some(SpeakerIdentity::Unassigned { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_relabel_speakers() {
+        let jane = SpeakerIdentity::Assigned {
+            id: "jane".to_string(),
+            label: "Jane".to_string(),
+        };
+
+        let mut words = vec![
+            word("a", 100, 300),
+            word("b", 900, 1300),
+            word("c", 1400, 1600),
+            word("d", 5000, 5200),
+        ];
+        words[0].speaker = Some(SpeakerIdentity::Unassigned { index: 1 });
+        words[2].speaker = Some(jane.clone());
+        words[3].speaker = Some(SpeakerIdentity::Unassigned { index: 0 });
+
+        assert!(relabel_speakers(&mut words, &turns(), &HashMap::new()));
+
+        let speakers: Vec<_> = words.iter().map(|w| w.speaker.clone()).collect();
+        assert_eq!(
+            speakers,
+            vec![
+                Some(SpeakerIdentity::Unassigned { index: 0 }),
+                Some(SpeakerIdentity::Unassigned { index: 1 }),
+                Some(jane),
+                // No turn of the recording, so the old index would be meaningless.
+                None,
+            ]
+        );
+
+        assert!(!relabel_speakers(&mut words, &turns(), &HashMap::new()));
+    }
+
+    #[test]
+    fn test_voice_matcher() {
+        let profiles = vec![
+            VoiceProfile::new("jane", vec![1.0, 0.0, 0.0]),
+            VoiceProfile::new("john", vec![0.0, 1.0, 0.0]),
+        ];
+        let labels = HashMap::from([("jane".to_string(), "Jane".to_string())]);
+        let mut matcher = VoiceMatcher::new("session", profiles, labels);
+
+        let speaker = |speaker: usize, embedding: Vec<f32>, count: usize| SpeakerEmbedding {
+            speaker,
+            embedding,
+            count,
+        };
+
+        let speakers = vec![
+            // Close to Jane.
+            speaker(0, vec![0.95, 0.1, 0.0], 5),
+            // Somewhat like John.
+            speaker(1, vec![0.3, 0.6, 0.6], 5),
+            // Nobody we know.
+            speaker(2, vec![0.0, 0.0, 1.0], 5),
+            // Close to John, but not heard enough yet.
+            speaker(3, vec![0.0, 1.0, 0.0], 1),
+        ];
+
+        let suggestions = matcher.update(&speakers);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].speaker_index, 1);
+        assert_eq!(suggestions[0].human_id, "john");
+
+        assert_eq!(
+            matcher.known(),
+            &HashMap::from([(
+                0,
+                SpeakerIdentity::Assigned {
+                    id: "jane".to_string(),
+                    label: "Jane".to_string(),
+                }
+            )])
+        );
+
+        // Suggested once per session.
+        assert!(matcher.update(&speakers).is_empty());
+    }
+
+    #[test]
+    fn test_tag_speakers() {
+        let user = SpeakerIdentity::Assigned {
+            id: "user".to_string(),
+            label: "You".to_string(),
+        };
+        let words = || vec![word("a", 100, 300), word("b", 5000, 5200)];
+
+        let mic = tag_speakers(words(), Some(MIC_CHANNEL), &user);
+        assert!(mic.iter().all(|w| w.speaker == Some(user.clone())));
+
+        let mut remote = tag_speakers(words(), Some(SPEAKER_CHANNEL), &user);
+        assert!(remote.iter().all(|w| w.speaker == Some(REMOTE_SPEAKER)));
+
+        let mixed = tag_speakers(words(), None, &user);
+        assert!(mixed.iter().all(|w| w.speaker.is_none()));
+
+        // Diarization refines the remote speaker where it has a turn, and keeps the placeholder elsewhere.
+        assert!(assign_speakers(&mut remote, &turns(), &HashMap::new()));
+        assert_eq!(
+            remote[0].speaker,
+            Some(SpeakerIdentity::Unassigned { index: 0 })
+        );
+        assert_eq!(remote[1].speaker, Some(REMOTE_SPEAKER));
+
+        let mut remote = tag_speakers(words(), Some(SPEAKER_CHANNEL), &user);
+        relabel_speakers(&mut remote, &turns(), &HashMap::new());
+        assert_eq!(
+            remote[0].speaker,
+            Some(SpeakerIdentity::Unassigned { index: 0 })
+        );
+        assert_eq!(remote[1].speaker, Some(REMOTE_SPEAKER));
+    }
+}
@@ -14,6 +14,14 @@ pub enum Error {
     DatabaseError(#[from] tauri_plugin_db::Error),
     #[error(transparent)]
     ConnectorError(#[from] tauri_plugin_connector::Error),
+    #[error(transparent)]
+    WavError(#[from] hound::Error),
+    #[error(transparent)]
+    DiarizationError(#[from] anyhow::Error),
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    #[error("diarization job {0} timed out")]
+    DiarizationTimeout(String),
     #[error("no session")]
     NoneSession,
     #[error("start session failed")]
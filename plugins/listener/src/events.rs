@@ -17,6 +17,9 @@ common_event_derives! {
         RunningPaused {},
         #[serde(rename = "words")]
         Words { words: Vec<hypr_listener_interface::Word>},
+        // Replaced by the next `words` or `interimWords` event.
+        #[serde(rename = "interimWords")]
+        InterimWords { words: Vec<hypr_listener_interface::Word>},
         #[serde(rename = "audioAmplitude")]
         AudioAmplitude { mic: u16, speaker: u16 },
         #[serde(rename = "micMuted")]
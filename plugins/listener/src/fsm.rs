@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
 use statig::prelude::*;
@@ -10,8 +11,14 @@ use tokio::sync::mpsc;
 use tokio::task::JoinSet;
 
 use hypr_audio::AsyncSource;
+use hypr_db_user::VoiceProfile;
+use hypr_listener_interface::{merge_words, SpeakerIdentity, Vocabulary, VocabularyTerm};
+use hypr_pyannote::local::diarization::SpeakerTurn;
 
-use crate::SessionEvent;
+use crate::{
+    diarization::{tag_speakers, VoiceMatcher},
+    SessionEvent,
+};
 
 const SAMPLE_RATE: u32 = 16000;
 const AUDIO_AMPLITUDE_THROTTLE: Duration = Duration::from_millis(100);
@@ -51,7 +58,7 @@ impl Session {
         let session_id = id.into();
         self.session_id = Some(session_id.clone());
 
-        let (record, language, jargons) = {
+        let (record, language, language_detection, jargons, multichannel) = {
             let config = self.app.db_get_config(&user_id).await?;
 
             let record = config
@@ -63,9 +70,19 @@ impl Session {
                 |c| c.general.display_language.clone(),
             );
 
+            let language_detection = config
+                .as_ref()
+                .and_then(|c| c.general.language_detection)
+                .unwrap_or_default();
+
+            let multichannel = config
+                .as_ref()
+                .and_then(|c| c.general.multichannel)
+                .unwrap_or(false);
+
             let jargons = config.map_or_else(Vec::new, |c| c.general.jargons);
 
-            (record, language, jargons)
+            (record, language, language_detection, jargons, multichannel)
         };
 
         let session = self
@@ -74,6 +91,23 @@ impl Session {
             .await?
             .ok_or(crate::Error::NoneSession)?;
 
+        let vocabulary = {
+            let mut vocabulary: Vocabulary = jargons.into_iter().collect();
+
+            if let Some(event_id) = &session.calendar_event_id {
+                if let Some(event) = self.app.db_get_event(event_id).await? {
+                    vocabulary.extend(
+                        event
+                            .participants
+                            .into_iter()
+                            .map(|p| VocabularyTerm::new(p.name)),
+                    );
+                }
+            }
+
+            vocabulary
+        };
+
         let (mic_muted_tx, mic_muted_rx_main) = tokio::sync::watch::channel(false);
         let (speaker_muted_tx, speaker_muted_rx_main) = tokio::sync::watch::channel(false);
         let (session_state_tx, session_state_rx) =
@@ -87,7 +121,8 @@ impl Session {
         self.speaker_muted_rx = Some(speaker_muted_rx_main.clone());
         self.session_state_tx = Some(session_state_tx);
 
-        let listen_client = setup_listen_client(&self.app, language, jargons).await?;
+        let listen_client =
+            setup_listen_client(&self.app, language, language_detection, vocabulary).await?;
 
         let mic_sample_stream = {
             let mut input = hypr_audio::AudioInput::from_mic();
@@ -107,6 +142,30 @@ impl Session {
 
         let (save_tx, mut save_rx) = mpsc::channel::<f32>(sample_buffer_size);
         let (process_tx, process_rx) = mpsc::channel::<f32>(sample_buffer_size);
+        // Only used with `multichannel`, where `process_tx` carries the mic alone.
+        let (speaker_process_tx, speaker_process_rx) = mpsc::channel::<f32>(sample_buffer_size);
+        // Unbounded, so a diarizer that falls behind never holds up transcription.
+        let (diarize_tx, diarize_rx) = mpsc::unbounded_channel::<Vec<f32>>();
+        let mut diarization_rx = crate::diarization::spawn_diarizer(SAMPLE_RATE, diarize_rx);
+
+        // Only with `multichannel` is the mic known to be the user alone.
+        let (mut enroll_tx, mut enrollment_rx) = {
+            let enrolled = self
+                .app
+                .db_list_voice_profiles(Some(user_id.clone()))
+                .await?
+                .len();
+
+            if multichannel && enrolled < crate::diarization::MAX_USER_VOICE_PROFILES {
+                let (enroll_tx, enroll_rx) = mpsc::unbounded_channel::<Vec<f32>>();
+                let enrollment_rx = crate::diarization::spawn_enrollment(SAMPLE_RATE, enroll_rx);
+                (Some(enroll_tx), enrollment_rx)
+            } else {
+                (None, mpsc::unbounded_channel().1)
+            }
+        };
+
+        let mut voice_matcher = load_voice_matcher(&self.app, &session.id).await?;
 
         {
             let silence_stream_tx = hypr_audio::AudioOutput::silence();
@@ -192,20 +251,41 @@ impl Session {
                         last_broadcast = now;
                     }
 
-                    let mixed: Vec<f32> = mic_chunk
-                        .into_iter()
-                        .zip(speaker_chunk.into_iter())
-                        .map(|(a, b)| (a + b).clamp(-1.0, 1.0))
-                        .collect();
+                    // With `multichannel`, mic words are already the user's, so only the others need diarizing.
+                    let diarize_chunk = if multichannel {
+                        speaker_chunk.clone()
+                    } else {
+                        mic_chunk
+                            .iter()
+                            .zip(&speaker_chunk)
+                            .map(|(a, b)| (a + b).clamp(-1.0, 1.0))
+                            .collect()
+                    };
+                    let _ = diarize_tx.send(diarize_chunk);
+
+                    if let Some(tx) = &enroll_tx {
+                        if tx.send(mic_chunk.clone()).is_err() {
+                            enroll_tx = None;
+                        }
+                    }
 
-                    for &sample in &mixed {
-                        if process_tx.send(sample).await.is_err() {
+                    for (mic, speaker) in mic_chunk.into_iter().zip(speaker_chunk.into_iter()) {
+                        let mixed = (mic + speaker).clamp(-1.0, 1.0);
+
+                        if multichannel {
+                            if process_tx.send(mic).await.is_err()
+                                || speaker_process_tx.send(speaker).await.is_err()
+                            {
+                                tracing::error!("process_tx_send_error");
+                                return;
+                            }
+                        } else if process_tx.send(mixed).await.is_err() {
                             tracing::error!("process_tx_send_error");
                             return;
                         }
 
                         if record {
-                            if save_tx.send(sample).await.is_err() {
+                            if save_tx.send(mixed).await.is_err() {
                                 tracing::error!("save_tx_send_error");
                             }
                         }
@@ -246,21 +326,84 @@ impl Session {
         // let timeline = Arc::new(Mutex::new(initialize_timeline(&session).await));
         let audio_stream = hypr_audio::ReceiverStreamSource::new(process_rx, SAMPLE_RATE);
 
-        let listen_stream = listen_client.from_audio(audio_stream).await?;
+        let mut listen_stream = if multichannel {
+            let speaker_stream =
+                hypr_audio::ReceiverStreamSource::new(speaker_process_rx, SAMPLE_RATE);
+
+            listen_client
+                .from_multichannel_audio(audio_stream, speaker_stream)
+                .await?
+                .boxed()
+        } else {
+            listen_client.from_audio(audio_stream).await?.boxed()
+        };
+
+        let user_speaker = {
+            let human = self.app.db_get_human(&user_id).await?;
+            SpeakerIdentity::Assigned {
+                id: user_id.clone(),
+                label: human
+                    .and_then(|h| h.full_name)
+                    .unwrap_or_else(|| "You".to_string()),
+            }
+        };
 
         tasks.spawn({
             let app = self.app.clone();
             let stop_tx = stop_tx.clone();
+            let user_id = user_id.clone();
 
             async move {
-                futures_util::pin_mut!(listen_stream);
+                let mut turns = Vec::new();
+
+                loop {
+                    let (words, language) = tokio::select! {
+                        result = listen_stream.next() => match result {
+                            // Shown until the next chunk arrives, but never stored.
+                            Some(result) if result.interim => {
+                                let words = tag_speakers(result.words, result.channel, &user_speaker);
+                                if let Err(e) = (SessionEvent::InterimWords { words }).emit(&app) {
+                                    tracing::error!("interim_words_emit_error: {:?}", e);
+                                }
+                                continue;
+                            }
+                            Some(result) => (
+                                tag_speakers(result.words, result.channel, &user_speaker),
+                                result.language,
+                            ),
+                            None => break,
+                        },
+                        Some(update) = diarization_rx.recv() => {
+                            turns.extend(update.turns);
+
+                            for suggestion in voice_matcher.update(&update.speakers) {
+                                if let Err(e) = app.db_upsert_speaker_suggestion(suggestion).await {
+                                    tracing::error!("speaker_suggestion_error: {:?}", e);
+                                }
+                            }
+                            (vec![], None)
+                        }
+                        Some(embedding) = enrollment_rx.recv() => {
+                            let profile = VoiceProfile::new(&user_id, embedding);
+                            if let Err(e) = app.db_upsert_voice_profile(profile).await {
+                                tracing::error!("voice_enrollment_error: {:?}", e);
+                            }
+                            continue;
+                        }
+                    };
 
-                while let Some(result) = listen_stream.next().await {
                     // We don't have to do this, and inefficient. But this is what works at the moment.
                     {
-                        let updated_words = update_session(&app, &session.id, result.words)
-                            .await
-                            .unwrap();
+                        let updated_words = update_session(
+                            &app,
+                            &session.id,
+                            words,
+                            language,
+                            &turns,
+                            voice_matcher.known(),
+                        )
+                        .await
+                        .unwrap();
 
                         SessionEvent::Words {
                             words: updated_words,
@@ -326,7 +469,8 @@ impl Session {
 async fn setup_listen_client<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     language: hypr_language::Language,
-    jargons: Vec<String>,
+    language_detection: hypr_listener_interface::LanguageDetection,
+    vocabulary: Vocabulary,
 ) -> Result<crate::client::ListenClient, crate::Error> {
     let api_base = {
         use tauri_plugin_connector::{Connection, ConnectorPluginExt};
@@ -344,8 +488,7 @@ async fn setup_listen_client<R: tauri::Runtime>(
     tracing::info!(api_base = ?api_base, api_key = ?api_key, language = ?language, "listen_client");
 
     let static_prompt = format!(
-        "{} / {}:",
-        jargons.join(", "),
+        "{}:",
         language
             .text_transcript()
             .unwrap_or("transcript".to_string())
@@ -356,29 +499,54 @@ async fn setup_listen_client<R: tauri::Runtime>(
         .api_key(api_key)
         .params(hypr_listener_interface::ListenParams {
             language,
+            language_detection,
+            vocabulary,
             static_prompt,
             ..Default::default()
         })
         .build())
 }
 
+pub(crate) async fn load_voice_matcher<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    session_id: &str,
+) -> Result<VoiceMatcher, crate::Error> {
+    use tauri_plugin_db::DatabasePluginExt;
+
+    let profiles = app.db_list_voice_profiles(None).await?;
+
+    let mut labels = HashMap::new();
+    let human_ids: HashSet<_> = profiles.iter().map(|p| p.human_id.clone()).collect();
+    for human_id in human_ids {
+        if let Some(name) = app.db_get_human(&human_id).await?.and_then(|h| h.full_name) {
+            labels.insert(human_id, name);
+        }
+    }
+
+    Ok(VoiceMatcher::new(session_id, profiles, labels))
+}
+
 async fn update_session<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     session_id: impl Into<String>,
     words: Vec<hypr_listener_interface::Word>,
+    language: Option<hypr_language::Language>,
+    turns: &[SpeakerTurn],
+    known: &HashMap<usize, SpeakerIdentity>,
 ) -> Result<Vec<hypr_listener_interface::Word>, crate::Error> {
     use tauri_plugin_db::DatabasePluginExt;
 
-    // TODO: not ideal. We might want to only do "update" everywhere instead of upserts.
-    // We do this because it is highly likely that the session fetched in the listener is stale (session can be updated on the React side).
-    let mut session = app
-        .db_get_session(session_id)
+    // Only words and language, in one transaction, since the session can be edited on the React side meanwhile.
+    let session = app
+        .db_update_session_transcript(session_id, language, |stored| {
+            // Words of the two channels arrive independently, so they are merged by time.
+            merge_words(stored, words);
+            // Diarization lags behind transcription, so earlier words may only now have a turn.
+            crate::diarization::assign_speakers(stored, turns, known);
+        })
         .await?
         .ok_or(crate::Error::NoneSession)?;
 
-    session.words.extend(words);
-    app.db_upsert_session(session.clone()).await.unwrap();
-
     Ok(session.words)
 }
 
@@ -493,7 +661,13 @@ impl Session {
             }
         }
 
+        let session_id = self.session_id.clone();
         self.teardown_resources().await;
+
+        // After teardown, which drops the writer and so finalizes the recording.
+        if let Some(session_id) = session_id {
+            crate::offline_diarization::spawn(&self.app, session_id).await;
+        }
     }
 
     #[action]
@@ -4,10 +4,12 @@ use tokio::sync::Mutex;
 
 mod client;
 mod commands;
+mod diarization;
 mod error;
 mod events;
 mod ext;
 mod fsm;
+mod offline_diarization;
 
 pub use client::*;
 pub use error::*;
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::Manager;
+use tauri_plugin_connector::{Connection, ConnectionSTT, ConnectorPluginExt};
+use tauri_plugin_db::DatabasePluginExt;
+use tauri_plugin_task::{TaskCtx, TaskPluginExt};
+use tokio::sync::mpsc;
+
+use hypr_pyannote::cloud::DiarizationSegment;
+use hypr_pyannote::local::diarization::{Diarization, OfflineDiarizer, SpeakerTurn};
+
+// Audio diarized per step of the task.
+const STEP_SECONDS: u32 = 10;
+
+// Same pace as `PyannoteClient::diarize`, doubling the interval after each poll.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_POLLS: usize = 60;
+
+// Follows the STT connection, so recordings only leave the device when transcription already does.
+enum Backend {
+    Local,
+    Cloud(Connection),
+}
+
+// Diarizes the whole recording of a session once it ended, and relabels its words with the result.
+// Returns the id of the task reporting progress, or `None` if the session wasn't recorded.
+pub async fn spawn<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    session_id: impl Into<String>,
+) -> Option<String> {
+    let session_id = session_id.into();
+    let path = app
+        .path()
+        .app_data_dir()
+        .ok()?
+        .join(&session_id)
+        .join("audio.wav");
+
+    let backend = match app.get_stt_connection().await {
+        Ok(ConnectionSTT::HyprCloud(conn)) => Backend::Cloud(conn),
+        Ok(ConnectionSTT::HyprLocal(_)) => Backend::Local,
+        Err(e) => {
+            tracing::error!("offline_diarization_backend_error: {:?}", e);
+            return None;
+        }
+    };
+
+    let steps = match &backend {
+        Backend::Local => {
+            let reader = hound::WavReader::open(&path).ok()?;
+            let step = reader.spec().sample_rate * STEP_SECONDS;
+            // One more to relabel the words.
+            reader.duration().div_ceil(step) + 1
+        }
+        Backend::Cloud(_) => {
+            if !path.exists() {
+                return None;
+            }
+            // Upload, diarize, relabel.
+            3
+        }
+    };
+    let backend = Arc::new(backend);
+
+    let id = app.spawn_task(steps, {
+        let app = app.clone();
+
+        move |mut ctx| {
+            let app = app.clone();
+            let session_id = session_id.clone();
+            let path = path.clone();
+            let backend = backend.clone();
+
+            async move {
+                match run(&app, &session_id, &backend, path, &mut ctx).await {
+                    Ok(()) => ctx.complete(),
+                    Err(e) => {
+                        tracing::error!("offline_diarization_error: {:?}", e);
+                        ctx.fail(e.to_string())
+                    }
+                }
+            }
+        }
+    });
+
+    Some(id)
+}
+
+async fn run<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    session_id: &str,
+    backend: &Backend,
+    path: PathBuf,
+    ctx: &mut TaskCtx<R>,
+) -> Result<(), crate::Error> {
+    let diarization = match backend {
+        Backend::Local => diarize_local(path, ctx).await?,
+        Backend::Cloud(conn) => diarize_cloud(conn, path, ctx).await?,
+    };
+    let Some(diarization) = diarization else {
+        return Ok(());
+    };
+
+    // The new speaker indices make suggestions from the live session meaningless.
+    let mut voice_matcher = crate::fsm::load_voice_matcher(app, session_id).await?;
+    let suggestions = voice_matcher.update(&diarization.speakers);
+    app.db_clear_speaker_suggestions(session_id).await?;
+    for suggestion in suggestions {
+        app.db_upsert_speaker_suggestion(suggestion).await?;
+    }
+
+    app.db_update_session_transcript(session_id, None, |words| {
+        crate::diarization::relabel_speakers(words, &diarization.turns, voice_matcher.known());
+    })
+    .await?
+    .ok_or(crate::Error::NoneSession)?;
+    let _ = ctx.advance(());
+
+    Ok(())
+}
+
+// Returns `None` if the task was cancelled.
+async fn diarize_local<R: tauri::Runtime>(
+    path: PathBuf,
+    ctx: &mut TaskCtx<R>,
+) -> Result<Option<Diarization>, crate::Error> {
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let handle = tokio::task::spawn_blocking(move || diarize(path, progress_tx));
+
+    while progress_rx.recv().await.is_some() {
+        // Dropping `progress_rx` stops the diarizer.
+        if ctx.is_cancelled() {
+            return Ok(None);
+        }
+        let _ = ctx.advance(());
+    }
+
+    handle.await.map_err(anyhow::Error::from)?
+}
+
+#[derive(serde::Deserialize)]
+struct UploadResponse {
+    audio_url: String,
+    upload_url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SubmitResponse {
+    job_id: String,
+}
+
+// Through `/api/desktop/diarize`, which keeps the pyannote API key on the server.
+// Returns `None` if the task was cancelled.
+async fn diarize_cloud<R: tauri::Runtime>(
+    conn: &Connection,
+    path: PathBuf,
+    ctx: &mut TaskCtx<R>,
+) -> Result<Option<Diarization>, crate::Error> {
+    let client = reqwest::Client::new();
+    let api_base = conn.api_base.trim_end_matches('/');
+    let authorized = |req: reqwest::RequestBuilder| match &conn.api_key {
+        Some(api_key) => req.bearer_auth(api_key),
+        None => req,
+    };
+
+    let UploadResponse {
+        audio_url,
+        upload_url,
+    } = authorized(client.post(format!("{}/api/desktop/diarize/upload", api_base)))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    client
+        .put(upload_url)
+        .body(tokio::fs::read(path).await?)
+        .send()
+        .await?
+        .error_for_status()?;
+    if ctx.is_cancelled() {
+        return Ok(None);
+    }
+    let _ = ctx.advance(());
+
+    let SubmitResponse { job_id } =
+        authorized(client.post(format!("{}/api/desktop/diarize", api_base)))
+            .json(&serde_json::json!({ "audio_url": audio_url }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+    let result_url = format!("{}/api/desktop/diarize/{}", api_base, job_id);
+    let mut delay = MIN_POLL_INTERVAL;
+    let mut segments = None;
+    for _ in 0..MAX_POLLS {
+        if ctx.is_cancelled() {
+            return Ok(None);
+        }
+
+        let res = authorized(client.get(&result_url))
+            .send()
+            .await?
+            .error_for_status()?;
+        // `202 Accepted` until the job is done.
+        if res.status() != reqwest::StatusCode::ACCEPTED {
+            segments = Some(res.json::<Vec<DiarizationSegment>>().await?);
+            break;
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_POLL_INTERVAL);
+    }
+    let segments = segments.ok_or(crate::Error::DiarizationTimeout(job_id))?;
+    let _ = ctx.advance(());
+
+    Ok(Some(Diarization {
+        turns: to_turns(&segments),
+        // Jobs don't return embeddings, so there is nothing to match against voice profiles.
+        speakers: vec![],
+    }))
+}
+
+// Speakers like `SPEAKER_00` become indices in order of first appearance, as `assign_speakers` does.
+fn to_turns(segments: &[DiarizationSegment]) -> Vec<SpeakerTurn> {
+    let mut ordered: Vec<&DiarizationSegment> = segments.iter().collect();
+    ordered.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let mut indices: HashMap<&str, usize> = HashMap::new();
+    ordered
+        .into_iter()
+        .map(|s| {
+            let next = indices.len();
+            SpeakerTurn {
+                start_ms: (s.start * 1000.0) as u64,
+                end_ms: (s.end * 1000.0) as u64,
+                speaker: *indices.entry(s.speaker.as_str()).or_insert(next),
+            }
+        })
+        .collect()
+}
+
+// Sends a progress tick per step. Returns `None` if nobody listens to them anymore.
+fn diarize(
+    path: PathBuf,
+    progress_tx: mpsc::UnboundedSender<()>,
+) -> Result<Option<Diarization>, crate::Error> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+    let step = (spec.sample_rate * STEP_SECONDS) as usize * channels;
+
+    let mut diarizer = OfflineDiarizer::new(spec.sample_rate)?;
+    let mut samples = reader.samples::<f32>();
+
+    loop {
+        let chunk = samples
+            .by_ref()
+            .take(step)
+            .collect::<Result<Vec<f32>, _>>()?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        // Every channel of the recording holds the same mix.
+        let mono: Vec<f32> = chunk.into_iter().step_by(channels).collect();
+        diarizer.push(&mono)?;
+
+        if progress_tx.send(()).is_err() {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(diarizer.finish()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_turns() {
+        let segment = |speaker: &str, start: f32, end: f32| DiarizationSegment {
+            speaker: speaker.to_string(),
+            start,
+            end,
+        };
+
+        let turns = to_turns(&[
+            segment("SPEAKER_01", 2.0, 3.5),
+            segment("SPEAKER_00", 3.5, 4.0),
+            segment("SPEAKER_01", 0.25, 1.0),
+        ]);
+
+        assert_eq!(
+            turns
+                .iter()
+                .map(|t| (t.start_ms, t.end_ms, t.speaker))
+                .collect::<Vec<_>>(),
+            vec![(250, 1000, 0), (2000, 3500, 0), (3500, 4000, 1)]
+        );
+    }
+}
@@ -1,10 +1,18 @@
 const COMMANDS: &[&str] = &[
+    "list_supported_models",
+    "get_current_model",
+    "set_current_model",
     "is_server_running",
     "is_model_downloaded",
     "is_model_downloading",
     "download_model",
+    "delete_model",
     "start_server",
     "stop_server",
+    "is_embedding_model_downloaded",
+    "download_embedding_model",
+    "embed_documents",
+    "embed_query",
 ];
 
 fn main() {
@@ -7,26 +7,47 @@
 
 
 export const commands = {
-async listSupportedModels() : Promise<SupportedModel[]> {
+async listSupportedModels() : Promise<ModelInfo[]> {
     return await TAURI_INVOKE("plugin:local-llm|list_supported_models");
 },
+async getCurrentModel() : Promise<SupportedModel> {
+    return await TAURI_INVOKE("plugin:local-llm|get_current_model");
+},
+async setCurrentModel(model: SupportedModel) : Promise<null> {
+    return await TAURI_INVOKE("plugin:local-llm|set_current_model", { model });
+},
 async isServerRunning() : Promise<boolean> {
     return await TAURI_INVOKE("plugin:local-llm|is_server_running");
 },
-async isModelDownloaded() : Promise<boolean> {
-    return await TAURI_INVOKE("plugin:local-llm|is_model_downloaded");
+async isModelDownloaded(model: SupportedModel) : Promise<boolean> {
+    return await TAURI_INVOKE("plugin:local-llm|is_model_downloaded", { model });
 },
-async isModelDownloading() : Promise<boolean> {
-    return await TAURI_INVOKE("plugin:local-llm|is_model_downloading");
+async isModelDownloading(model: SupportedModel) : Promise<boolean> {
+    return await TAURI_INVOKE("plugin:local-llm|is_model_downloading", { model });
 },
-async downloadModel(channel: TAURI_CHANNEL<number>) : Promise<null> {
-    return await TAURI_INVOKE("plugin:local-llm|download_model", { channel });
+async downloadModel(model: SupportedModel, channel: TAURI_CHANNEL<number>) : Promise<null> {
+    return await TAURI_INVOKE("plugin:local-llm|download_model", { model, channel });
+},
+async deleteModel(model: SupportedModel) : Promise<null> {
+    return await TAURI_INVOKE("plugin:local-llm|delete_model", { model });
 },
 async startServer() : Promise<string> {
     return await TAURI_INVOKE("plugin:local-llm|start_server");
 },
 async stopServer() : Promise<null> {
     return await TAURI_INVOKE("plugin:local-llm|stop_server");
+},
+async isEmbeddingModelDownloaded() : Promise<boolean> {
+    return await TAURI_INVOKE("plugin:local-llm|is_embedding_model_downloaded");
+},
+async downloadEmbeddingModel(channel: TAURI_CHANNEL<number>) : Promise<null> {
+    return await TAURI_INVOKE("plugin:local-llm|download_embedding_model", { channel });
+},
+async embedDocuments(texts: string[]) : Promise<number[][]> {
+    return await TAURI_INVOKE("plugin:local-llm|embed_documents", { texts });
+},
+async embedQuery(query: string) : Promise<number[]> {
+    return await TAURI_INVOKE("plugin:local-llm|embed_query", { query });
 }
 }
 
@@ -40,7 +61,8 @@ async stopServer() : Promise<null> {
 
 /** user-defined types **/
 
-export type SupportedModel = "Llama3p2_3bQ4"
+export type ModelInfo = { model: SupportedModel; name: string; size: number; ram_required: number; context_size: number }
+export type SupportedModel = "Llama3p2_3bQ4" | "Llama3p2_1bQ8" | "Qwen2p5_3bQ4"
 export type TAURI_CHANNEL<TSend> = null
 
 /** tauri-specta globals **/
@@ -4,8 +4,30 @@ use tauri::ipc::Channel;
 
 #[tauri::command]
 #[specta::specta]
-pub async fn list_supported_models() -> Result<Vec<crate::local::SupportedModel>, String> {
-    Ok(crate::local::SUPPORTED_MODELS.to_vec())
+pub async fn list_supported_models() -> Result<Vec<crate::local::ModelInfo>, String> {
+    Ok(crate::local::SUPPORTED_MODELS
+        .iter()
+        .map(|model| model.info())
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_current_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<crate::local::SupportedModel, String> {
+    app.current_model().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_current_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    model: crate::local::SupportedModel,
+) -> Result<(), String> {
+    app.set_current_model(model)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -18,25 +40,41 @@ pub async fn is_server_running<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> b
 #[specta::specta]
 pub async fn is_model_downloaded<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
+    model: crate::local::SupportedModel,
 ) -> Result<bool, String> {
-    app.is_model_downloaded().await.map_err(|e| e.to_string())
+    app.is_model_downloaded(&model)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[specta::specta]
 pub async fn is_model_downloading<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
+    model: crate::local::SupportedModel,
 ) -> Result<bool, String> {
-    Ok(app.is_model_downloading().await)
+    Ok(app.is_model_downloading(&model).await)
 }
 
 #[tauri::command]
 #[specta::specta]
 pub async fn download_model<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
+    model: crate::local::SupportedModel,
     channel: Channel<i8>,
 ) -> Result<(), String> {
-    app.download_model(channel).await.map_err(|e| e.to_string())
+    app.download_model(model, channel)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    model: crate::local::SupportedModel,
+) -> Result<(), String> {
+    app.delete_model(&model).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -50,3 +88,40 @@ pub async fn start_server<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> Result
 pub async fn stop_server<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> Result<(), String> {
     app.stop_server().await.map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn is_embedding_model_downloaded<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<bool, String> {
+    Ok(app.is_embedding_model_downloaded().await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn download_embedding_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    channel: Channel<i8>,
+) -> Result<(), String> {
+    app.download_embedding_model(channel)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn embed_documents<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    texts: Vec<String>,
+) -> Result<Vec<Vec<f32>>, String> {
+    app.embed_documents(texts).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn embed_query<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    query: String,
+) -> Result<Vec<f32>, String> {
+    app.embed_query(query).await.map_err(|e| e.to_string())
+}
@@ -9,6 +9,8 @@ pub enum Error {
     #[error(transparent)]
     HyprFileError(#[from] hypr_file::Error),
     #[error(transparent)]
+    HyprGgufError(#[from] hypr_gguf::Error),
+    #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
@@ -16,6 +18,8 @@ pub enum Error {
     StoreError(#[from] tauri_plugin_store2::Error),
     #[error("Model not downloaded")]
     ModelNotDownloaded,
+    #[error("Invalid model: {0}")]
+    InvalidModel(String),
 }
 
 impl Serialize for Error {
@@ -3,22 +3,45 @@ use std::future::Future;
 use tauri::{ipc::Channel, Manager, Runtime};
 use tauri_plugin_store2::StorePluginExt;
 
-use crate::local::{ModelManager, SupportedModel};
-use hypr_file::{download_file_with_callback, DownloadProgress};
+use crate::local::{ModelManager, SupportedModel, EMBEDDING_MODEL};
+use hypr_file::{download_file_with_manifest, DownloadProgress};
 
 pub trait LocalLlmPluginExt<R: Runtime> {
     fn local_llm_store(&self) -> tauri_plugin_store2::ScopedStore<R, crate::StoreKey>;
     fn current_model(&self) -> impl Future<Output = Result<SupportedModel, crate::Error>>;
+    fn set_current_model(
+        &self,
+        model: SupportedModel,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
     fn api_base(&self) -> impl Future<Output = Option<String>>;
-    fn is_model_downloading(&self) -> impl Future<Output = bool>;
-    fn is_model_downloaded(&self) -> impl Future<Output = Result<bool, crate::Error>>;
+    fn is_model_downloading(&self, model: &SupportedModel) -> impl Future<Output = bool>;
+    fn is_model_downloaded(
+        &self,
+        model: &SupportedModel,
+    ) -> impl Future<Output = Result<bool, crate::Error>>;
     fn is_server_running(&self) -> impl Future<Output = bool>;
     fn download_model(
         &self,
+        model: SupportedModel,
         channel: Channel<i8>,
     ) -> impl Future<Output = Result<(), crate::Error>>;
+    fn delete_model(
+        &self,
+        model: &SupportedModel,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
     fn start_server(&self) -> impl Future<Output = Result<String, crate::Error>>;
     fn stop_server(&self) -> impl Future<Output = Result<(), crate::Error>>;
+
+    fn is_embedding_model_downloaded(&self) -> impl Future<Output = bool>;
+    fn download_embedding_model(
+        &self,
+        channel: Channel<i8>,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
+    fn embed_documents(
+        &self,
+        texts: Vec<String>,
+    ) -> impl Future<Output = Result<Vec<Vec<f32>>, crate::Error>>;
+    fn embed_query(&self, query: String) -> impl Future<Output = Result<Vec<f32>, crate::Error>>;
 }
 
 impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
@@ -35,6 +58,20 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
         Ok(stored.unwrap_or(SupportedModel::Llama3p2_3bQ4))
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn set_current_model(&self, model: SupportedModel) -> Result<(), crate::Error> {
+        let store = self.local_llm_store();
+        store.set(crate::StoreKey::Model, model.clone())?;
+
+        let state = self.state::<crate::SharedState>();
+        let s = state.lock().await;
+        if let Some(model_manager) = &s.model_manager {
+            model_manager.set_model(model).await;
+        }
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip_all)]
     async fn api_base(&self) -> Option<String> {
         let state = self.state::<crate::SharedState>();
@@ -43,16 +80,16 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn is_model_downloading(&self) -> bool {
+    async fn is_model_downloading(&self, model: &SupportedModel) -> bool {
         let state = self.state::<crate::SharedState>();
         let s = state.lock().await;
-        s.download_task.is_some()
+        s.download_task
+            .get(model)
+            .is_some_and(|task| !task.is_finished())
     }
 
     #[tracing::instrument(skip_all)]
-    async fn is_model_downloaded(&self) -> Result<bool, crate::Error> {
-        let model = self.current_model().await?;
-
+    async fn is_model_downloaded(&self, model: &SupportedModel) -> Result<bool, crate::Error> {
         let data_dir = self.path().app_data_dir().unwrap();
         let path = model.model_path(data_dir);
 
@@ -60,8 +97,8 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
             return Ok(false);
         }
 
-        let size = hypr_file::file_size(&path)?;
-        Ok(size == model.model_size())
+        // `llm.gguf` used to be written in place, so it can be a partial download.
+        Ok(model.validate(&path).is_ok())
     }
 
     #[tracing::instrument(skip_all)]
@@ -72,41 +109,92 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn download_model(&self, channel: Channel<i8>) -> Result<(), crate::Error> {
-        let model = self.current_model().await?;
+    async fn download_model(
+        &self,
+        model: SupportedModel,
+        channel: Channel<i8>,
+    ) -> Result<(), crate::Error> {
         let data_dir = self.path().app_data_dir().unwrap();
 
         let path = model.model_path(data_dir);
         let url = model.model_url().to_string();
 
-        let task = tokio::spawn(async move {
-            let callback = |progress: DownloadProgress| match progress {
-                DownloadProgress::Started => {
-                    let _ = channel.send(0);
-                }
-                DownloadProgress::Progress(downloaded, total_size) => {
-                    let percent = (downloaded as f64 / total_size as f64) * 100.0;
-                    let _ = channel.send(percent as i8);
-                }
-                DownloadProgress::Finished => {
-                    let _ = channel.send(100);
+        let state = self.state::<crate::SharedState>();
+        let mut s = state.lock().await;
+
+        // Awaited so the aborted download is done with the partial file before the new one opens it.
+        if let Some(task) = s.download_task.remove(&model) {
+            task.abort();
+            let _ = task.await;
+        }
+
+        let task = tokio::spawn({
+            let model = model.clone();
+
+            async move {
+                let callback = |progress: DownloadProgress| match progress {
+                    DownloadProgress::Started => {
+                        let _ = channel.send(0);
+                    }
+                    DownloadProgress::Progress(downloaded, total_size) => {
+                        let percent = (downloaded as f64 / total_size as f64) * 100.0;
+                        let _ = channel.send(percent as i8);
+                    }
+                    DownloadProgress::Finished => {}
+                };
+
+                let result = async {
+                    download_file_with_manifest(url, &path, &model.manifest(), callback).await?;
+
+                    // Already moved into place, so an invalid model is removed rather than resumed.
+                    model.validate(&path).inspect_err(|_| {
+                        let _ = std::fs::remove_file(&path);
+                    })
                 }
-            };
+                .await;
 
-            if let Err(e) = download_file_with_callback(url, path, callback).await {
-                tracing::error!("model_download_error: {}", e);
-                let _ = channel.send(-1);
+                match result {
+                    Ok(_) => {
+                        let _ = channel.send(100);
+                    }
+                    Err(e) => {
+                        tracing::error!("model_download_error: {}", e);
+                        let _ = channel.send(-1);
+                    }
+                }
             }
         });
+        s.download_task.insert(model, task);
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn delete_model(&self, model: &SupportedModel) -> Result<(), crate::Error> {
+        let data_dir = self.path().app_data_dir().unwrap();
+        let path = model.model_path(data_dir);
 
         {
             let state = self.state::<crate::SharedState>();
             let mut s = state.lock().await;
 
-            if let Some(task) = s.download_task.take() {
+            // Otherwise it could still write to the partial file after it is removed below.
+            if let Some(task) = s.download_task.remove(model) {
                 task.abort();
+                let _ = task.await;
+            }
+
+            if let Some(model_manager) = &s.model_manager {
+                if model_manager.current_model().await == *model {
+                    model_manager.unload_model().await;
+                }
+            }
+        }
+
+        for path in [hypr_file::partial_path(&path), path] {
+            if path.exists() {
+                std::fs::remove_file(path)?;
             }
-            s.download_task = Some(task);
         }
 
         Ok(())
@@ -116,10 +204,7 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
     async fn start_server(&self) -> Result<String, crate::Error> {
         let state = self.state::<crate::SharedState>();
 
-        let model_manager = {
-            let s = state.lock().await;
-            ModelManager::new(s.model_path.clone())
-        };
+        let model_manager = model_manager(self).await?;
 
         let server = crate::server::run_server(model_manager).await?;
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -145,4 +230,94 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
         }
         Ok(())
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn is_embedding_model_downloaded(&self) -> bool {
+        let state = self.state::<crate::SharedState>();
+        let s = state.lock().await;
+        s.embedding_model_path.exists()
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn download_embedding_model(&self, channel: Channel<i8>) -> Result<(), crate::Error> {
+        let state = self.state::<crate::SharedState>();
+        let mut s = state.lock().await;
+
+        let path = s.embedding_model_path.clone();
+        let url = EMBEDDING_MODEL.model_url().to_string();
+
+        if let Some(task) = s.embedding_download_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+
+        let task = tokio::spawn(async move {
+            let callback = |progress: DownloadProgress| match progress {
+                DownloadProgress::Started => {
+                    let _ = channel.send(0);
+                }
+                DownloadProgress::Progress(downloaded, total_size) => {
+                    let percent = (downloaded as f64 / total_size as f64) * 100.0;
+                    let _ = channel.send(percent as i8);
+                }
+                DownloadProgress::Finished => {
+                    let _ = channel.send(100);
+                }
+            };
+
+            let result = async {
+                download_file_with_manifest(url, &path, &EMBEDDING_MODEL.manifest(), callback)
+                    .await?;
+
+                EMBEDDING_MODEL.validate(&path).inspect_err(|_| {
+                    let _ = std::fs::remove_file(&path);
+                })
+            }
+            .await;
+
+            if let Err(e) = result {
+                tracing::error!("embedding_model_download_error: {}", e);
+                let _ = channel.send(-1);
+            }
+        });
+        s.embedding_download_task = Some(task);
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn embed_documents(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, crate::Error> {
+        let embedder = model_manager(self).await?.get_embedder().await?;
+
+        let texts = texts
+            .into_iter()
+            .map(|text| format!("{}{}", EMBEDDING_MODEL.document_prefix(), text))
+            .collect();
+
+        Ok(embedder.embed(texts).await?)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn embed_query(&self, query: String) -> Result<Vec<f32>, crate::Error> {
+        let embedder = model_manager(self).await?.get_embedder().await?;
+
+        let query = format!("{}{}", EMBEDDING_MODEL.query_prefix(), query);
+        let mut embeddings = embedder.embed(vec![query]).await?;
+
+        Ok(embeddings.remove(0))
+    }
+}
+
+async fn model_manager<R: Runtime, T: Manager<R>>(app: &T) -> Result<ModelManager, crate::Error> {
+    let model = app.current_model().await?;
+    let data_dir = app.path().app_data_dir().unwrap();
+
+    let state = app.state::<crate::SharedState>();
+    let mut s = state.lock().await;
+
+    if s.model_manager.is_none() {
+        s.model_manager = Some(ModelManager::new(data_dir, model));
+    }
+
+    Ok(s.model_manager.clone().unwrap())
 }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tauri::{Manager, Wry};
 use tokio::sync::Mutex;
 
@@ -22,17 +22,22 @@ pub type SharedState = std::sync::Arc<tokio::sync::Mutex<State>>;
 pub struct State {
     pub api_base: Option<String>,
     pub server: Option<crate::server::ServerHandle>,
-    pub model_path: std::path::PathBuf,
-    pub download_task: Option<tokio::task::JoinHandle<()>>,
+    pub embedding_model_path: std::path::PathBuf,
+    // Shared by the server and embedding commands, so models are only loaded once.
+    pub model_manager: Option<crate::local::ModelManager>,
+    pub download_task: HashMap<crate::local::SupportedModel, tokio::task::JoinHandle<()>>,
+    pub embedding_download_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl State {
-    pub fn new(model_path: std::path::PathBuf) -> Self {
+    pub fn new(embedding_model_path: std::path::PathBuf) -> Self {
         Self {
             api_base: None,
             server: None,
-            model_path,
-            download_task: None,
+            embedding_model_path,
+            model_manager: None,
+            download_task: HashMap::new(),
+            embedding_download_task: None,
         }
     }
 }
@@ -42,12 +47,19 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
         .plugin_name(PLUGIN_NAME)
         .commands(tauri_specta::collect_commands![
             commands::list_supported_models,
+            commands::get_current_model::<Wry>,
+            commands::set_current_model::<Wry>,
             commands::is_server_running::<Wry>,
             commands::is_model_downloaded::<Wry>,
             commands::is_model_downloading::<Wry>,
             commands::download_model::<Wry>,
+            commands::delete_model::<Wry>,
             commands::start_server::<Wry>,
             commands::stop_server::<Wry>,
+            commands::is_embedding_model_downloaded::<Wry>,
+            commands::download_embedding_model::<Wry>,
+            commands::embed_documents::<Wry>,
+            commands::embed_query::<Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
 }
@@ -58,8 +70,10 @@ pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
     tauri::plugin::Builder::new(PLUGIN_NAME)
         .invoke_handler(specta_builder.invoke_handler())
         .setup(|app, _api| {
-            let model_path = app.path().app_data_dir().unwrap().join("llm.gguf");
-            let state: SharedState = Arc::new(Mutex::new(State::new(model_path)));
+            let data_dir = app.path().app_data_dir().unwrap();
+            let embedding_model_path = local::EMBEDDING_MODEL.model_path(data_dir);
+
+            let state: SharedState = Arc::new(Mutex::new(State::new(embedding_model_path)));
             app.manage(state);
             Ok(())
         })
@@ -73,7 +87,8 @@ mod test {
     use async_openai::types::{
         ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
         ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequest,
-        CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+        CreateChatCompletionResponse, CreateChatCompletionStreamResponse, CreateEmbeddingRequest,
+        CreateEmbeddingResponse, EmbeddingInput,
     };
     use futures_util::StreamExt;
 
@@ -96,6 +111,37 @@ mod test {
         builder.plugin(init()).build(ctx).unwrap()
     }
 
+    #[tokio::test]
+    #[ignore]
+    // cargo test test_delete_model -p tauri-plugin-local-llm -- --ignored --nocapture
+    async fn test_delete_model() {
+        let mut ctx = tauri::test::mock_context(tauri::test::noop_assets());
+        ctx.config_mut().identifier = "com.hyprnote.test".to_string();
+        let app = tauri::test::mock_builder()
+            .plugin(init())
+            .build(ctx)
+            .unwrap();
+
+        // Linked from the dev app, whose copy stays in place.
+        let model = local::SupportedModel::Llama3p2_3bQ4;
+        let data_dir = app.path().app_data_dir().unwrap();
+        let path = model.model_path(&data_dir);
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::hard_link(
+            model.model_path(dirs::data_dir().unwrap().join("com.hyprnote.dev")),
+            &path,
+        )
+        .unwrap();
+
+        let manager = local::ModelManager::new(&data_dir, model.clone());
+        app.state::<SharedState>().lock().await.model_manager = Some(manager.clone());
+        let llama = manager.get_model().await.unwrap();
+
+        app.delete_model(&model).await.unwrap();
+        assert!(!path.exists());
+        assert_eq!(Arc::strong_count(&llama), 1);
+    }
+
     fn shared_request() -> CreateChatCompletionRequest {
         CreateChatCompletionRequest {
             messages: vec![ChatCompletionRequestMessage::User(
@@ -290,4 +336,39 @@ mod test {
             .chars()
             .all(|c| c.is_alphabetic() || c.is_whitespace()));
     }
+
+    #[tokio::test]
+    #[ignore]
+    // cargo test test_embeddings -p tauri-plugin-local-llm -- --ignored --nocapture
+    async fn test_embeddings() {
+        let app = create_app(tauri::test::mock_builder());
+        app.start_server().await.unwrap();
+        let api_base = app.api_base().await.unwrap();
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/v1/embeddings", api_base))
+            .json(&CreateEmbeddingRequest {
+                model: "local".to_string(),
+                input: EmbeddingInput::StringArray(vec![
+                    "search_document: Seoul is the capital of South Korea.".to_string(),
+                    "search_document: Paris is the capital of France.".to_string(),
+                ]),
+                encoding_format: None,
+                user: None,
+                dimensions: None,
+            })
+            .send()
+            .await
+            .unwrap();
+
+        let data = response.json::<CreateEmbeddingResponse>().await.unwrap();
+        assert_eq!(data.data.len(), 2);
+        assert!(data.usage.prompt_tokens > 0);
+
+        let query = app
+            .embed_query("What is the capital of South Korea?".to_string())
+            .await
+            .unwrap();
+        assert_eq!(query.len(), data.data[0].embedding.len());
+    }
 }
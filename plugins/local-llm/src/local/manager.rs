@@ -1,14 +1,24 @@
 use std::sync::Arc;
 use tokio::sync::{watch, Mutex};
 
+use super::{SupportedModel, EMBEDDING_MODEL};
+
 #[derive(Clone)]
 pub struct ModelManager {
-    model_path: std::path::PathBuf,
-    model: Arc<Mutex<Option<Arc<hypr_llama::Llama>>>>,
+    data_dir: std::path::PathBuf,
+    embedding_model_path: std::path::PathBuf,
+    model: Arc<Mutex<ModelSlot>>,
+    embedder: Arc<Mutex<Option<Arc<hypr_llama::LlamaEmbedder>>>>,
     last_activity: Arc<Mutex<Option<tokio::time::Instant>>>,
     _drop_guard: Arc<DropGuard>,
 }
 
+// Which model to serve, and the loaded instance once requested.
+struct ModelSlot {
+    model: SupportedModel,
+    llama: Option<Arc<hypr_llama::Llama>>,
+}
+
 struct DropGuard {
     shutdown_tx: watch::Sender<()>,
 }
@@ -20,12 +30,15 @@ impl Drop for DropGuard {
 }
 
 impl ModelManager {
-    pub fn new(model_path: impl Into<std::path::PathBuf>) -> Self {
+    pub fn new(data_dir: impl Into<std::path::PathBuf>, model: SupportedModel) -> Self {
         let (shutdown_tx, shutdown_rx) = watch::channel(());
+        let data_dir = data_dir.into();
 
         let manager = Self {
-            model_path: model_path.into(),
-            model: Arc::new(tokio::sync::Mutex::new(None)),
+            embedding_model_path: EMBEDDING_MODEL.model_path(&data_dir),
+            data_dir,
+            model: Arc::new(tokio::sync::Mutex::new(ModelSlot { model, llama: None })),
+            embedder: Arc::new(tokio::sync::Mutex::new(None)),
             last_activity: Arc::new(tokio::sync::Mutex::new(None)),
             _drop_guard: Arc::new(DropGuard { shutdown_tx }),
         };
@@ -43,16 +56,67 @@ impl ModelManager {
 
         let mut guard = self.model.lock().await;
 
+        match guard.llama.as_ref() {
+            Some(llama) => Ok(llama.clone()),
+            None => {
+                let model_path = guard.model.model_path(&self.data_dir);
+                if !model_path.exists() {
+                    return Err(crate::Error::ModelNotDownloaded);
+                }
+
+                let llama = Arc::new(
+                    hypr_llama::Llama::builder()
+                        .model_path(model_path)
+                        .context_size(guard.model.context_size())
+                        .chat_template(hypr_gguf::ChatTemplate::TemplateKey(
+                            guard.model.chat_template(),
+                        ))
+                        .build()?,
+                );
+                guard.llama = Some(llama.clone());
+                Ok(llama)
+            }
+        }
+    }
+
+    pub async fn current_model(&self) -> SupportedModel {
+        self.model.lock().await.model.clone()
+    }
+
+    // Requests already running keep the previous model alive until they finish.
+    // The next one loads `model`, so the server never needs a restart.
+    pub async fn set_model(&self, model: SupportedModel) {
+        let mut guard = self.model.lock().await;
+
+        if guard.model != model {
+            guard.model = model;
+            guard.llama = None;
+        }
+    }
+
+    // Releases the file so it can be deleted. Loaded again on the next request.
+    pub async fn unload_model(&self) {
+        self.model.lock().await.llama = None;
+    }
+
+    pub async fn get_embedder(
+        &self,
+    ) -> Result<std::sync::Arc<hypr_llama::LlamaEmbedder>, crate::Error> {
+        self.update_activity().await;
+
+        let mut guard = self.embedder.lock().await;
+
         match guard.as_ref() {
-            Some(model) => Ok(model.clone()),
+            Some(embedder) => Ok(embedder.clone()),
             None => {
-                if !self.model_path.exists() {
+                if !self.embedding_model_path.exists() {
                     return Err(crate::Error::ModelNotDownloaded);
                 }
 
-                let model = Arc::new(hypr_llama::Llama::new(&self.model_path)?);
-                *guard = Some(model.clone());
-                Ok(model)
+                let embedder =
+                    Arc::new(hypr_llama::LlamaEmbedder::new(&self.embedding_model_path)?);
+                *guard = Some(embedder.clone());
+                Ok(embedder)
             }
         }
     }
@@ -62,6 +126,7 @@ impl ModelManager {
         let inactivity_threshold = std::time::Duration::from_secs(150);
 
         let model = self.model.clone();
+        let embedder = self.embedder.clone();
         let last_activity = self.last_activity.clone();
 
         let _handle = tokio::spawn(async move {
@@ -78,13 +143,14 @@ impl ModelManager {
                     _ = interval.tick() => {
                         let should_unload = match *last_activity.lock().await {
                             Some(last_time) if last_time.elapsed() > inactivity_threshold => {
-                                model.lock().await.is_some()
+                                model.lock().await.llama.is_some() || embedder.lock().await.is_some()
                             },
                             _ => false
                         };
 
                         if should_unload {
-                            *model.lock().await = None;
+                            model.lock().await.llama = None;
+                            *embedder.lock().await = None;
                         }
                     }
                 }
@@ -92,3 +158,39 @@ impl ModelManager {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_set_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = ModelManager::new(dir.path(), SupportedModel::Llama3p2_3bQ4);
+
+        manager.set_model(SupportedModel::Qwen2p5_3bQ4).await;
+        assert_eq!(manager.current_model().await, SupportedModel::Qwen2p5_3bQ4);
+        assert!(matches!(
+            manager.get_model().await,
+            Err(crate::Error::ModelNotDownloaded)
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    // cargo test test_set_model_unloads -p tauri-plugin-local-llm -- --ignored --nocapture
+    async fn test_set_model_unloads() {
+        let data_dir = dirs::data_dir().unwrap().join("com.hyprnote.dev");
+        let manager = ModelManager::new(data_dir, SupportedModel::Llama3p2_3bQ4);
+
+        // The manager holds the other reference while the model is loaded.
+        let llama = manager.get_model().await.unwrap();
+        assert_eq!(Arc::strong_count(&llama), 2);
+
+        manager.set_model(SupportedModel::Llama3p2_3bQ4).await;
+        assert_eq!(Arc::strong_count(&llama), 2);
+
+        manager.set_model(SupportedModel::Llama3p2_1bQ8).await;
+        assert_eq!(Arc::strong_count(&llama), 1);
+    }
+}
@@ -1,26 +1,207 @@
-pub static SUPPORTED_MODELS: &[SupportedModel; 1] = &[SupportedModel::Llama3p2_3bQ4];
+pub static SUPPORTED_MODELS: &[SupportedModel] = &[
+    SupportedModel::Llama3p2_3bQ4,
+    SupportedModel::Llama3p2_1bQ8,
+    SupportedModel::Qwen2p5_3bQ4,
+];
 
-#[derive(serde::Serialize, serde::Deserialize, specta::Type, Clone)]
+#[derive(Debug, Eq, Hash, PartialEq, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum SupportedModel {
     Llama3p2_3bQ4,
+    Llama3p2_1bQ8,
+    Qwen2p5_3bQ4,
+}
+
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct ModelInfo {
+    pub model: SupportedModel,
+    pub name: String,
+    pub size: u64,
+    pub ram_required: u64,
+    pub context_size: u32,
 }
 
 impl SupportedModel {
+    pub fn info(&self) -> ModelInfo {
+        ModelInfo {
+            model: self.clone(),
+            name: self.name().to_string(),
+            size: self.model_size(),
+            ram_required: self.ram_required(),
+            context_size: self.context_size(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SupportedModel::Llama3p2_3bQ4 => "Llama 3.2 3B (Q4_K_M)",
+            SupportedModel::Llama3p2_1bQ8 => "Llama 3.2 1B (Q8_0)",
+            SupportedModel::Qwen2p5_3bQ4 => "Qwen 2.5 3B (Q4_K_M)",
+        }
+    }
+
     pub fn model_path(&self, data_dir: impl Into<std::path::PathBuf>) -> std::path::PathBuf {
         match self {
+            // Kept from when this was the only model, so existing downloads are reused.
             SupportedModel::Llama3p2_3bQ4 => data_dir.into().join("llm.gguf"),
+            SupportedModel::Llama3p2_1bQ8 => data_dir.into().join("llama-3.2-1b-q8_0.gguf"),
+            SupportedModel::Qwen2p5_3bQ4 => data_dir.into().join("qwen-2.5-3b-q4_k_m.gguf"),
         }
     }
 
     pub fn model_url(&self) -> &str {
         match self {
-            SupportedModel::Llama3p2_3bQ4 => "https://storage.hyprnote.com/v0/lmstudio-community/Llama-3.2-3B-Instruct-GGUF/main/Llama-3.2-3B-Instruct-Q4_K_M.gguf"
+            SupportedModel::Llama3p2_3bQ4 => "https://storage.hyprnote.com/v0/lmstudio-community/Llama-3.2-3B-Instruct-GGUF/main/Llama-3.2-3B-Instruct-Q4_K_M.gguf",
+            SupportedModel::Llama3p2_1bQ8 => "https://huggingface.co/lmstudio-community/Llama-3.2-1B-Instruct-GGUF/resolve/main/Llama-3.2-1B-Instruct-Q8_0.gguf",
+            SupportedModel::Qwen2p5_3bQ4 => "https://huggingface.co/lmstudio-community/Qwen2.5-3B-Instruct-GGUF/resolve/main/Qwen2.5-3B-Instruct-Q4_K_M.gguf",
         }
     }
 
+    // Shown before downloading, and checked after it once `sha256` is pinned.
     pub fn model_size(&self) -> u64 {
         match self {
             SupportedModel::Llama3p2_3bQ4 => 2019377440,
+            SupportedModel::Llama3p2_1bQ8 => 1321082528,
+            SupportedModel::Qwen2p5_3bQ4 => 1929903264,
+        }
+    }
+
+    // Of the file at `model_url`. Pin it together with the exact `model_size`, both printed by `test_print_manifests`.
+    pub fn sha256(&self) -> Option<&str> {
+        match self {
+            SupportedModel::Llama3p2_3bQ4 => None,
+            SupportedModel::Llama3p2_1bQ8 => None,
+            SupportedModel::Qwen2p5_3bQ4 => None,
+        }
+    }
+
+    // Unpinned models are only checked by `validate`.
+    pub fn manifest(&self) -> hypr_file::Manifest {
+        match self.sha256() {
+            Some(sha256) => hypr_file::Manifest::builder()
+                .size(self.model_size())
+                .checksum(hypr_file::Checksum::Sha256(sha256.to_string())),
+            None => hypr_file::Manifest::default(),
+        }
+    }
+
+    // Used instead of guessing from the template embedded in the GGUF.
+    pub fn chat_template(&self) -> hypr_gguf::LlamaCppRegistry {
+        match self {
+            SupportedModel::Llama3p2_3bQ4 => hypr_gguf::LlamaCppRegistry::Llama3,
+            SupportedModel::Llama3p2_1bQ8 => hypr_gguf::LlamaCppRegistry::Llama3,
+            SupportedModel::Qwen2p5_3bQ4 => hypr_gguf::LlamaCppRegistry::ChatML,
+        }
+    }
+
+    // `general.architecture` in the GGUF header.
+    pub fn architecture(&self) -> &str {
+        match self {
+            SupportedModel::Llama3p2_3bQ4 => "llama",
+            SupportedModel::Llama3p2_1bQ8 => "llama",
+            SupportedModel::Qwen2p5_3bQ4 => "qwen2",
+        }
+    }
+
+    // Prompt and completion tokens together.
+    pub fn context_size(&self) -> u32 {
+        match self {
+            SupportedModel::Llama3p2_3bQ4 => 1024 * 9,
+            SupportedModel::Llama3p2_1bQ8 => 1024 * 9,
+            SupportedModel::Qwen2p5_3bQ4 => 1024 * 9,
+        }
+    }
+
+    // Weights plus KV cache for `context_size`, rounded up.
+    pub fn ram_required(&self) -> u64 {
+        match self {
+            SupportedModel::Llama3p2_3bQ4 => 4 * 1024 * 1024 * 1024,
+            SupportedModel::Llama3p2_1bQ8 => 2 * 1024 * 1024 * 1024,
+            SupportedModel::Qwen2p5_3bQ4 => 4 * 1024 * 1024 * 1024,
+        }
+    }
+
+    // Cheap enough to run on every check, unlike the SHA-256 in `manifest`.
+    pub fn validate(&self, path: impl AsRef<std::path::Path>) -> Result<(), crate::Error> {
+        validate_gguf(path, self.architecture())
+    }
+}
+
+fn validate_gguf(
+    path: impl AsRef<std::path::Path>,
+    architecture: &str,
+) -> Result<(), crate::Error> {
+    use hypr_gguf::GgufExt;
+
+    let path = path.as_ref();
+    let metadata = path.gguf_metadata()?;
+
+    if metadata.architecture.as_deref() != Some(architecture) {
+        return Err(crate::Error::InvalidModel(format!(
+            "expected architecture {}, found {:?}",
+            architecture, metadata.architecture
+        )));
+    }
+
+    if hypr_file::file_size(path)? < metadata.min_file_size {
+        return Err(crate::Error::InvalidModel("file is truncated".to_string()));
+    }
+
+    Ok(())
+}
+
+// Used for semantic search. Embeddings from different models can't be compared,
+// so it is not configurable like `SupportedModel`.
+pub static EMBEDDING_MODEL: SupportedEmbeddingModel = SupportedEmbeddingModel::NomicEmbedTextV1p5Q8;
+
+#[derive(serde::Serialize, serde::Deserialize, specta::Type, Clone)]
+pub enum SupportedEmbeddingModel {
+    NomicEmbedTextV1p5Q8,
+}
+
+impl SupportedEmbeddingModel {
+    pub fn model_path(&self, data_dir: impl Into<std::path::PathBuf>) -> std::path::PathBuf {
+        match self {
+            SupportedEmbeddingModel::NomicEmbedTextV1p5Q8 => data_dir.into().join("embedding.gguf"),
+        }
+    }
+
+    pub fn model_url(&self) -> &str {
+        match self {
+            SupportedEmbeddingModel::NomicEmbedTextV1p5Q8 => "https://huggingface.co/nomic-ai/nomic-embed-text-v1.5-GGUF/resolve/main/nomic-embed-text-v1.5.Q8_0.gguf"
+        }
+    }
+
+    // Of the file at `model_url`, printed by `test_print_manifests`.
+    pub fn sha256(&self) -> Option<&str> {
+        match self {
+            SupportedEmbeddingModel::NomicEmbedTextV1p5Q8 => None,
+        }
+    }
+
+    // Unpinned, the download is only checked by `validate`.
+    pub fn manifest(&self) -> hypr_file::Manifest {
+        hypr_file::Manifest::builder().checksum(
+            self.sha256()
+                .map(|sha256| hypr_file::Checksum::Sha256(sha256.to_string())),
+        )
+    }
+
+    pub fn validate(&self, path: impl AsRef<std::path::Path>) -> Result<(), crate::Error> {
+        match self {
+            SupportedEmbeddingModel::NomicEmbedTextV1p5Q8 => validate_gguf(path, "nomic-bert"),
+        }
+    }
+
+    // https://huggingface.co/nomic-ai/nomic-embed-text-v1.5#task-instruction-prefixes
+    pub fn document_prefix(&self) -> &str {
+        match self {
+            SupportedEmbeddingModel::NomicEmbedTextV1p5Q8 => "search_document: ",
+        }
+    }
+
+    pub fn query_prefix(&self) -> &str {
+        match self {
+            SupportedEmbeddingModel::NomicEmbedTextV1p5Q8 => "search_query: ",
         }
     }
 }
@@ -32,3 +213,34 @@ pub enum ModelIdentifier {
     #[serde(rename = "mock-onboarding")]
     MockOnboarding,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Where `model_url` is mirrored from, if it is not Hugging Face itself.
+    fn huggingface_url(model: &SupportedModel) -> &'static str {
+        match model {
+            SupportedModel::Llama3p2_3bQ4 => "https://huggingface.co/lmstudio-community/Llama-3.2-3B-Instruct-GGUF/resolve/main/Llama-3.2-3B-Instruct-Q4_K_M.gguf",
+            SupportedModel::Llama3p2_1bQ8 => "https://huggingface.co/lmstudio-community/Llama-3.2-1B-Instruct-GGUF/resolve/main/Llama-3.2-1B-Instruct-Q8_0.gguf",
+            SupportedModel::Qwen2p5_3bQ4 => "https://huggingface.co/lmstudio-community/Qwen2.5-3B-Instruct-GGUF/resolve/main/Qwen2.5-3B-Instruct-Q4_K_M.gguf",
+        }
+    }
+
+    #[tokio::test]
+    #[ignore]
+    // cargo test test_print_manifests -p tauri-plugin-local-llm -- --ignored --nocapture
+    async fn test_print_manifests() {
+        for model in SUPPORTED_MODELS {
+            let manifest = hypr_file::huggingface_manifest(huggingface_url(model))
+                .await
+                .unwrap();
+            println!("{:?}: {:?}", model, manifest);
+        }
+
+        let manifest = hypr_file::huggingface_manifest(EMBEDDING_MODEL.model_url())
+            .await
+            .unwrap();
+        println!("{:?}", manifest);
+    }
+}
@@ -12,9 +12,14 @@ use futures_util::StreamExt;
 use tower_http::cors::{self, CorsLayer};
 
 use async_openai::types::{
-    ChatChoice, ChatChoiceStream, ChatCompletionResponseMessage, ChatCompletionStreamResponseDelta,
-    CreateChatCompletionRequest, CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
-    Role,
+    ChatChoice, ChatChoiceStream, ChatCompletionMessageToolCall,
+    ChatCompletionMessageToolCallChunk, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessage, ChatCompletionRequestSystemMessageContent,
+    ChatCompletionResponseMessage, ChatCompletionStreamResponseDelta, ChatCompletionTool,
+    ChatCompletionToolChoiceOption, ChatCompletionToolType, CreateChatCompletionRequest,
+    CreateChatCompletionResponse, CreateChatCompletionStreamResponse, CreateEmbeddingRequest,
+    CreateEmbeddingResponse, Embedding, EmbeddingInput, EmbeddingUsage, FinishReason, FunctionCall,
+    FunctionCallStream, ResponseFormat, Role, Stop,
 };
 
 use crate::local::ModelManager;
@@ -35,6 +40,7 @@ pub async fn run_server(model_manager: ModelManager) -> Result<ServerHandle, cra
     let app = Router::new()
         .route("/health", get(health))
         .route("/chat/completions", post(chat_completions))
+        .route("/v1/embeddings", post(embeddings))
         .with_state(model_manager)
         .layer(
             CorsLayer::new()
@@ -90,11 +96,69 @@ async fn chat_completions(
         inference_without_mock(&model, &request).await
     };
 
-    inference_result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+    inference_result.map_err(|e| match e {
+        crate::Error::HyprLlamaError(hypr_llama::Error::ContextOverflow { .. }) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })
 }
 
+// Inputs are embedded as given, without the task prefixes some models expect.
+async fn embeddings(
+    AxumState(model_manager): AxumState<ModelManager>,
+    Json(request): Json<CreateEmbeddingRequest>,
+) -> Result<Json<CreateEmbeddingResponse>, (StatusCode, String)> {
+    let texts = match request.input {
+        EmbeddingInput::String(text) => vec![text],
+        EmbeddingInput::StringArray(texts) => texts,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "only text inputs are supported".to_string(),
+            ))
+        }
+    };
+
+    let embedder = model_manager
+        .get_embedder()
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    let tokens = texts
+        .iter()
+        .map(|text| embedder.count_tokens(text))
+        .sum::<Result<usize, _>>()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))? as u32;
+
+    let embeddings = embedder
+        .embed(texts)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(CreateEmbeddingResponse {
+        object: "list".to_string(),
+        model: request.model,
+        data: embeddings
+            .into_iter()
+            .enumerate()
+            .map(|(index, embedding)| Embedding {
+                index: index as u32,
+                object: "embedding".to_string(),
+                embedding,
+            })
+            .collect(),
+        usage: EmbeddingUsage {
+            prompt_tokens: tokens,
+            total_tokens: tokens,
+        },
+    }))
+}
+
+// With `tools`, the output is collected and parsed into `tool_calls` before responding.
 async fn build_and_send_response(
     request: &CreateChatCompletionRequest,
+    tools: bool,
     response_stream_fn: impl FnOnce() -> Result<
         Pin<Box<dyn futures_util::Stream<Item = String> + Send>>,
         crate::Error,
@@ -160,11 +224,14 @@ async fn build_and_send_response(
     if !is_stream {
         let stream = response_stream_fn()?;
         let completion = futures_util::StreamExt::collect::<String>(stream).await;
+        let tool_calls = tools.then(|| to_tool_calls(&completion)).flatten();
 
         let res = CreateChatCompletionResponse {
             choices: vec![ChatChoice {
+                finish_reason: tool_calls.as_ref().map(|_| FinishReason::ToolCalls),
                 message: ChatCompletionResponseMessage {
-                    content: Some(completion),
+                    content: tool_calls.is_none().then_some(completion),
+                    tool_calls,
                     ..empty_message
                 },
                 ..empty_choice
@@ -174,18 +241,64 @@ async fn build_and_send_response(
         Ok(Json(res).into_response())
     } else {
         let source_stream = response_stream_fn()?;
-        let stream = source_stream
-            .map(move |chunk| {
-                let delta_template = empty_stream_response_delta.clone();
+
+        let deltas: Pin<Box<dyn futures_util::Stream<Item = _> + Send>> = if tools {
+            let delta_template = empty_stream_response_delta.clone();
+
+            Box::pin(futures_util::stream::once(async move {
+                let completion = futures_util::StreamExt::collect::<String>(source_stream).await;
+
+                match to_tool_calls(&completion) {
+                    Some(calls) => (
+                        ChatCompletionStreamResponseDelta {
+                            tool_calls: Some(
+                                calls
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(i, call)| ChatCompletionMessageToolCallChunk {
+                                        index: i as u32,
+                                        id: Some(call.id),
+                                        r#type: Some(call.r#type),
+                                        function: Some(FunctionCallStream {
+                                            name: Some(call.function.name),
+                                            arguments: Some(call.function.arguments),
+                                        }),
+                                    })
+                                    .collect(),
+                            ),
+                            ..delta_template
+                        },
+                        Some(FinishReason::ToolCalls),
+                    ),
+                    None => (
+                        ChatCompletionStreamResponseDelta {
+                            content: Some(completion),
+                            ..delta_template
+                        },
+                        None,
+                    ),
+                }
+            }))
+        } else {
+            Box::pin(source_stream.map(move |chunk| {
+                (
+                    ChatCompletionStreamResponseDelta {
+                        content: Some(chunk),
+                        ..empty_stream_response_delta.clone()
+                    },
+                    None,
+                )
+            }))
+        };
+
+        let stream = deltas
+            .map(move |(delta, finish_reason)| {
                 let response_template = base_stream_response_template.clone();
                 CreateChatCompletionStreamResponse {
                     choices: vec![ChatChoiceStream {
                         index: 0,
-                        delta: ChatCompletionStreamResponseDelta {
-                            content: Some(chunk),
-                            ..delta_template
-                        },
-                        finish_reason: None,
+                        delta,
+                        finish_reason,
                         logprobs: None,
                     }],
                     ..response_template
@@ -200,43 +313,150 @@ async fn build_and_send_response(
     }
 }
 
+// `None` if the model answered in plain text.
+fn to_tool_calls(completion: &str) -> Option<Vec<ChatCompletionMessageToolCall>> {
+    let calls = hypr_llama::parse_tool_calls(completion);
+    if calls.is_empty() {
+        return None;
+    }
+
+    Some(
+        calls
+            .into_iter()
+            .map(|call| ChatCompletionMessageToolCall {
+                id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+                r#type: ChatCompletionToolType::Function,
+                function: FunctionCall {
+                    name: call.name,
+                    arguments: call.arguments.to_string(),
+                },
+            })
+            .collect(),
+    )
+}
+
+// Schema the output must match, per `response_format`.
+fn response_schema(request: &CreateChatCompletionRequest) -> Option<serde_json::Value> {
+    match &request.response_format {
+        Some(ResponseFormat::JsonSchema { json_schema }) => {
+            Some(json_schema.schema.clone().unwrap_or(serde_json::json!({})))
+        }
+        Some(ResponseFormat::JsonObject) => Some(serde_json::json!({ "type": "object" })),
+        _ => None,
+    }
+}
+
+// Tools the model may call, and whether it must call one of them.
+fn requested_tools(request: &CreateChatCompletionRequest) -> (Vec<ChatCompletionTool>, bool) {
+    let tools = request.tools.clone().unwrap_or_default();
+
+    match &request.tool_choice {
+        Some(ChatCompletionToolChoiceOption::None) => (vec![], false),
+        Some(ChatCompletionToolChoiceOption::Required) => (tools, true),
+        Some(ChatCompletionToolChoiceOption::Named(named)) => (
+            tools
+                .into_iter()
+                .filter(|tool| tool.function.name == named.function.name)
+                .collect(),
+            true,
+        ),
+        _ => (tools, false),
+    }
+}
+
 async fn inference_with_mock(
     request: &CreateChatCompletionRequest,
 ) -> Result<Response, crate::Error> {
-    build_and_send_response(request, || Ok(build_mock_response())).await
+    build_and_send_response(request, false, || Ok(build_mock_response())).await
 }
 
 async fn inference_without_mock(
     model: &hypr_llama::Llama,
     request: &CreateChatCompletionRequest,
 ) -> Result<Response, crate::Error> {
-    build_and_send_response(request, || build_response(model, request)).await
+    let (tools, _) = requested_tools(request);
+    build_and_send_response(request, !tools.is_empty(), || {
+        build_response(model, request)
+    })
+    .await
 }
 
 fn build_response(
     model: &hypr_llama::Llama,
     request: &CreateChatCompletionRequest,
 ) -> Result<Pin<Box<dyn futures_util::Stream<Item = String> + Send>>, crate::Error> {
-    let messages = request
+    let (tools, required) = requested_tools(request);
+
+    let mut messages: Vec<_> = request
         .messages
         .iter()
         .map(hypr_llama::FromOpenAI::from_openai)
         .collect();
 
-    let grammar = if request
+    if !tools.is_empty() {
+        let system = ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+            content: ChatCompletionRequestSystemMessageContent::Text(hypr_llama::tools_prompt(
+                &tools,
+            )),
+            name: None,
+        });
+        messages.insert(0, hypr_llama::FromOpenAI::from_openai(&system));
+    }
+
+    let metadata = request
         .metadata
-        .as_ref()
-        .unwrap_or(&serde_json::Value::Object(Default::default()))
-        .get("grammar")
-        .and_then(|v| v.as_str())
-        == Some("title")
-    {
+        .clone()
+        .unwrap_or(serde_json::Value::Object(Default::default()));
+
+    let grammar = if !tools.is_empty() {
+        Some(
+            hypr_gbnf::GBNF::ToolCall {
+                tools: tools
+                    .iter()
+                    .map(|tool| hypr_gbnf::Tool {
+                        name: tool.function.name.clone(),
+                        parameters: tool
+                            .function
+                            .parameters
+                            .clone()
+                            .unwrap_or(serde_json::json!({ "type": "object" })),
+                    })
+                    .collect(),
+                required,
+            }
+            .build(),
+        )
+    } else if let Some(schema) = response_schema(request) {
+        Some(hypr_gbnf::GBNF::JsonSchema(schema).build())
+    } else if metadata.get("grammar").and_then(|v| v.as_str()) == Some("title") {
         Some(hypr_gbnf::GBNF::Title.build())
     } else {
         Some(hypr_gbnf::GBNF::Enhance(Some(vec!["".to_string()])).build())
     };
 
-    let request = hypr_llama::LlamaRequest { messages, grammar };
+    let stop = match &request.stop {
+        Some(Stop::String(stop)) => vec![stop.clone()],
+        Some(Stop::StringArray(stop)) => stop.clone(),
+        None => vec![],
+    };
+
+    #[allow(deprecated)]
+    let max_tokens = request.max_completion_tokens.or(request.max_tokens);
+
+    let request = hypr_llama::LlamaRequest {
+        messages,
+        grammar,
+        temperature: request.temperature,
+        top_p: request.top_p,
+        // Not part of the OpenAI API.
+        top_k: metadata
+            .get("top_k")
+            .and_then(|v| v.as_i64())
+            .map(|v| v as i32),
+        max_tokens,
+        stop,
+        seed: request.seed.map(|v| v as u32),
+    };
 
     Ok(Box::pin(model.generate_stream(request)?))
 }
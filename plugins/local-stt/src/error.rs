@@ -12,6 +12,10 @@ pub enum Error {
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     StoreError(#[from] tauri_plugin_store2::Error),
+    #[error(transparent)]
+    WhisperError(#[from] hypr_whisper::local::Error),
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
     #[error("Model not downloaded")]
     ModelNotDownloaded,
 }
@@ -3,7 +3,7 @@ use std::future::Future;
 use tauri::{ipc::Channel, Manager, Runtime};
 use tauri_plugin_store2::StorePluginExt;
 
-use hypr_file::{download_file_with_callback, DownloadProgress};
+use hypr_file::{download_file_with_manifest, DownloadProgress};
 
 pub trait LocalSttPluginExt<R: Runtime> {
     fn local_stt_store(&self) -> tauri_plugin_store2::ScopedStore<R, crate::StoreKey>;
@@ -117,6 +117,15 @@ impl<R: Runtime, T: Manager<R>> LocalSttPluginExt<R> for T {
     ) -> Result<(), crate::Error> {
         let data_dir = self.path().app_data_dir()?;
 
+        let state = self.state::<crate::SharedState>();
+        let mut s = state.lock().await;
+
+        // Both would write to the same partial file, so the previous download has to stop first.
+        if let Some(existing_task) = s.download_task.remove(&model) {
+            existing_task.abort();
+            let _ = existing_task.await;
+        }
+
         let m = model.clone();
         let task = tokio::spawn(async move {
             let callback = |progress: DownloadProgress| match progress {
@@ -132,23 +141,19 @@ impl<R: Runtime, T: Manager<R>> LocalSttPluginExt<R> for T {
                 }
             };
 
-            if let Err(e) =
-                download_file_with_callback(m.model_url(), m.model_path(&data_dir), callback).await
+            if let Err(e) = download_file_with_manifest(
+                m.model_url(),
+                m.model_path(&data_dir),
+                &m.manifest(),
+                callback,
+            )
+            .await
             {
                 tracing::error!("model_download_error: {}", e);
                 let _ = channel.send(-1);
             }
         });
-
-        {
-            let state = self.state::<crate::SharedState>();
-            let mut s = state.lock().await;
-
-            if let Some(existing_task) = s.download_task.remove(&model) {
-                existing_task.abort();
-            }
-            s.download_task.insert(model.clone(), task);
-        }
+        s.download_task.insert(model, task);
 
         Ok(())
     }
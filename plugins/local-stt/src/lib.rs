@@ -6,6 +6,7 @@ mod error;
 mod ext;
 mod manager;
 mod model;
+mod pool;
 pub mod server;
 mod store;
 
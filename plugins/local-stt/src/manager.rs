@@ -1,36 +1,119 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_util::sync::CancellationToken;
 
+pub const DEFAULT_MAX_SESSIONS: usize = 2;
+pub const DEFAULT_MAX_QUEUED: usize = 4;
+pub const DEFAULT_QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum AcquireError {
+    #[error("connection queue is full")]
+    QueueFull,
+    #[error("timed out waiting for a free session")]
+    Timeout,
+    #[error("connection manager is shut down")]
+    Closed,
+}
+
+// Admits at most `max_sessions` concurrent connections. Up to `max_queued` more wait for a free slot, and anything beyond that is rejected immediately.
 #[derive(Clone)]
 pub struct ConnectionManager {
-    inner: Arc<Mutex<Option<CancellationToken>>>,
+    permits: Arc<Semaphore>,
+    queued: Arc<AtomicUsize>,
+    max_queued: usize,
+    queue_timeout: Duration,
+    shutdown: CancellationToken,
 }
 
 impl Default for ConnectionManager {
     fn default() -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(None)),
-        }
+        Self::new(
+            DEFAULT_MAX_SESSIONS,
+            DEFAULT_MAX_QUEUED,
+            DEFAULT_QUEUE_TIMEOUT,
+        )
     }
 }
 
 impl ConnectionManager {
-    pub fn acquire_connection(&self) -> ConnectionGuard {
-        let mut slot = self.inner.lock().unwrap();
+    pub fn new(max_sessions: usize, max_queued: usize, queue_timeout: Duration) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_sessions.max(1))),
+            queued: Arc::new(AtomicUsize::new(0)),
+            max_queued,
+            queue_timeout,
+            shutdown: CancellationToken::new(),
+        }
+    }
 
-        if let Some(old) = slot.take() {
-            old.cancel();
+    pub async fn acquire_connection(&self) -> Result<ConnectionGuard, AcquireError> {
+        if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+            return Ok(self.guard(permit));
         }
 
-        let token = CancellationToken::new();
-        *slot = Some(token.clone());
+        let _ticket = QueueTicket::take(&self.queued, self.max_queued)?;
+
+        let permit = tokio::select! {
+            _ = self.shutdown.cancelled() => return Err(AcquireError::Closed),
+            result = tokio::time::timeout(self.queue_timeout, self.permits.clone().acquire_owned()) => {
+                match result {
+                    Ok(Ok(permit)) => permit,
+                    Ok(Err(_)) => return Err(AcquireError::Closed),
+                    Err(_) => return Err(AcquireError::Timeout),
+                }
+            }
+        };
+
+        Ok(self.guard(permit))
+    }
+
+    pub fn shutdown(&self) {
+        self.permits.close();
+        self.shutdown.cancel();
+    }
+
+    fn guard(&self, permit: OwnedSemaphorePermit) -> ConnectionGuard {
+        ConnectionGuard {
+            token: self.shutdown.child_token(),
+            _permit: permit,
+        }
+    }
+}
+
+// Keeps the queue length accurate even if the waiting request is dropped mid-wait.
+struct QueueTicket<'a> {
+    queued: &'a AtomicUsize,
+}
+
+impl<'a> QueueTicket<'a> {
+    fn take(queued: &'a AtomicUsize, max_queued: usize) -> Result<Self, AcquireError> {
+        queued
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < max_queued).then_some(n + 1)
+            })
+            .map_err(|_| AcquireError::QueueFull)?;
 
-        ConnectionGuard { token }
+        Ok(Self { queued })
+    }
+}
+
+impl Drop for QueueTicket<'_> {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::SeqCst);
     }
 }
 
 pub struct ConnectionGuard {
     token: CancellationToken,
+    _permit: OwnedSemaphorePermit,
 }
 
 impl ConnectionGuard {
@@ -60,7 +143,7 @@ mod tests {
     use tokio_tungstenite::{connect_async, tungstenite::protocol::Message as TungsteniteMessage};
 
     fn app() -> Router {
-        let manager = ConnectionManager::default();
+        let manager = ConnectionManager::new(1, 0, Duration::from_secs(1));
         Router::new().route("/ws", get(handler)).with_state(manager)
     }
 
@@ -68,7 +151,10 @@ mod tests {
         ws: WebSocketUpgrade,
         AxumState(manager): AxumState<ConnectionManager>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        let guard = manager.acquire_connection();
+        let guard = manager
+            .acquire_connection()
+            .await
+            .map_err(|_| StatusCode::TOO_MANY_REQUESTS)?;
 
         Ok(ws.on_upgrade(move |socket| handle_socket(socket, guard)))
     }
@@ -130,4 +216,56 @@ mod tests {
             assert!(result.is_ok());
         }
     }
+
+    #[tokio::test]
+    async fn test_queue_backpressure() {
+        let manager = ConnectionManager::new(1, 1, Duration::from_millis(200));
+
+        let first = manager.acquire_connection().await.unwrap();
+
+        let queued = {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.acquire_connection().await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(matches!(
+            manager.acquire_connection().await,
+            Err(AcquireError::QueueFull)
+        ));
+
+        drop(first);
+        assert!(queued.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queue_timeout() {
+        let manager = ConnectionManager::new(1, 1, Duration::from_millis(50));
+
+        let _first = manager.acquire_connection().await.unwrap();
+        assert!(matches!(
+            manager.acquire_connection().await,
+            Err(AcquireError::Timeout)
+        ));
+
+        // The timed-out request must have released its queue slot.
+        assert!(matches!(
+            manager.acquire_connection().await,
+            Err(AcquireError::Timeout)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_sessions() {
+        let manager = ConnectionManager::default();
+        let guard = manager.acquire_connection().await.unwrap();
+
+        manager.shutdown();
+        guard.cancelled().await;
+
+        assert!(matches!(
+            manager.acquire_connection().await,
+            Err(AcquireError::Closed)
+        ));
+    }
 }
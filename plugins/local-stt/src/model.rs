@@ -57,4 +57,57 @@ impl SupportedModel {
             SupportedModel::QuantizedLargeTurbo => 874188075,
         }
     }
+
+    // Of the file at `model_url`, printed by `test_print_manifests`.
+    pub fn sha256(&self) -> Option<&str> {
+        match self {
+            SupportedModel::QuantizedTiny => None,
+            SupportedModel::QuantizedTinyEn => None,
+            SupportedModel::QuantizedBase => None,
+            SupportedModel::QuantizedBaseEn => None,
+            SupportedModel::QuantizedSmall => None,
+            SupportedModel::QuantizedSmallEn => None,
+            SupportedModel::QuantizedLargeTurbo => None,
+        }
+    }
+
+    // `model_size` is exact, so it is checked even while `sha256` is unpinned.
+    pub fn manifest(&self) -> hypr_file::Manifest {
+        hypr_file::Manifest::builder()
+            .size(self.model_size())
+            .checksum(
+                self.sha256()
+                    .map(|sha256| hypr_file::Checksum::Sha256(sha256.to_string())),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Where `model_url` is mirrored from.
+    fn huggingface_url(model: &SupportedModel) -> &'static str {
+        match model {
+            SupportedModel::QuantizedTiny => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny-q8_0.bin",
+            SupportedModel::QuantizedTinyEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.en-q8_0.bin",
+            SupportedModel::QuantizedBase => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q8_0.bin",
+            SupportedModel::QuantizedBaseEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.en-q8_0.bin",
+            SupportedModel::QuantizedSmall => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q8_0.bin",
+            SupportedModel::QuantizedSmallEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.en-q8_0.bin",
+            SupportedModel::QuantizedLargeTurbo => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q8_0.bin",
+        }
+    }
+
+    #[tokio::test]
+    #[ignore]
+    // cargo test test_print_manifests -p tauri-plugin-local-stt -- --ignored --nocapture
+    async fn test_print_manifests() {
+        for model in SUPPORTED_MODELS {
+            let manifest = hypr_file::huggingface_manifest(huggingface_url(model))
+                .await
+                .unwrap();
+            println!("{:?}: {:?}", model, manifest);
+        }
+    }
 }
@@ -0,0 +1,74 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use hypr_whisper::local::{WhisperModel, WhisperState};
+use tokio::sync::OnceCell;
+
+// The ggml model is loaded once, on first use, and shared by every session.
+// States are recycled between sessions. At most `max_states` are kept, which the server sizes as
+// the sessions the connection manager admits times the channels of each session.
+#[derive(Clone)]
+pub struct ModelPool {
+    inner: Arc<ModelPoolInner>,
+}
+
+struct ModelPoolInner {
+    model_path: PathBuf,
+    model: OnceCell<WhisperModel>,
+    states: Mutex<Vec<WhisperState>>,
+    max_states: usize,
+}
+
+impl ModelPool {
+    pub fn new(model_path: PathBuf, max_states: usize) -> Self {
+        Self {
+            inner: Arc::new(ModelPoolInner {
+                model_path,
+                model: OnceCell::new(),
+                states: Mutex::new(Vec::with_capacity(max_states)),
+                max_states,
+            }),
+        }
+    }
+
+    pub async fn model(&self) -> Result<WhisperModel, crate::Error> {
+        let model = self
+            .inner
+            .model
+            .get_or_try_init(|| async {
+                let model_path = self.inner.model_path.to_string_lossy().to_string();
+                tracing::info!("loading_whisper_model: {}", model_path);
+
+                let model =
+                    tokio::task::spawn_blocking(move || WhisperModel::load(model_path)).await??;
+                Ok::<_, crate::Error>(model)
+            })
+            .await?;
+
+        Ok(model.clone())
+    }
+
+    pub async fn checkout(&self) -> Result<(WhisperModel, WhisperState), crate::Error> {
+        let model = self.model().await?;
+
+        let recycled = self.inner.states.lock().unwrap().pop();
+        let state = match recycled {
+            Some(state) => state,
+            None => {
+                let m = model.clone();
+                tokio::task::spawn_blocking(move || m.create_state()).await??
+            }
+        };
+
+        Ok((model, state))
+    }
+
+    pub fn checkin(&self, state: WhisperState) {
+        let mut states = self.inner.states.lock().unwrap();
+        if states.len() < self.inner.max_states {
+            states.push(state);
+        }
+    }
+}
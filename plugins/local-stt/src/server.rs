@@ -5,28 +5,37 @@ use std::{
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, WebSocket, WebSocketUpgrade},
         Query, State as AxumState,
     },
-    http::StatusCode,
     response::IntoResponse,
     routing::get,
     Router,
 };
 
-use futures_util::{SinkExt, StreamExt};
+use futures_util::StreamExt;
 use tower_http::cors::{self, CorsLayer};
 
 use hypr_chunker::ChunkerExt;
-use hypr_listener_interface::{ListenOutputChunk, ListenParams, Word};
-use hypr_ws_utils::WebSocketAudioSource;
+use hypr_listener_interface::{LanguageDetection, ListenOutputChunk, ListenParams, Word};
+use hypr_whisper::local::{DetectLanguage, WhisperModel, WhisperState};
+use hypr_ws_utils::{ListenSession, ListenSessions, ListenSink, WebSocketAudioSource};
+use kalosm_sound::AsyncSource;
 
-use crate::manager::{ConnectionGuard, ConnectionManager};
+use crate::{
+    manager::{ConnectionGuard, ConnectionManager},
+    pool::ModelPool,
+};
+
+// Mic and speaker. Each channel holds its own Whisper state.
+pub const MAX_CHANNELS: usize = 2;
 
 #[derive(Default)]
 pub struct ServerStateBuilder {
     pub model_type: Option<crate::SupportedModel>,
     pub model_cache_dir: Option<PathBuf>,
+    pub max_sessions: Option<usize>,
+    pub max_queued: Option<usize>,
 }
 
 impl ServerStateBuilder {
@@ -40,20 +49,44 @@ impl ServerStateBuilder {
         self
     }
 
+    pub fn max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = Some(max_sessions);
+        self
+    }
+
+    pub fn max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = Some(max_queued);
+        self
+    }
+
     pub fn build(self) -> ServerState {
+        let model_type = self.model_type.unwrap();
+        let model_path = model_type.model_path(self.model_cache_dir.unwrap());
+
+        let max_sessions = self
+            .max_sessions
+            .unwrap_or(crate::manager::DEFAULT_MAX_SESSIONS);
+
+        let connection_manager = ConnectionManager::new(
+            max_sessions,
+            self.max_queued
+                .unwrap_or(crate::manager::DEFAULT_MAX_QUEUED),
+            crate::manager::DEFAULT_QUEUE_TIMEOUT,
+        );
+
         ServerState {
-            model_type: self.model_type.unwrap(),
-            model_cache_dir: self.model_cache_dir.unwrap(),
-            connection_manager: ConnectionManager::default(),
+            model_pool: ModelPool::new(model_path, max_sessions * MAX_CHANNELS),
+            connection_manager,
+            listen_sessions: ListenSessions::default(),
         }
     }
 }
 
 #[derive(Clone)]
 pub struct ServerState {
-    model_type: crate::SupportedModel,
-    model_cache_dir: PathBuf,
+    model_pool: ModelPool,
     connection_manager: ConnectionManager,
+    listen_sessions: ListenSessions,
 }
 
 #[derive(Clone)]
@@ -63,6 +96,8 @@ pub struct ServerHandle {
 }
 
 pub async fn run_server(state: ServerState) -> Result<ServerHandle, crate::Error> {
+    let connection_manager = state.connection_manager.clone();
+
     let router = Router::new()
         .route("/health", get(health))
         .route("/api/desktop/listen/realtime", get(listen))
@@ -93,6 +128,8 @@ pub async fn run_server(state: ServerState) -> Result<ServerHandle, crate::Error
             })
             .await
             .unwrap();
+
+        connection_manager.shutdown();
     });
 
     tracing::info!("local_stt_server_started {}", server_addr);
@@ -107,55 +144,162 @@ async fn listen(
     Query(params): Query<ListenParams>,
     ws: WebSocketUpgrade,
     AxumState(state): AxumState<ServerState>,
-) -> Result<impl IntoResponse, StatusCode> {
-    let guard = state.connection_manager.acquire_connection();
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move { websocket_with_model(socket, params, state).await })
+}
+
+async fn websocket_with_model(socket: WebSocket, params: ListenParams, state: ServerState) {
+    // `None` when the client resumed a session that is already being transcribed.
+    let Some(ListenSession {
+        sources,
+        sink,
+        params: start_params,
+    }) = state.listen_sessions.accept(socket).await
+    else {
+        return;
+    };
+    let params = start_params.unwrap_or(params);
+
+    // Acquired after the handshake, so resuming a session doesn't take a second slot.
+    let guard = match state.connection_manager.acquire_connection().await {
+        Ok(guard) => guard,
+        Err(e) => {
+            tracing::warn!("listen_rejected: {}", e);
+            sink.close(Some(CloseFrame {
+                code: close_code::AGAIN,
+                reason: e.to_string().into(),
+            }))
+            .await;
+            return;
+        }
+    };
+
+    if sources.len() > MAX_CHANNELS {
+        tracing::warn!("listen_rejected: {} channels", sources.len());
+        sink.close(Some(CloseFrame {
+            code: close_code::POLICY,
+            reason: format!("at most {} channels are supported", MAX_CHANNELS).into(),
+        }))
+        .await;
+        return;
+    }
 
-    Ok(ws.on_upgrade(move |socket| async move {
-        websocket_with_model(socket, params, state, guard).await
-    }))
+    // Multichannel sessions run one Whisper stream per channel.
+    let mut whispers = Vec::with_capacity(sources.len());
+    for _ in &sources {
+        match state.model_pool.checkout().await {
+            Ok((model, whisper_state)) => {
+                whispers.push(build_whisper(&params, model, whisper_state))
+            }
+            Err(e) => {
+                tracing::error!("whisper_model_unavailable: {}", e);
+                for whisper in whispers {
+                    state.model_pool.checkin(whisper.into_state());
+                }
+                sink.close(None).await;
+                return;
+            }
+        }
+    }
+
+    let multichannel = sources.len() > 1;
+    let params = tokio::sync::watch::Sender::new(params);
+    let whispers =
+        futures_util::future::join_all(sources.into_iter().zip(whispers).enumerate().map(
+            |(channel, (audio_source, whisper))| {
+                let channel = multichannel.then_some(channel as u8);
+                websocket(&sink, &params, audio_source, whisper, channel, &guard)
+            },
+        ))
+        .await;
+
+    sink.close(None).await;
+    for whisper in whispers {
+        state.model_pool.checkin(whisper.into_state());
+    }
 }
 
-async fn websocket_with_model(
-    socket: WebSocket,
-    params: ListenParams,
-    state: ServerState,
-    guard: ConnectionGuard,
-) {
-    let model_type = state.model_type;
-    let model_cache_dir = state.model_cache_dir.clone();
-
-    let model_path = model_type.model_path(&model_cache_dir);
-    let language = params.language.try_into().unwrap_or_else(|e| {
+fn build_whisper(
+    params: &ListenParams,
+    model: WhisperModel,
+    whisper_state: WhisperState,
+) -> hypr_whisper::local::Whisper {
+    let language = params.language.clone().try_into().unwrap_or_else(|e| {
         tracing::error!("convert_to_whisper_language: {e:?}");
         hypr_whisper::Language::En
     });
 
-    let model = hypr_whisper::local::Whisper::builder()
-        .model_path(model_path.to_str().unwrap())
+    // Whisper has no keyword boosting, so terms are primed through the prompt instead.
+    let static_prompt = match params.vocabulary.to_prompt() {
+        vocabulary if vocabulary.is_empty() => params.static_prompt.clone(),
+        vocabulary => format!("{} / {}", vocabulary, params.static_prompt),
+    };
+
+    let builder = hypr_whisper::local::Whisper::builder()
+        .model(model)
+        .state(whisper_state)
         .language(language)
-        .static_prompt(&params.static_prompt)
-        .dynamic_prompt(&params.dynamic_prompt)
-        .build();
+        .static_prompt(static_prompt)
+        .dynamic_prompt(&params.dynamic_prompt);
+
+    let builder = match params.language_detection {
+        LanguageDetection::Off => builder,
+        LanguageDetection::FirstChunk => builder.detect_language(DetectLanguage::Once),
+        LanguageDetection::PerChunk => builder.detect_language(DetectLanguage::PerChunk),
+    };
 
-    websocket(socket, model, guard).await;
+    builder.build()
+}
+
+// With `LanguageDetection::FirstChunk`, the first language detected on any channel becomes the session's.
+fn pin_detected_language(
+    params: &tokio::sync::watch::Sender<ListenParams>,
+    detected: Option<hypr_language::Language>,
+) -> bool {
+    params.send_if_modified(|params| match detected {
+        Some(language) if params.language_detection == LanguageDetection::FirstChunk => {
+            params.language = language;
+            params.language_detection = LanguageDetection::Off;
+            true
+        }
+        _ => false,
+    })
 }
 
 #[tracing::instrument(skip_all)]
-async fn websocket(socket: WebSocket, model: hypr_whisper::local::Whisper, guard: ConnectionGuard) {
-    let (mut ws_sender, ws_receiver) = socket.split();
+async fn websocket(
+    sink: &ListenSink,
+    params: &tokio::sync::watch::Sender<ListenParams>,
+    audio_source: WebSocketAudioSource,
+    model: hypr_whisper::local::Whisper,
+    channel: Option<u8>,
+    guard: &ConnectionGuard,
+) -> hypr_whisper::local::Whisper {
     let mut stream = {
-        let audio_source = WebSocketAudioSource::new(ws_receiver, 16 * 1000);
-        let chunked =
-            audio_source.chunks(hypr_chunker::RMS::new(), std::time::Duration::from_secs(15));
+        // Whisper only takes 16kHz, while v2 clients can send any sample rate.
+        let chunked = ChunkerExt::chunks(
+            audio_source.resample(16 * 1000),
+            hypr_chunker::RMS::new(),
+            std::time::Duration::from_secs(15),
+        );
         hypr_whisper::local::TranscribeChunkedAudioStreamExt::transcribe(chunked, model)
     };
+    let mut params_rx = params.subscribe();
 
     loop {
         tokio::select! {
             _ = guard.cancelled() => {
-                tracing::info!("websocket_cancelled_by_new_connection");
+                tracing::info!("websocket_cancelled_by_shutdown");
                 break;
             }
+            // Another channel pinned the language first.
+            Ok(()) = params_rx.changed() => {
+                let language = params_rx.borrow_and_update().language.clone();
+                match language.try_into() {
+                    Ok(language) => stream.whisper_mut().set_language(language),
+                    Err(e) => tracing::error!("convert_to_whisper_language: {e:?}"),
+                }
+            }
             chunk_opt = stream.next() => {
                 let Some(chunk) = chunk_opt else { break };
                 let text = chunk.text().to_string();
@@ -163,11 +307,6 @@ async fn websocket(socket: WebSocket, model: hypr_whisper::local::Whisper, guard
                 let duration = chunk.duration() as u64;
                 let confidence = chunk.confidence();
 
-                if confidence < 0.4 {
-                    tracing::warn!(confidence, "skipping_transcript: {}", text);
-                    continue;
-                }
-
                 let data = ListenOutputChunk {
                     words: text
                         .split_whitespace()
@@ -180,16 +319,55 @@ async fn websocket(socket: WebSocket, model: hypr_whisper::local::Whisper, guard
                             confidence: Some(confidence),
                         })
                         .collect(),
+                    language: chunk.language().and_then(|l| l.try_into().ok()),
+                    channel,
+                    interim: false,
                 };
 
-                let msg = Message::Text(serde_json::to_string(&data).unwrap().into());
-                if let Err(e) = ws_sender.send(msg).await {
-                    tracing::warn!("websocket_send_error: {}", e);
-                    break;
+                if pin_detected_language(params, data.language.clone()) {
+                    tracing::info!(language = ?data.language, "session_language_pinned");
                 }
+                sink.send(&data).await;
             }
         }
     }
 
-    let _ = ws_sender.close().await;
+    stream.into_whisper()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_detected_language() {
+        let params = tokio::sync::watch::Sender::new(ListenParams {
+            language: hypr_language::ISO639::En.into(),
+            language_detection: LanguageDetection::FirstChunk,
+            ..Default::default()
+        });
+        let mut rx = params.subscribe();
+
+        // Nothing detected in a chunk without speech.
+        assert!(!pin_detected_language(&params, None));
+        assert!(!rx.has_changed().unwrap());
+
+        // The first detected language is pinned, and every channel is told.
+        assert!(pin_detected_language(
+            &params,
+            Some(hypr_language::ISO639::Ko.into())
+        ));
+        assert!(rx.has_changed().unwrap());
+        let pinned = rx.borrow_and_update().clone();
+        assert_eq!(pinned.language, hypr_language::ISO639::Ko.into());
+        assert_eq!(pinned.language_detection, LanguageDetection::Off);
+
+        // Later chunks don't change it.
+        assert!(!pin_detected_language(
+            &params,
+            Some(hypr_language::ISO639::En.into())
+        ));
+        assert!(!rx.has_changed().unwrap());
+        assert_eq!(params.borrow().language, hypr_language::ISO639::Ko.into());
+    }
 }
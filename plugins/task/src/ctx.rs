@@ -60,6 +60,24 @@ impl<R: Runtime> TaskCtx<R> {
         })
     }
 
+    pub fn complete(&self) -> Result<(), crate::Error> {
+        if self.is_cancelled() {
+            return Ok(());
+        }
+
+        self.update_status(TaskStatus::Completed)
+    }
+
+    pub fn fail(&self, error: impl Into<String>) -> Result<(), crate::Error> {
+        if self.is_cancelled() {
+            return Ok(());
+        }
+
+        self.update_status(TaskStatus::Failed {
+            error: error.into(),
+        })
+    }
+
     fn update_status(&self, status: TaskStatus) -> Result<(), crate::Error> {
         let id = self.id.clone();
 
@@ -275,4 +275,49 @@ mod test {
         Note that above given informations like participants, transcript, etc. are already displayed in the UI, so you don't need to repeat them.
         "###);
     }
+
+    #[test]
+    fn test_enhance_user_with_summaries() {
+        let app = create_app(tauri::test::mock_builder());
+
+        let rendered = app
+            .render(
+                hypr_template::PredefinedTemplate::EnhanceUser,
+                serde_json::json!({
+                    "type": "HyprCloud",
+                    "words": [],
+                    "participants": [],
+                    "editor": "",
+                    "summaries": ["- first", "- second"],
+                })
+                .as_object()
+                .unwrap()
+                .clone(),
+            )
+            .unwrap();
+
+        insta::assert_snapshot!(rendered, @r###"
+        <participants>
+
+        </participants>
+
+        <raw_note>
+
+        </raw_note>
+
+        The transcript was too long, so below are summaries of its consecutive parts, in order.
+
+        <transcript_summaries>
+        <part>
+        - first
+        </part>
+        <part>
+        - second
+        </part>
+        </transcript_summaries>
+
+        Your job is to write a perfect note based on the above informations.
+        Note that above given informations like participants, transcript, etc. are already displayed in the UI, so you don't need to repeat them.
+        "###);
+    }
 }